@@ -87,6 +87,7 @@ fn bench_json_path(c: &mut Criterion) {
         path: file_path.to_string_lossy().to_string(),
         json_path: ".nested.value".to_string(),
         expected: serde_json::json!(42),
+        op: Default::default(),
     });
 
     c.bench_function("json_path", |b| {
@@ -114,6 +115,12 @@ fn bench_command_succeeds(c: &mut Criterion) {
     let claim = Claim::new("Command succeeds").with_evidence(EvidenceSpec::CommandSucceeds {
         command: "true".to_string(),
         args: vec![],
+        cwd: None,
+        env: Default::default(),
+        timeout_secs: None,
+        expected_exit_code: 0,
+        stdout_contains: None,
+        stdout_matches: None,
     });
 
     c.bench_function("command_succeeds", |b| {
@@ -167,7 +174,7 @@ fn bench_custom_checker(c: &mut Criterion) {
 
     let claim = Claim::new("Custom check").with_evidence(EvidenceSpec::Custom {
         name: "always_confirm".to_string(),
-        params: std::collections::HashMap::new(),
+        params: serde_json::json!({}),
     });
 
     c.bench_function("custom_checker", |b| {
@@ -10,6 +10,7 @@
 //! did-you-actually-do-that = { version = "0.1", features = ["async"] }
 //! ```
 
+use crate::query::{self, JsonOp};
 use crate::Verdict;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
@@ -45,9 +46,12 @@ pub enum NetworkEvidenceSpec {
     /// HTTP response should match a JSON path query
     HttpJsonPath {
         url: String,
-        /// JSONPath expression (simplified: supports `.field` and `[index]`)
+        /// JSONPath expression, as for `FileJsonPath` (see [`crate::query`])
         path: String,
+        #[serde(default)]
         expected_value: serde_json::Value,
+        #[serde(default)]
+        op: JsonOp,
         #[serde(default = "default_timeout")]
         timeout_secs: u64,
     },
@@ -135,9 +139,10 @@ impl AsyncVerifier {
                 url,
                 path,
                 expected_value,
+                op,
                 timeout_secs,
             } => {
-                self.check_http_json_path(url, path, expected_value, *timeout_secs)
+                self.check_http_json_path(url, path, *op, expected_value, *timeout_secs)
                     .await
             }
 
@@ -285,6 +290,7 @@ impl AsyncVerifier {
         &self,
         url: &str,
         path: &str,
+        op: JsonOp,
         expected_value: &serde_json::Value,
         timeout_secs: u64,
     ) -> (Verdict, Option<String>) {
@@ -299,32 +305,7 @@ impl AsyncVerifier {
 
         match client.get(url).send().await {
             Ok(resp) => match resp.json::<serde_json::Value>().await {
-                Ok(json) => {
-                    // Simple JSONPath implementation (supports .field and [index])
-                    let actual = extract_json_path(&json, path);
-                    match actual {
-                        Some(value) => {
-                            if value == expected_value {
-                                (
-                                    Verdict::Confirmed,
-                                    Some(format!("JSON path {} matches", path)),
-                                )
-                            } else {
-                                (
-                                    Verdict::Refuted,
-                                    Some(format!(
-                                        "JSON path {} = {:?} (expected {:?})",
-                                        path, value, expected_value
-                                    )),
-                                )
-                            }
-                        }
-                        None => (
-                            Verdict::Refuted,
-                            Some(format!("JSON path {} not found", path)),
-                        ),
-                    }
-                }
+                Ok(json) => query::check_document(&json, path, op, expected_value),
                 Err(e) => (
                     Verdict::Refuted,
                     Some(format!("Failed to parse JSON: {}", e)),
@@ -375,35 +356,31 @@ impl AsyncVerifier {
     }
 }
 
-/// Simple JSON path extraction
-/// Supports paths like ".field", ".nested.field", "[0]", ".array[0].field"
-fn extract_json_path<'a>(json: &'a serde_json::Value, path: &str) -> Option<&'a serde_json::Value> {
-    let mut current = json;
-
-    for segment in path.split('.').filter(|s| !s.is_empty()) {
-        // Check for array index
-        if let Some(bracket_pos) = segment.find('[') {
-            let field_name = &segment[..bracket_pos];
-            if !field_name.is_empty() {
-                current = current.get(field_name)?;
-            }
-
-            // Extract index
-            let end_bracket = segment.find(']')?;
-            let index_str = &segment[bracket_pos + 1..end_bracket];
-            let index: usize = index_str.parse().ok()?;
-            current = current.get(index)?;
-        } else {
-            current = current.get(segment)?;
-        }
-    }
-
-    Some(current)
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::query::JsonQuery;
+
+    /// First value the shared query engine selects, as HttpJsonPath sees it
+    fn extract_json_path<'a>(
+        json: &'a serde_json::Value,
+        path: &str,
+    ) -> Option<&'a serde_json::Value> {
+        JsonQuery::parse(path).ok()?.select(json).into_iter().next()
+    }
+
+    #[test]
+    fn test_http_json_path_defaults_to_equality() {
+        let spec: NetworkEvidenceSpec = serde_json::from_value(serde_json::json!({
+            "type": "HttpJsonPath",
+            "spec": {"url": "http://localhost/", "path": "$.ok", "expected_value": true}
+        }))
+        .unwrap();
+        match spec {
+            NetworkEvidenceSpec::HttpJsonPath { op, .. } => assert_eq!(op, JsonOp::Equals),
+            _ => panic!("Expected HttpJsonPath"),
+        }
+    }
 
     #[test]
     fn test_extract_json_path_simple() {
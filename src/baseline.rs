@@ -0,0 +1,186 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Comparing reports against a saved baseline
+//!
+//! A baseline is an earlier run's reports (the output of
+//! `dyadt report --json`). Claims are matched by id, which is stable across
+//! runs for claims that do not set one (see [`Claim::content_id`](crate::Claim::content_id)),
+//! and each current claim is classified:
+//!
+//! - **regressed**: Confirmed in the baseline, Refuted now
+//! - **fixed**: not Confirmed in the baseline, Confirmed now
+//! - **new**: not in the baseline
+//! - **unchanged**: anything else, including moves between Inconclusive and
+//!   Unverifiable, so long-standing noise never counts as a regression
+//!
+//! ```rust
+//! use did_you_actually_do_that::baseline::{Baseline, Change};
+//! use did_you_actually_do_that::{Claim, EvidenceSpec, Verifier};
+//!
+//! let claim = Claim::new("Root exists").with_evidence(EvidenceSpec::DirectoryExists {
+//!     path: "/".to_string(),
+//! });
+//! let before = Verifier::new().verify_batch(&[claim.clone()]).unwrap();
+//! let after = Verifier::new().verify_batch(&[claim]).unwrap();
+//!
+//! let changes = Baseline::from_reports(&before).compare(&after);
+//! assert_eq!(changes[0].change, Change::Unchanged);
+//! ```
+
+use crate::{Verdict, VerificationReport};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+
+/// How a claim's verdict moved relative to the baseline
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Change {
+    New,
+    Fixed,
+    Regressed,
+    Unchanged,
+}
+
+impl fmt::Display for Change {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Change::New => "new",
+            Change::Fixed => "fixed",
+            Change::Regressed => "regressed",
+            Change::Unchanged => "unchanged",
+        })
+    }
+}
+
+/// One current claim compared with the baseline
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Comparison {
+    pub claim_id: String,
+    pub description: String,
+    pub change: Change,
+    /// The baseline verdict, if the claim was in the baseline
+    pub before: Option<Verdict>,
+    pub after: Verdict,
+}
+
+/// Verdicts from an earlier run, by claim id
+#[derive(Debug, Clone, Default)]
+pub struct Baseline {
+    verdicts: HashMap<String, Verdict>,
+}
+
+impl Baseline {
+    pub fn from_reports(reports: &[VerificationReport]) -> Self {
+        Self {
+            verdicts: reports
+                .iter()
+                .map(|report| (report.claim.id.clone(), report.overall_verdict))
+                .collect(),
+        }
+    }
+
+    /// Read a baseline from `dyadt report --json` output or a JSON array of
+    /// reports. Only each report's claim id and verdict are read, so
+    /// baselines from older versions still load.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, String> {
+        let path = path.as_ref();
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("Cannot read baseline {}: {}", path.display(), e))?;
+        let document: Value = serde_json::from_str(&text)
+            .map_err(|e| format!("Invalid baseline {}: {}", path.display(), e))?;
+        Self::from_json(&document)
+            .map_err(|e| format!("Invalid baseline {}: {}", path.display(), e))
+    }
+
+    /// Parse a baseline from `dyadt report --json` output or an array of reports
+    pub fn from_json(document: &Value) -> Result<Self, String> {
+        let reports = document
+            .get("reports")
+            .unwrap_or(document)
+            .as_array()
+            .ok_or("expected report JSON with a \"reports\" array")?;
+
+        let mut verdicts = HashMap::new();
+        for (n, report) in reports.iter().enumerate() {
+            let id = report["claim"]["id"]
+                .as_str()
+                .ok_or_else(|| format!("report {} has no claim id", n + 1))?;
+            let verdict = Verdict::deserialize(&report["overall_verdict"])
+                .map_err(|e| format!("report {}: {}", n + 1, e))?;
+            verdicts.insert(id.to_string(), verdict);
+        }
+        Ok(Self { verdicts })
+    }
+
+    /// The baseline verdict for a claim id
+    pub fn verdict(&self, claim_id: &str) -> Option<Verdict> {
+        self.verdicts.get(claim_id).copied()
+    }
+
+    /// Classify every current report against the baseline, in order
+    pub fn compare(&self, reports: &[VerificationReport]) -> Vec<Comparison> {
+        reports
+            .iter()
+            .map(|report| {
+                let before = self.verdict(&report.claim.id);
+                let after = report.overall_verdict;
+                Comparison {
+                    claim_id: report.claim.id.clone(),
+                    description: report.claim.description.clone(),
+                    change: classify(before, after),
+                    before,
+                    after,
+                }
+            })
+            .collect()
+    }
+}
+
+fn classify(before: Option<Verdict>, after: Verdict) -> Change {
+    match (before, after) {
+        (None, _) => Change::New,
+        (Some(Verdict::Confirmed), Verdict::Refuted) => Change::Regressed,
+        (Some(before), Verdict::Confirmed) if before != Verdict::Confirmed => Change::Fixed,
+        _ => Change::Unchanged,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_classify() {
+        use Verdict::*;
+        assert_eq!(classify(None, Refuted), Change::New);
+        assert_eq!(classify(Some(Confirmed), Refuted), Change::Regressed);
+        assert_eq!(classify(Some(Refuted), Confirmed), Change::Fixed);
+        assert_eq!(classify(Some(Unverifiable), Confirmed), Change::Fixed);
+        assert_eq!(classify(Some(Refuted), Refuted), Change::Unchanged);
+        // Noise is never a regression
+        assert_eq!(classify(Some(Confirmed), Unverifiable), Change::Unchanged);
+        assert_eq!(classify(Some(Unverifiable), Refuted), Change::Unchanged);
+    }
+
+    #[test]
+    fn test_from_json_reads_ids_and_verdicts() {
+        let report = |id: &str, verdict: &str| json!({ "claim": { "id": id, "description": "d" }, "overall_verdict": verdict });
+        let wrapped = json!({ "reports": [report("a", "Confirmed"), report("b", "Refuted")] });
+        let baseline = Baseline::from_json(&wrapped).unwrap();
+        assert_eq!(baseline.verdict("a"), Some(Verdict::Confirmed));
+        assert_eq!(baseline.verdict("b"), Some(Verdict::Refuted));
+        assert_eq!(baseline.verdict("c"), None);
+
+        let bare = json!([report("a", "Unverifiable")]);
+        assert_eq!(
+            Baseline::from_json(&bare).unwrap().verdict("a"),
+            Some(Verdict::Unverifiable)
+        );
+
+        assert!(Baseline::from_json(&json!({ "claims": [] })).is_err());
+        assert!(Baseline::from_json(&json!([report("a", "Maybe")])).is_err());
+    }
+}
@@ -0,0 +1,381 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Pluggable evidence checkers
+//!
+//! Every kind of evidence is checked by something implementing [`EvidenceChecker`].
+//! The built-in evidence variants are exposed through [`BuiltinChecker`], and
+//! user plugins are registered on a [`Verifier`] and referenced from claims
+//! with `Custom` evidence.
+//!
+//! ## Writing a checker
+//!
+//! ```rust
+//! use did_you_actually_do_that::checker::{CheckOutcome, EvidenceChecker};
+//! use did_you_actually_do_that::{
+//!     Claim, EvidenceSpec, Verdict, VerificationError, Verifier,
+//! };
+//! use serde_json::{json, Value};
+//!
+//! struct LineCount;
+//!
+//! impl EvidenceChecker for LineCount {
+//!     fn name(&self) -> &str {
+//!         "line_count"
+//!     }
+//!
+//!     fn params_schema(&self) -> Value {
+//!         json!({
+//!             "type": "object",
+//!             "properties": {
+//!                 "text": { "type": "string" },
+//!                 "lines": { "type": "integer" }
+//!             },
+//!             "required": ["text", "lines"]
+//!         })
+//!     }
+//!
+//!     fn check(&self, params: &Value, _verifier: &Verifier) -> Result<CheckOutcome, VerificationError> {
+//!         let text = params["text"].as_str().ok_or_else(|| {
+//!             VerificationError::InvalidClaim("Missing text parameter".into())
+//!         })?;
+//!         let expected = params["lines"].as_u64().unwrap_or(0) as usize;
+//!         let actual = text.lines().count();
+//!
+//!         let verdict = if actual == expected { Verdict::Confirmed } else { Verdict::Refuted };
+//!         Ok(CheckOutcome::new(verdict)
+//!             .with_details(format!("{} lines (expected {})", actual, expected))
+//!             .with_artifact("lines", json!(actual)))
+//!     }
+//! }
+//!
+//! let mut verifier = Verifier::new();
+//! verifier.register(LineCount);
+//!
+//! let claim = Claim::new("Wrote two lines").with_evidence(EvidenceSpec::Custom {
+//!     name: "line_count".to_string(),
+//!     params: json!({ "text": "a\nb", "lines": 2 }),
+//! });
+//!
+//! assert_eq!(verifier.verify(&claim).overall_verdict, Verdict::Confirmed);
+//! ```
+
+use crate::{schema, EvidenceSpec, Verdict, VerificationError, Verifier};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, HashMap};
+
+/// Outcome of running an evidence checker
+#[derive(Debug, Clone)]
+pub struct CheckOutcome {
+    pub verdict: Verdict,
+    pub details: Option<String>,
+    /// Named supporting data (captured output, diffs, measurements, ...)
+    pub artifacts: BTreeMap<String, Value>,
+}
+
+impl CheckOutcome {
+    pub fn new(verdict: Verdict) -> Self {
+        Self {
+            verdict,
+            details: None,
+            artifacts: BTreeMap::new(),
+        }
+    }
+
+    pub fn with_details(mut self, details: impl Into<String>) -> Self {
+        self.details = Some(details.into());
+        self
+    }
+
+    pub fn with_artifact(mut self, name: impl Into<String>, value: Value) -> Self {
+        self.artifacts.insert(name.into(), value);
+        self
+    }
+}
+
+/// A checker that can verify one kind of evidence
+///
+/// Checkers receive their parameters as JSON and may use the calling
+/// [`Verifier`] to check nested evidence. They must be `Send + Sync` because
+/// a verifier may run them from several worker threads at once.
+pub trait EvidenceChecker: Send + Sync {
+    /// Name used to reference this checker from evidence specs
+    fn name(&self) -> &str;
+
+    /// Short human-readable description of what the checker verifies
+    fn description(&self) -> &str {
+        ""
+    }
+
+    /// JSON Schema describing the accepted parameters, advertised to
+    /// clients (e.g. over MCP). It is not enforced: `check` receives
+    /// whatever the evidence holds and must reject bad params itself.
+    fn params_schema(&self) -> Value {
+        json!({ "type": "object" })
+    }
+
+    /// Check the evidence described by `params`
+    fn check(&self, params: &Value, verifier: &Verifier)
+        -> Result<CheckOutcome, VerificationError>;
+}
+
+/// Adapter for closure checkers registered with [`Verifier::register_checker`]
+pub(crate) struct FnChecker<F> {
+    pub(crate) name: String,
+    pub(crate) func: F,
+}
+
+impl<F> EvidenceChecker for FnChecker<F>
+where
+    F: Fn(&HashMap<String, String>) -> Result<Verdict, VerificationError> + Send + Sync,
+{
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn check(
+        &self,
+        params: &Value,
+        _verifier: &Verifier,
+    ) -> Result<CheckOutcome, VerificationError> {
+        (self.func)(&string_params(params)).map(CheckOutcome::new)
+    }
+}
+
+/// Flatten JSON params into the string map expected by closure checkers
+fn string_params(params: &Value) -> HashMap<String, String> {
+    params
+        .as_object()
+        .map(|obj| {
+            obj.iter()
+                .map(|(key, value)| {
+                    let value = match value {
+                        Value::String(s) => s.clone(),
+                        other => other.to_string(),
+                    };
+                    (key.clone(), value)
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// A built-in evidence variant exposed as a checker
+///
+/// Parameters are the variant's `spec` object, so `{"path": "x"}` checked by
+/// the `FileExists` builtin behaves exactly like the typed `FileExists` evidence.
+#[derive(Debug, Clone, Copy)]
+pub struct BuiltinChecker {
+    name: &'static str,
+    description: &'static str,
+}
+
+impl BuiltinChecker {
+    /// All built-in checkers, in declaration order of [`EvidenceSpec`]
+    pub fn all() -> &'static [BuiltinChecker] {
+        BUILTIN_CHECKERS
+    }
+
+    /// Look up a built-in checker by evidence type name
+    pub fn get(name: &str) -> Option<&'static BuiltinChecker> {
+        BUILTIN_CHECKERS.iter().find(|c| c.name == name)
+    }
+}
+
+const BUILTIN_CHECKERS: &[BuiltinChecker] = &[
+    BuiltinChecker {
+        name: "FileExists",
+        description: "A file should exist at the given path",
+    },
+    BuiltinChecker {
+        name: "FileWithHash",
+        description: "A file should exist with a specific SHA-256 hash",
+    },
+    BuiltinChecker {
+        name: "FileContains",
+        description: "A file should contain the given substring",
+    },
+    BuiltinChecker {
+        name: "FileMatchesRegex",
+        description: "A file should match a regular expression pattern",
+    },
+    BuiltinChecker {
+        name: "FileLineRange",
+        description: "Lines start..=end of a file should be exactly the expected text",
+    },
+    BuiltinChecker {
+        name: "FileUnifiedDiffApplied",
+        description: "Every hunk of a unified diff should be present in a file",
+    },
+    BuiltinChecker {
+        name: "FileJsonPath",
+        description: "A value at a JSON path should satisfy a comparison (equality by default)",
+    },
+    BuiltinChecker {
+        name: "FileStructuredPath",
+        description: "A value in a TOML, YAML, INI or dotenv file should satisfy a comparison",
+    },
+    BuiltinChecker {
+        name: "SymbolDefined",
+        description: "A source file should define a named fn, type, method or variant",
+    },
+    BuiltinChecker {
+        name: "DirectoryExists",
+        description: "A directory should exist",
+    },
+    BuiltinChecker {
+        name: "CommandSucceeds",
+        description: "A command should exit with the expected code (0 by default)",
+    },
+    BuiltinChecker {
+        name: "TestPasses",
+        description: "A named test should run and pass under cargo, pytest or go",
+    },
+    BuiltinChecker {
+        name: "GitClean",
+        description: "Git working directory should have no uncommitted changes",
+    },
+    BuiltinChecker {
+        name: "GitCommitExists",
+        description: "A git commit should exist",
+    },
+    BuiltinChecker {
+        name: "GitBranchExists",
+        description: "A git branch should exist",
+    },
+    BuiltinChecker {
+        name: "FileModifiedAfter",
+        description: "A file should have been modified after a timestamp",
+    },
+    BuiltinChecker {
+        name: "EnvVar",
+        description: "An environment variable should have the expected value",
+    },
+    BuiltinChecker {
+        name: "FileChangedInCommit",
+        description: "A commit or range should change a path",
+    },
+    BuiltinChecker {
+        name: "CommitTouchesPaths",
+        description: "A commit or range should change every listed path",
+    },
+    BuiltinChecker {
+        name: "CommitDiffMatches",
+        description: "A commit's diff should add or remove a line matching a regex",
+    },
+    BuiltinChecker {
+        name: "CommitMessageMatches",
+        description: "A commit message should match a regex",
+    },
+    BuiltinChecker {
+        name: "CommitIsAncestor",
+        description: "HEAD should descend from a commit",
+    },
+    BuiltinChecker {
+        name: "FileAbsent",
+        description: "Nothing should exist at the given path",
+    },
+    BuiltinChecker {
+        name: "DirectoryAbsent",
+        description: "No directory should exist at the given path",
+    },
+    BuiltinChecker {
+        name: "GitBranchAbsent",
+        description: "A git branch should not exist",
+    },
+    BuiltinChecker {
+        name: "EnvVarAbsent",
+        description: "An environment variable should not be set",
+    },
+    BuiltinChecker {
+        name: "AllOf",
+        description: "Every nested piece of evidence should hold",
+    },
+    BuiltinChecker {
+        name: "AnyOf",
+        description: "At least one nested piece of evidence should hold",
+    },
+    BuiltinChecker {
+        name: "Not",
+        description: "The nested evidence should not hold",
+    },
+    BuiltinChecker {
+        name: "AtLeast",
+        description: "At least n nested pieces of evidence should hold",
+    },
+    BuiltinChecker {
+        name: "Weighted",
+        description: "Nested evidence with a weight and required flag for claim aggregation",
+    },
+];
+
+impl EvidenceChecker for BuiltinChecker {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn description(&self) -> &str {
+        self.description
+    }
+
+    /// The variant's `spec` schema, generated from [`EvidenceSpec`]
+    fn params_schema(&self) -> Value {
+        schema::evidence_spec_schema(self.name).unwrap_or_else(|| json!({ "type": "object" }))
+    }
+
+    fn check(
+        &self,
+        params: &Value,
+        verifier: &Verifier,
+    ) -> Result<CheckOutcome, VerificationError> {
+        let spec: EvidenceSpec =
+            serde_json::from_value(json!({ "type": self.name, "spec": params }))
+                .map_err(|e| VerificationError::InvalidClaim(e.to_string()))?;
+        let result = verifier.check_evidence(&spec);
+
+        Ok(CheckOutcome {
+            verdict: result.verdict,
+            details: result.details,
+            artifacts: result.artifacts,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builtin_checker_matches_typed_evidence() {
+        let verifier = Verifier::new();
+        let checker = BuiltinChecker::get("FileExists").unwrap();
+
+        let outcome = checker
+            .check(&json!({ "path": "/nonexistent/file.txt" }), &verifier)
+            .unwrap();
+        assert_eq!(outcome.verdict, Verdict::Refuted);
+
+        let err = checker.check(&json!({ "nope": 1 }), &verifier);
+        assert!(matches!(err, Err(VerificationError::InvalidClaim(_))));
+    }
+
+    #[test]
+    fn test_every_builtin_has_object_schema() {
+        for checker in BuiltinChecker::all() {
+            assert_eq!(
+                checker.params_schema()["type"],
+                "object",
+                "{}",
+                checker.name
+            );
+            assert!(!checker.description().is_empty());
+        }
+    }
+
+    #[test]
+    fn test_string_params_flattens_values() {
+        let params = string_params(&json!({ "a": "x", "b": 2, "c": true }));
+        assert_eq!(params["a"], "x");
+        assert_eq!(params["b"], "2");
+        assert_eq!(params["c"], "true");
+        assert!(string_params(&Value::Null).is_empty());
+    }
+}
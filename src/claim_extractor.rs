@@ -10,7 +10,8 @@
 //!
 //! - File operations: "I created/wrote/modified/deleted file X"
 //! - Command execution: "I ran/executed command X"
-//! - Git operations: "I committed/pushed/created branch X"
+//! - Git operations: "I committed/pushed/created branch X", "I deleted branch X"
+//! - Removals: "I deleted file X", "I removed directory X"
 //! - General assertions: "I did X" with evidence markers
 //!
 //! ## Usage
@@ -56,7 +57,9 @@ pub struct ClaimExtractor {
     git_commit_pattern: Regex,
     git_push_pattern: Regex,
     git_branch_pattern: Regex,
+    git_branch_deleted_pattern: Regex,
     directory_created_pattern: Regex,
+    directory_removed_pattern: Regex,
 }
 
 impl Default for ClaimExtractor {
@@ -98,10 +101,16 @@ impl ClaimExtractor {
             git_branch_pattern: Regex::new(
                 r#"(?i)(?:I\s+)?(?:created|switched\s+to)\s+(?:the\s+)?branch\s+[`"']?([^\s`"']+)[`"']?"#
             ).unwrap(),
+            git_branch_deleted_pattern: Regex::new(
+                r#"(?i)(?:I\s+)?(?:deleted|removed)\s+(?:the\s+)?(?:local\s+)?branch\s+[`"']?([^\s`"']+)[`"']?"#
+            ).unwrap(),
             // Directory operations - require "directory" or "folder" keyword
             directory_created_pattern: Regex::new(
                 r#"(?i)(?:I\s+)?(?:created|made)\s+(?:the\s+)?(?:directory|folder|dir)\s+[`"']?([^\s`"']+)[`"']?"#
             ).unwrap(),
+            directory_removed_pattern: Regex::new(
+                r#"(?i)(?:I\s+)?(?:deleted|removed)\s+(?:the\s+)?(?:directory|folder|dir)\s+[`"']?([^\s`"']+)[`"']?"#
+            ).unwrap(),
         }
     }
 
@@ -193,6 +202,38 @@ impl ClaimExtractor {
             }
         }
 
+        // Extract file deletion claims
+        for cap in self.file_deleted_pattern.captures_iter(text) {
+            if let Some(path) = cap.get(1) {
+                let path_str = self.resolve_path(path.as_str().trim(), context);
+                if seen_paths.insert(path_str.clone()) {
+                    let mut claim = Claim::new(format!("Deleted file: {}", path_str))
+                        .with_evidence(EvidenceSpec::FileAbsent { path: path_str });
+
+                    if !context.source.is_empty() {
+                        claim = claim.with_source(&context.source);
+                    }
+                    claims.push(claim);
+                }
+            }
+        }
+
+        // Extract directory removal claims
+        for cap in self.directory_removed_pattern.captures_iter(text) {
+            if let Some(path) = cap.get(1) {
+                let path_str = self.resolve_path(path.as_str().trim(), context);
+                if seen_paths.insert(path_str.clone()) {
+                    let mut claim = Claim::new(format!("Removed directory: {}", path_str))
+                        .with_evidence(EvidenceSpec::DirectoryAbsent { path: path_str });
+
+                    if !context.source.is_empty() {
+                        claim = claim.with_source(&context.source);
+                    }
+                    claims.push(claim);
+                }
+            }
+        }
+
         // Extract git commit claims
         for cap in self.git_commit_pattern.captures_iter(text) {
             if let Some(hash) = cap.get(1) {
@@ -229,6 +270,24 @@ impl ClaimExtractor {
             }
         }
 
+        // Extract git branch deletion claims
+        for cap in self.git_branch_deleted_pattern.captures_iter(text) {
+            if let Some(branch) = cap.get(1) {
+                let branch_name = branch.as_str().trim();
+                let repo_path = context.working_directory.clone();
+                let mut claim = Claim::new(format!("Deleted branch: {}", branch_name))
+                    .with_evidence(EvidenceSpec::GitBranchAbsent {
+                        branch: branch_name.to_string(),
+                        repo_path,
+                    });
+
+                if !context.source.is_empty() {
+                    claim = claim.with_source(&context.source);
+                }
+                claims.push(claim);
+            }
+        }
+
         claims
     }
 
@@ -286,6 +345,7 @@ impl ClaimExtractor {
                                 claims.push(claim);
                             }
                         }
+                        claims.extend(self.extract_removals(cmd_str, context));
                     }
                 }
                 _ => {}
@@ -295,6 +355,67 @@ impl ClaimExtractor {
         claims
     }
 
+    /// Extract absence claims from `rm`, `rmdir` and `git branch -d` in a
+    /// shell command line. Arguments with globs or expansions are skipped,
+    /// since the paths they name cannot be known here, and so are commands
+    /// whose quoting cannot be parsed.
+    fn extract_removals(&self, command: &str, context: &ExtractionContext) -> Vec<Claim> {
+        let mut claims = Vec::new();
+
+        for segment in split_commands(command) {
+            let Some(words) = shlex::split(segment) else {
+                continue;
+            };
+            let words: Vec<&str> = words.iter().map(String::as_str).collect();
+            let (claim_kind, operands): (&str, &[&str]) = match words.as_slice() {
+                ["rm", rest @ ..] => ("file", rest),
+                ["rmdir", rest @ ..] => ("directory", rest),
+                ["git", "branch", flag, rest @ ..] if matches!(*flag, "-d" | "-D" | "--delete") => {
+                    ("branch", rest)
+                }
+                _ => continue,
+            };
+
+            for operand in operands.iter().filter(|w| !w.starts_with('-')) {
+                if operand.contains(['*', '?', '[', '$', '`', '>', '<']) {
+                    continue;
+                }
+                let (description, evidence) = match claim_kind {
+                    "branch" => (
+                        format!("Deleted branch via git: {}", operand),
+                        EvidenceSpec::GitBranchAbsent {
+                            branch: operand.to_string(),
+                            repo_path: context.working_directory.clone(),
+                        },
+                    ),
+                    "directory" => {
+                        let path = self.resolve_path(operand, context);
+                        (
+                            format!("Removed directory via rmdir: {}", path),
+                            EvidenceSpec::DirectoryAbsent { path },
+                        )
+                    }
+                    // `rm -r` may remove a directory too; FileAbsent covers both
+                    _ => {
+                        let path = self.resolve_path(operand, context);
+                        (
+                            format!("Removed via rm: {}", path),
+                            EvidenceSpec::FileAbsent { path },
+                        )
+                    }
+                };
+
+                let mut claim = Claim::new(description).with_evidence(evidence);
+                if !context.source.is_empty() {
+                    claim = claim.with_source(&context.source);
+                }
+                claims.push(claim);
+            }
+        }
+
+        claims
+    }
+
     /// Resolve a path, handling relative paths if working_directory is set
     fn resolve_path(&self, path: &str, context: &ExtractionContext) -> String {
         let path = path.trim_matches(|c| c == '`' || c == '"' || c == '\'');
@@ -336,6 +457,33 @@ impl ToolCall {
     }
 }
 
+/// Split a shell command line into simple commands at `&`, `;`, `|` and
+/// newlines outside quotes
+fn split_commands(command: &str) -> Vec<&str> {
+    let mut commands = Vec::new();
+    let mut start = 0;
+    let mut quote = None;
+    let mut escaped = false;
+    for (i, c) in command.char_indices() {
+        match (quote, c) {
+            _ if escaped => escaped = false,
+            (Some('\''), '\'') => quote = None,
+            (Some('\''), _) => {}
+            (_, '\\') => escaped = true,
+            (Some('"'), '"') => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"') => quote = Some(c),
+            (None, '&' | ';' | '|' | '\n') => {
+                commands.push(&command[start..i]);
+                start = i + 1;
+            }
+            (None, _) => {}
+        }
+    }
+    commands.push(&command[start..]);
+    commands
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -426,6 +574,87 @@ mod tests {
         assert!(!claims.is_empty());
     }
 
+    #[test]
+    fn test_extract_deletions_from_text() {
+        let extractor = ClaimExtractor::new();
+        let context = ExtractionContext {
+            working_directory: Some("/repo".to_string()),
+            ..Default::default()
+        };
+
+        let text = "I deleted file old.rs, removed directory build and deleted branch wip";
+        let claims = extractor.extract_from_text(text, &context);
+        let evidence: Vec<_> = claims.iter().flat_map(|c| c.evidence.clone()).collect();
+
+        assert_eq!(
+            serde_json::to_value(&evidence).unwrap(),
+            serde_json::json!([
+                {"type": "FileAbsent", "spec": {"path": "/repo/old.rs"}},
+                {"type": "DirectoryAbsent", "spec": {"path": "/repo/build"}},
+                {"type": "GitBranchAbsent", "spec": {"branch": "wip", "repo_path": "/repo"}},
+            ])
+        );
+    }
+
+    #[test]
+    fn test_extract_removals_from_bash() {
+        let extractor = ClaimExtractor::new();
+        let context = ExtractionContext {
+            working_directory: Some("/repo".to_string()),
+            ..Default::default()
+        };
+
+        let tool_call = ToolCall::new("Bash").with_arg(
+            "command",
+            serde_json::json!("rm -f a.txt /tmp/b.txt *.log && rmdir out; git branch -D topic"),
+        );
+        let claims = extractor.extract_from_tool_calls(&[tool_call], &context);
+        let evidence: Vec<_> = claims.iter().flat_map(|c| c.evidence.clone()).collect();
+
+        assert_eq!(
+            serde_json::to_value(&evidence).unwrap(),
+            serde_json::json!([
+                {"type": "FileAbsent", "spec": {"path": "/repo/a.txt"}},
+                {"type": "FileAbsent", "spec": {"path": "/tmp/b.txt"}},
+                {"type": "DirectoryAbsent", "spec": {"path": "/repo/out"}},
+                {"type": "GitBranchAbsent", "spec": {"branch": "topic", "repo_path": "/repo"}},
+            ])
+        );
+    }
+
+    #[test]
+    fn test_extract_removals_respects_quoting() {
+        let extractor = ClaimExtractor::new();
+        let context = ExtractionContext {
+            working_directory: Some("/repo".to_string()),
+            ..Default::default()
+        };
+        let paths = |command: &str| -> Vec<String> {
+            let tool_call = ToolCall::new("Bash").with_arg("command", serde_json::json!(command));
+            extractor
+                .extract_from_tool_calls(&[tool_call], &context)
+                .iter()
+                .flat_map(|c| c.evidence.clone())
+                .map(|e| match e {
+                    EvidenceSpec::FileAbsent { path } => path,
+                    other => panic!("unexpected evidence {:?}", other),
+                })
+                .collect()
+        };
+
+        assert_eq!(
+            paths(r#"rm "my file.txt" 'a b'"#),
+            ["/repo/my file.txt", "/repo/a b"]
+        );
+        assert_eq!(paths(r"rm my\ notes.txt"), ["/repo/my notes.txt"]);
+        // `rm` inside a quoted string is not a command
+        assert!(paths(r#"echo "done; rm -rf later""#).is_empty());
+        assert!(paths("echo 'x | rm y' && ls").is_empty());
+        assert_eq!(paths(r#"echo "a;b" ; rm c.txt"#), ["/repo/c.txt"]);
+        // Unbalanced quotes cannot be parsed, so nothing is claimed
+        assert!(paths(r#"rm "unterminated"#).is_empty());
+    }
+
     #[test]
     fn test_git_commit_extraction() {
         let extractor = ClaimExtractor::new();
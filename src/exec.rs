@@ -0,0 +1,142 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Running external commands for evidence checks
+//!
+//! Commands are spawned with stdin closed and both output streams captured.
+//! When a timeout is given the process is killed once it expires, so a hung
+//! check can never stall a whole verification run.
+
+use std::io::{self, Read};
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Maximum number of bytes kept from the end of each output stream
+pub(crate) const OUTPUT_TAIL_BYTES: usize = 4096;
+
+/// How often a running process is polled for exit
+const POLL_INTERVAL: Duration = Duration::from_millis(10);
+
+/// How long to wait for output readers after the process has exited.
+/// Grandchildren that inherited the pipes can keep them open indefinitely.
+const READER_GRACE: Duration = Duration::from_millis(500);
+
+/// Captured result of running a command
+#[derive(Debug)]
+pub(crate) struct CommandRun {
+    /// Exit code, or `None` if the process was killed or terminated by a signal
+    pub code: Option<i32>,
+    /// Whether the process was killed because the timeout expired
+    pub timed_out: bool,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+/// Spawn `command` and wait for it, killing it after `timeout` if given
+pub(crate) fn run(command: &mut Command, timeout: Option<Duration>) -> io::Result<CommandRun> {
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    let stdout = child.stdout.take().map(spawn_reader);
+    let stderr = child.stderr.take().map(spawn_reader);
+
+    let started = Instant::now();
+    let mut timed_out = false;
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if timeout.is_some_and(|limit| started.elapsed() >= limit) {
+            // The process may exit between try_wait and kill; either way
+            // the following wait reaps it.
+            let _ = child.kill();
+            timed_out = true;
+            break child.wait()?;
+        }
+        thread::sleep(POLL_INTERVAL);
+    };
+
+    Ok(CommandRun {
+        code: if timed_out { None } else { status.code() },
+        timed_out,
+        stdout: collect(stdout),
+        stderr: collect(stderr),
+    })
+}
+
+fn spawn_reader<R: Read + Send + 'static>(mut stream: R) -> mpsc::Receiver<Vec<u8>> {
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        let mut buf = Vec::new();
+        let _ = stream.read_to_end(&mut buf);
+        let _ = tx.send(buf);
+    });
+    rx
+}
+
+fn collect(reader: Option<mpsc::Receiver<Vec<u8>>>) -> String {
+    reader
+        .and_then(|rx| rx.recv_timeout(READER_GRACE).ok())
+        .map(|buf| String::from_utf8_lossy(&buf).into_owned())
+        .unwrap_or_default()
+}
+
+/// Keep the last `max` bytes of `text`, marking how much was dropped
+pub(crate) fn tail(text: &str, max: usize) -> String {
+    if text.len() <= max {
+        return text.to_string();
+    }
+    let mut start = text.len() - max;
+    while !text.is_char_boundary(start) {
+        start += 1;
+    }
+    format!("[... {} bytes truncated]\n{}", start, &text[start..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tail_keeps_short_text() {
+        assert_eq!(tail("hello", 10), "hello");
+    }
+
+    #[test]
+    fn test_tail_truncates_on_char_boundary() {
+        // 30 bytes; cutting at byte 26 would split an 'é', so the cut moves to 27
+        let text = "aé".repeat(10);
+        assert_eq!(tail(&text, 4), "[... 27 bytes truncated]\naé");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_captures_output() {
+        let run = run(
+            Command::new("sh").args(["-c", "echo out; echo err >&2; exit 3"]),
+            None,
+        )
+        .unwrap();
+        assert_eq!(run.code, Some(3));
+        assert!(!run.timed_out);
+        assert_eq!(run.stdout, "out\n");
+        assert_eq!(run.stderr, "err\n");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_kills_on_timeout() {
+        let started = Instant::now();
+        let run = run(
+            Command::new("sleep").arg("5"),
+            Some(Duration::from_millis(100)),
+        )
+        .unwrap();
+        assert!(run.timed_out);
+        assert_eq!(run.code, None);
+        assert!(started.elapsed() < Duration::from_secs(4));
+    }
+}
@@ -0,0 +1,526 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Git access for git evidence
+//!
+//! Repository queries behind `GitClean`, `GitCommitExists`, `GitBranchExists`
+//! and `GitBranchAbsent` go through a [`GitBackend`]. The default backend runs
+//! the `git` binary; with the `libgit2` feature the queries run in-process,
+//! which is faster for large batches and works where git is not installed.
+//! Backends return structured results and the verifier formats them, so
+//! verdicts and details do not depend on the backend in use.
+//!
+//! The diff-aware evidence always uses the `git` binary. A `commit` there is
+//! either a single revision, inspected with `git show`, or a range such as
+//! `main..HEAD`, inspected with `git log` so that every commit in the range
+//! counts (a change that was later reverted still touched the file). Merge
+//! commits contribute no diff.
+
+use crate::policy::ExecutionPolicy;
+use crate::DiffSide;
+use std::process::{Output, Stdio};
+
+/// Why a backend could not answer a query
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum GitError {
+    /// Git could not be run at all (missing binary, blocked by policy)
+    Unavailable(String),
+    /// Git ran but failed, e.g. the path is not a repository
+    Failed,
+}
+
+/// One line of `git status --porcelain`: a two-letter code and a path
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct StatusEntry {
+    pub code: String,
+    pub path: String,
+}
+
+/// Read-only repository queries used by git evidence
+pub(crate) trait GitBackend {
+    /// Uncommitted changes and untracked files, sorted by path. Renames are
+    /// reported as a deletion plus an addition.
+    fn status(&self, repo: &str) -> Result<Vec<StatusEntry>, GitError>;
+
+    /// Object type (`commit`, `tree`, `blob`, `tag`) that `rev` resolves to,
+    /// or `None` if it does not resolve
+    fn object_type(&self, repo: &str, rev: &str) -> Result<Option<String>, GitError>;
+
+    /// Whether `refs/heads/<branch>` exists
+    fn branch_exists(&self, repo: &str, branch: &str) -> Result<bool, GitError>;
+}
+
+/// Run the `git` binary, subject to the execution policy
+pub(crate) fn run_cli(policy: &ExecutionPolicy, args: &[&str]) -> Result<Output, String> {
+    let mut cmd = policy.command("git")?;
+    cmd.args(args)
+        .stdin(Stdio::null())
+        .output()
+        .map_err(|e| format!("Git not available: {}", e))
+}
+
+/// Backend that shells out to the `git` binary
+pub(crate) struct CliBackend<'a> {
+    policy: &'a ExecutionPolicy,
+}
+
+impl<'a> CliBackend<'a> {
+    pub fn new(policy: &'a ExecutionPolicy) -> Self {
+        Self { policy }
+    }
+
+    fn run(&self, repo: &str, args: &[&str]) -> Result<Output, GitError> {
+        let mut full = vec!["-C", repo];
+        full.extend_from_slice(args);
+        run_cli(self.policy, &full).map_err(GitError::Unavailable)
+    }
+}
+
+impl GitBackend for CliBackend<'_> {
+    fn status(&self, repo: &str) -> Result<Vec<StatusEntry>, GitError> {
+        let output = self.run(repo, &["status", "--porcelain", "-z", "--no-renames"])?;
+        if !output.status.success() {
+            return Err(GitError::Failed);
+        }
+        let mut entries: Vec<StatusEntry> = String::from_utf8_lossy(&output.stdout)
+            .split('\0')
+            .filter(|entry| entry.len() > 3)
+            .map(|entry| StatusEntry {
+                code: entry[..2].to_string(),
+                path: entry[3..].to_string(),
+            })
+            .collect();
+        // git lists untracked files last
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(entries)
+    }
+
+    fn object_type(&self, repo: &str, rev: &str) -> Result<Option<String>, GitError> {
+        let output = self.run(repo, &["cat-file", "-t", rev])?;
+        if output.status.success() {
+            let kind = String::from_utf8_lossy(&output.stdout);
+            Ok(Some(kind.trim().to_string()))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn branch_exists(&self, repo: &str, branch: &str) -> Result<bool, GitError> {
+        let reference = format!("refs/heads/{}", branch);
+        // show-ref exits 1 for a missing ref, and >1 for real errors
+        let output = self.run(repo, &["show-ref", "--verify", "--quiet", &reference])?;
+        match output.status.code() {
+            Some(0) => Ok(true),
+            Some(1) => Ok(false),
+            _ => Err(GitError::Failed),
+        }
+    }
+}
+
+/// In-process backend built on libgit2
+///
+/// Repositories libgit2 cannot open (for example ones using extensions it
+/// does not support) are handed to the `git` binary instead.
+#[cfg(feature = "libgit2")]
+pub(crate) struct Libgit2Backend<'a> {
+    fallback: CliBackend<'a>,
+}
+
+#[cfg(feature = "libgit2")]
+impl<'a> Libgit2Backend<'a> {
+    pub fn new(policy: &'a ExecutionPolicy) -> Self {
+        Self {
+            fallback: CliBackend::new(policy),
+        }
+    }
+
+    /// Open the repository containing `repo`, or `None` to use the fallback
+    fn open(&self, repo: &str) -> Result<Option<git2::Repository>, GitError> {
+        match git2::Repository::discover(repo) {
+            Ok(repository) => Ok(Some(repository)),
+            Err(e) if e.code() == git2::ErrorCode::NotFound => Err(GitError::Failed),
+            Err(_) => Ok(None),
+        }
+    }
+}
+
+#[cfg(feature = "libgit2")]
+impl GitBackend for Libgit2Backend<'_> {
+    fn status(&self, repo: &str) -> Result<Vec<StatusEntry>, GitError> {
+        let Some(repository) = self.open(repo)? else {
+            return self.fallback.status(repo);
+        };
+        let mut options = git2::StatusOptions::new();
+        options
+            .include_untracked(true)
+            .recurse_untracked_dirs(false)
+            .include_ignored(false);
+        let statuses = repository
+            .statuses(Some(&mut options))
+            .map_err(|_| GitError::Failed)?;
+
+        let mut entries: Vec<StatusEntry> = statuses
+            .iter()
+            .filter_map(|entry| {
+                let path = entry.path()?.to_string();
+                Some(StatusEntry {
+                    code: porcelain_code(entry.status())?,
+                    path,
+                })
+            })
+            .collect();
+        entries.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(entries)
+    }
+
+    fn object_type(&self, repo: &str, rev: &str) -> Result<Option<String>, GitError> {
+        let Some(repository) = self.open(repo)? else {
+            return self.fallback.object_type(repo, rev);
+        };
+        Ok(repository
+            .revparse_single(rev)
+            .ok()
+            .and_then(|object| object.kind())
+            .map(|kind| kind.str().to_string()))
+    }
+
+    fn branch_exists(&self, repo: &str, branch: &str) -> Result<bool, GitError> {
+        let Some(repository) = self.open(repo)? else {
+            return self.fallback.branch_exists(repo, branch);
+        };
+        let reference = format!("refs/heads/{}", branch);
+        let found = match repository.find_reference(&reference) {
+            Ok(_) => Ok(true),
+            Err(e) if e.code() == git2::ErrorCode::NotFound => Ok(false),
+            Err(e) if e.code() == git2::ErrorCode::InvalidSpec => Ok(false),
+            Err(_) => Err(GitError::Failed),
+        };
+        found
+    }
+}
+
+/// Two-letter porcelain v1 code for a libgit2 status, as `git status` prints it
+#[cfg(feature = "libgit2")]
+fn porcelain_code(status: git2::Status) -> Option<String> {
+    use git2::Status;
+
+    if status.contains(Status::IGNORED) {
+        return None;
+    }
+    if status.contains(Status::CONFLICTED) {
+        return Some("UU".to_string());
+    }
+    if status.contains(Status::WT_NEW) && !status.intersects(Status::INDEX_NEW) {
+        return Some("??".to_string());
+    }
+
+    let index = if status.contains(Status::INDEX_NEW) {
+        'A'
+    } else if status.contains(Status::INDEX_MODIFIED) {
+        'M'
+    } else if status.contains(Status::INDEX_DELETED) {
+        'D'
+    } else if status.contains(Status::INDEX_TYPECHANGE) {
+        'T'
+    } else {
+        ' '
+    };
+    let worktree = if status.contains(Status::WT_MODIFIED) {
+        'M'
+    } else if status.contains(Status::WT_DELETED) {
+        'D'
+    } else if status.contains(Status::WT_TYPECHANGE) {
+        'T'
+    } else {
+        ' '
+    };
+
+    if index == ' ' && worktree == ' ' {
+        None
+    } else {
+        Some(format!("{}{}", index, worktree))
+    }
+}
+
+/// Whether `commit` names a revision range rather than a single commit
+pub(crate) fn is_range(commit: &str) -> bool {
+    commit.contains("..")
+}
+
+/// Reject revisions git would parse as options (e.g. `--output=...`)
+pub(crate) fn check_revision(commit: &str) -> Result<(), String> {
+    if commit.is_empty() || commit.starts_with('-') {
+        Err(format!("Invalid revision: '{}'", commit))
+    } else {
+        Ok(())
+    }
+}
+
+/// Arguments listing the changes made by `commit`, without the commit header
+pub(crate) fn changes_args(commit: &str) -> [&str; 3] {
+    let command = if is_range(commit) { "log" } else { "show" };
+    [command, "--format=", commit]
+}
+
+/// Lines added and removed in one file (`None` for binary files)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct FileStat {
+    pub path: String,
+    pub added: Option<u64>,
+    pub removed: Option<u64>,
+}
+
+/// Per-file line counts, as printed by `git --numstat -z --no-renames`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct DiffStat {
+    pub files: Vec<FileStat>,
+}
+
+impl DiffStat {
+    /// Parse `--numstat -z` output, merging repeated files from a range
+    pub fn parse_numstat(output: &str) -> Self {
+        let mut stat = DiffStat::default();
+        for entry in output.split('\0') {
+            let mut fields = entry.trim_start_matches('\n').splitn(3, '\t');
+            let (Some(added), Some(removed), Some(path)) =
+                (fields.next(), fields.next(), fields.next())
+            else {
+                continue;
+            };
+            let added = added.parse().ok();
+            let removed = removed.parse().ok();
+
+            match stat.files.iter_mut().find(|f| f.path == path) {
+                Some(file) => {
+                    file.added = file.added.zip(added).map(|(a, b)| a + b);
+                    file.removed = file.removed.zip(removed).map(|(a, b)| a + b);
+                }
+                None => stat.files.push(FileStat {
+                    path: path.to_string(),
+                    added,
+                    removed,
+                }),
+            }
+        }
+        stat
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+
+    /// One-line summary, e.g. `2 files changed, +12 -3 (src/lib.rs +10 -3, README.md +2 -0)`
+    pub fn summary(&self) -> String {
+        if self.files.is_empty() {
+            return "no files changed".to_string();
+        }
+
+        let added: u64 = self.files.iter().filter_map(|f| f.added).sum();
+        let removed: u64 = self.files.iter().filter_map(|f| f.removed).sum();
+        let files: Vec<String> = self
+            .files
+            .iter()
+            .take(5)
+            .map(|f| match (f.added, f.removed) {
+                (Some(a), Some(r)) => format!("{} +{} -{}", f.path, a, r),
+                _ => format!("{} (binary)", f.path),
+            })
+            .collect();
+        let more = self.files.len().saturating_sub(files.len());
+
+        format!(
+            "{} file{} changed, +{} -{} ({}{})",
+            self.files.len(),
+            if self.files.len() == 1 { "" } else { "s" },
+            added,
+            removed,
+            files.join(", "),
+            if more > 0 {
+                format!(", {} more", more)
+            } else {
+                String::new()
+            }
+        )
+    }
+}
+
+/// Added and/or removed lines from a unified diff, without the `+`/`-` marker.
+/// File headers (`--- a/x`, `+++ b/x`) are skipped.
+pub(crate) fn changed_lines(patch: &str, side: DiffSide) -> Vec<&str> {
+    let mut lines = Vec::new();
+    let mut in_hunk = false;
+
+    for line in patch.lines() {
+        if line.starts_with("diff ") {
+            in_hunk = false;
+        } else if line.starts_with("@@") {
+            in_hunk = true;
+        } else if in_hunk {
+            if let Some(rest) = line.strip_prefix('+') {
+                if side != DiffSide::Removed {
+                    lines.push(rest);
+                }
+            } else if let Some(rest) = line.strip_prefix('-') {
+                if side != DiffSide::Added {
+                    lines.push(rest);
+                }
+            }
+        }
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_numstat_merges_range_entries() {
+        let output = "3\t1\tsrc/lib.rs\0-\t-\tlogo.png\0\n2\t0\tsrc/lib.rs\0";
+        let stat = DiffStat::parse_numstat(output);
+
+        assert_eq!(stat.files.len(), 2);
+        assert_eq!(stat.files[0].added, Some(5));
+        assert_eq!(stat.files[0].removed, Some(1));
+        assert_eq!(stat.files[1].added, None);
+        assert_eq!(
+            stat.summary(),
+            "2 files changed, +5 -1 (src/lib.rs +5 -1, logo.png (binary))"
+        );
+    }
+
+    #[test]
+    fn test_changed_lines_skips_headers() {
+        let patch = "\
+diff --git a/a.txt b/a.txt
+--- a/a.txt
++++ b/a.txt
+@@ -1 +1 @@
+--- old separator
++fn added() {}
+";
+        assert_eq!(changed_lines(patch, DiffSide::Added), vec!["fn added() {}"]);
+        assert_eq!(
+            changed_lines(patch, DiffSide::Removed),
+            vec!["-- old separator"]
+        );
+        assert_eq!(changed_lines(patch, DiffSide::Any).len(), 2);
+    }
+
+    fn sh_git(dir: &std::path::Path, args: &[&str]) {
+        let status = std::process::Command::new("git")
+            .args(["-c", "user.name=t", "-c", "user.email=t@example.com", "-C"])
+            .arg(dir)
+            .args(args)
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {:?} failed", args);
+    }
+
+    /// A repository with one of each kind of status entry
+    fn messy_repo() -> tempfile::TempDir {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        sh_git(root, &["init", "-q", "-b", "main"]);
+        for name in ["kept.txt", "edited.txt", "staged.txt", "gone.txt"] {
+            std::fs::write(root.join(name), "v1\n").unwrap();
+        }
+        sh_git(root, &["add", "."]);
+        sh_git(root, &["commit", "-qm", "init"]);
+        sh_git(root, &["tag", "-a", "v1", "-m", "release"]);
+        sh_git(root, &["branch", "topic"]);
+
+        std::fs::write(root.join("edited.txt"), "v2\n").unwrap();
+        std::fs::write(root.join("staged.txt"), "v2\n").unwrap();
+        sh_git(root, &["add", "staged.txt"]);
+        std::fs::write(root.join("staged.txt"), "v3\n").unwrap();
+        std::fs::remove_file(root.join("gone.txt")).unwrap();
+        std::fs::write(root.join("new.txt"), "").unwrap();
+        sh_git(root, &["add", "new.txt"]);
+        std::fs::create_dir(root.join("scratch")).unwrap();
+        std::fs::write(root.join("scratch/a.txt"), "").unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_cli_backend_status() {
+        let dir = messy_repo();
+        let policy = ExecutionPolicy::default();
+        let entries = CliBackend::new(&policy)
+            .status(dir.path().to_str().unwrap())
+            .unwrap();
+        let lines: Vec<String> = entries
+            .iter()
+            .map(|e| format!("{} {}", e.code, e.path))
+            .collect();
+        assert_eq!(
+            lines,
+            vec![
+                " M edited.txt",
+                " D gone.txt",
+                "A  new.txt",
+                "?? scratch/",
+                "MM staged.txt",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cli_backend_reports_missing_repo() {
+        let dir = tempfile::tempdir().unwrap();
+        let policy = ExecutionPolicy::default();
+        let backend = CliBackend::new(&policy);
+        let path = dir.path().to_str().unwrap();
+        assert_eq!(backend.status(path), Err(GitError::Failed));
+        assert_eq!(backend.branch_exists(path, "main"), Err(GitError::Failed));
+    }
+
+    #[cfg(feature = "libgit2")]
+    #[test]
+    fn test_backends_agree() {
+        let dir = messy_repo();
+        let path = dir.path().to_str().unwrap();
+        let policy = ExecutionPolicy::default();
+        let cli = CliBackend::new(&policy);
+        let lib = Libgit2Backend::new(&policy);
+
+        assert_eq!(cli.status(path), lib.status(path));
+        for rev in [
+            "HEAD",
+            "v1",
+            "HEAD^{tree}",
+            "HEAD:kept.txt",
+            "nope",
+            "HEAD~5",
+        ] {
+            assert_eq!(
+                cli.object_type(path, rev),
+                lib.object_type(path, rev),
+                "{}",
+                rev
+            );
+        }
+        for branch in ["main", "topic", "missing", "bad..name"] {
+            assert_eq!(
+                cli.branch_exists(path, branch),
+                lib.branch_exists(path, branch),
+                "{}",
+                branch
+            );
+        }
+
+        let empty = tempfile::tempdir().unwrap();
+        let empty = empty.path().to_str().unwrap();
+        assert_eq!(cli.status(empty), lib.status(empty));
+        assert_eq!(
+            cli.branch_exists(empty, "main"),
+            lib.branch_exists(empty, "main")
+        );
+    }
+
+    #[test]
+    fn test_check_revision_rejects_options() {
+        assert!(check_revision("HEAD~1").is_ok());
+        assert!(check_revision("--output=/tmp/x").is_err());
+        assert!(check_revision("").is_err());
+    }
+}
@@ -0,0 +1,431 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Test harness output for `TestPasses`
+//!
+//! A zero exit status from a test runner proves little: a filter that
+//! matches nothing still exits 0. `TestPasses` therefore runs the runner and
+//! reads back which tests actually executed:
+//!
+//! - `cargo test` prints one `test name ... ok` line per test (libtest's
+//!   JSON event stream is understood too, for harnesses that emit it)
+//! - `pytest` writes a JUnit XML report
+//! - `go test -json` emits test2json events
+//!
+//! A test name matches a reported test when its `::`, `.` or `/` separated
+//! segments are a suffix of the reported name, so `parse_args` matches
+//! `cli::tests::parse_args`, and `TestMath::test_add` matches pytest's
+//! `tests.test_math.TestMath.test_add[1-2]`.
+//!
+//! ```rust
+//! use did_you_actually_do_that::harness::{parse_libtest, TestStatus};
+//!
+//! let output = "running 2 tests\ntest cli::parse_args ... ok\ntest cli::parse_env ... FAILED\n";
+//! let outcomes = parse_libtest(output);
+//! assert!(outcomes[0].matches("parse_args") && outcomes[0].status == TestStatus::Passed);
+//! assert_eq!(outcomes[1].status, TestStatus::Failed);
+//! ```
+
+use crate::exec::CommandRun;
+use crate::{Verdict, VerificationError};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::Path;
+
+/// A supported test runner
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum TestRunner {
+    /// `cargo test NAME ARGS...`
+    #[default]
+    Cargo,
+    /// `pytest -k NAME ARGS...`, or the node id itself if NAME contains `::`
+    Pytest,
+    /// `go test -json -run ^NAME$ ARGS...` (`./...` if no ARGS)
+    Go,
+}
+
+/// How a single test finished
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TestStatus {
+    Passed,
+    Failed,
+    /// Ignored or skipped; the test did not run to completion
+    Skipped,
+}
+
+/// One test reported by a harness
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TestOutcome {
+    pub name: String,
+    pub status: TestStatus,
+}
+
+impl TestOutcome {
+    /// Whether `query` names this test (see the module docs)
+    pub fn matches(&self, query: &str) -> bool {
+        if self.name == query {
+            return true;
+        }
+        let name = strip_params(&self.name);
+        let have = segments(name);
+        let want = segments(query.trim().replace(".py::", "::"));
+        !want.is_empty() && want.len() <= have.len() && have[have.len() - want.len()..] == want[..]
+    }
+}
+
+/// Drop pytest's parametrization suffix: `test_add[1-2]` -> `test_add`
+fn strip_params(name: &str) -> &str {
+    match name.find('[') {
+        Some(pos) if name.ends_with(']') => &name[..pos],
+        _ => name,
+    }
+}
+
+fn segments(name: impl AsRef<str>) -> Vec<String> {
+    name.as_ref()
+        .split([':', '.', '/'])
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+impl TestRunner {
+    /// The program this runner executes
+    pub fn program(self) -> &'static str {
+        match self {
+            TestRunner::Cargo => "cargo",
+            TestRunner::Pytest => "pytest",
+            TestRunner::Go => "go",
+        }
+    }
+
+    /// Arguments selecting `name`, with pytest writing its report to `report`
+    pub(crate) fn args(self, name: &str, extra: &[String], report: &Path) -> Vec<String> {
+        let mut args: Vec<String> = match self {
+            TestRunner::Cargo => vec!["test".into(), name.into()],
+            TestRunner::Pytest => {
+                let mut args = vec![
+                    "-q".into(),
+                    "-p".into(),
+                    "no:cacheprovider".into(),
+                    format!("--junitxml={}", report.display()),
+                ];
+                if name.contains("::") {
+                    args.push(name.into());
+                } else {
+                    let last = segments(strip_params(name)).pop().unwrap_or_default();
+                    args.extend(["-k".into(), last]);
+                }
+                args
+            }
+            TestRunner::Go => {
+                let pattern: Vec<String> = name
+                    .split('/')
+                    .map(|part| format!("^{}$", regex::escape(part)))
+                    .collect();
+                vec![
+                    "test".into(),
+                    "-json".into(),
+                    "-run".into(),
+                    pattern.join("/"),
+                ]
+            }
+        };
+        args.extend(extra.iter().cloned());
+        if self == TestRunner::Go && extra.is_empty() {
+            args.push("./...".into());
+        }
+        args
+    }
+
+    /// The tests a finished run reports
+    pub(crate) fn outcomes(
+        self,
+        run: &CommandRun,
+        report: &Path,
+    ) -> Result<Vec<TestOutcome>, String> {
+        match self {
+            TestRunner::Cargo => Ok(parse_libtest(&run.stdout)),
+            TestRunner::Go => Ok(parse_go_json(&run.stdout)),
+            TestRunner::Pytest => match std::fs::read_to_string(report) {
+                Ok(xml) => parse_junit(&xml),
+                // pytest writes no report when it fails before collecting
+                Err(_) => Ok(Vec::new()),
+            },
+        }
+    }
+}
+
+/// Parse libtest output, either the human-readable `test name ... ok`
+/// lines or the JSON event stream (`--format json`)
+pub fn parse_libtest(output: &str) -> Vec<TestOutcome> {
+    let mut outcomes = Vec::new();
+    for line in output.lines() {
+        let line = line.trim_end();
+        if line.starts_with('{') {
+            let Ok(event) = serde_json::from_str::<Value>(line) else {
+                continue;
+            };
+            if event["type"] != "test" {
+                continue;
+            }
+            let status = match event["event"].as_str() {
+                Some("ok") => TestStatus::Passed,
+                Some("failed" | "timeout") => TestStatus::Failed,
+                Some("ignored") => TestStatus::Skipped,
+                _ => continue,
+            };
+            if let Some(name) = event["name"].as_str() {
+                outcomes.push(TestOutcome {
+                    name: name.to_string(),
+                    status,
+                });
+            }
+        } else if let Some((name, result)) = line
+            .strip_prefix("test ")
+            .and_then(|rest| rest.rsplit_once(" ... "))
+        {
+            let status = match result {
+                "ok" => TestStatus::Passed,
+                "FAILED" => TestStatus::Failed,
+                r if r.starts_with("ignored") => TestStatus::Skipped,
+                _ => continue,
+            };
+            outcomes.push(TestOutcome {
+                name: name.to_string(),
+                status,
+            });
+        }
+    }
+    outcomes
+}
+
+/// Parse `go test -json` (test2json) events
+pub fn parse_go_json(output: &str) -> Vec<TestOutcome> {
+    output
+        .lines()
+        .filter_map(|line| serde_json::from_str::<Value>(line).ok())
+        .filter_map(|event| {
+            let status = match event["Action"].as_str()? {
+                "pass" => TestStatus::Passed,
+                "fail" => TestStatus::Failed,
+                "skip" => TestStatus::Skipped,
+                _ => return None,
+            };
+            Some(TestOutcome {
+                name: event["Test"].as_str()?.to_string(),
+                status,
+            })
+        })
+        .collect()
+}
+
+/// Parse a JUnit XML report. Test names are `classname.name`.
+pub fn parse_junit(xml: &str) -> Result<Vec<TestOutcome>, String> {
+    let doc = roxmltree::Document::parse(xml).map_err(|e| format!("Invalid JUnit XML: {}", e))?;
+    Ok(doc
+        .descendants()
+        .filter(|node| node.has_tag_name("testcase"))
+        .map(|case| {
+            let name = case.attribute("name").unwrap_or_default();
+            let status = if case
+                .children()
+                .any(|c| c.has_tag_name("failure") || c.has_tag_name("error"))
+            {
+                TestStatus::Failed
+            } else if case.children().any(|c| c.has_tag_name("skipped")) {
+                TestStatus::Skipped
+            } else {
+                TestStatus::Passed
+            };
+            TestOutcome {
+                name: match case.attribute("classname") {
+                    Some(class) if !class.is_empty() => format!("{}.{}", class, name),
+                    _ => name.to_string(),
+                },
+                status,
+            }
+        })
+        .collect())
+}
+
+/// A fresh private directory for a runner's report file, removed when
+/// dropped. It is created exclusively and only its owner can enter it, so a
+/// program under audit cannot plant a report (or a symlink) there first.
+pub(crate) fn report_dir() -> std::io::Result<tempfile::TempDir> {
+    tempfile::Builder::new().prefix("dyadt-junit-").tempdir()
+}
+
+/// Name of the report file inside [`report_dir`]
+pub(crate) const REPORT_FILE: &str = "junit.xml";
+
+/// Verdict for a `TestPasses` run: at least one test matching `name` must
+/// have run and passed, and none may have failed
+pub(crate) fn judge(
+    runner: TestRunner,
+    name: &str,
+    run: &CommandRun,
+    timeout_secs: Option<u64>,
+    outcomes: &[TestOutcome],
+) -> (Verdict, Option<String>) {
+    if run.timed_out {
+        return (
+            Verdict::Inconclusive,
+            Some(VerificationError::Timeout(timeout_secs.unwrap_or_default()).to_string()),
+        );
+    }
+
+    let matching: Vec<&TestOutcome> = outcomes.iter().filter(|t| t.matches(name)).collect();
+    let names = |status| {
+        matching
+            .iter()
+            .filter(|t| t.status == status)
+            .map(|t| t.name.as_str())
+            .collect::<Vec<_>>()
+    };
+
+    if matching.is_empty() {
+        let exit = match run.code {
+            Some(0) => String::new(),
+            Some(code) => format!(" ({} exited with code {})", runner.program(), code),
+            None => format!(" ({} was terminated by a signal)", runner.program()),
+        };
+        return (
+            Verdict::Refuted,
+            Some(format!("No test matching '{}' ran{}", name, exit)),
+        );
+    }
+
+    let failed = names(TestStatus::Failed);
+    if !failed.is_empty() {
+        return (
+            Verdict::Refuted,
+            Some(format!("Failed: {}", failed.join(", "))),
+        );
+    }
+
+    let passed = names(TestStatus::Passed);
+    if passed.is_empty() {
+        return (
+            Verdict::Refuted,
+            Some(format!("Test '{}' was skipped", name)),
+        );
+    }
+    (
+        Verdict::Confirmed,
+        Some(format!("Passed: {}", passed.join(", "))),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn outcome(name: &str) -> TestOutcome {
+        TestOutcome {
+            name: name.to_string(),
+            status: TestStatus::Passed,
+        }
+    }
+
+    #[test]
+    fn test_name_matching() {
+        assert!(outcome("cli::tests::parse_args").matches("parse_args"));
+        assert!(outcome("cli::tests::parse_args").matches("tests::parse_args"));
+        assert!(!outcome("cli::tests::parse_args_bad").matches("parse_args"));
+        assert!(!outcome("cli::tests::parse_args").matches("other::parse_args"));
+        assert!(outcome("tests.test_math.TestMath.test_add[1-2]").matches("TestMath::test_add"));
+        assert!(outcome("tests.test_math.test_add").matches("tests/test_math.py::test_add"));
+        assert!(outcome("TestParse/empty").matches("TestParse/empty"));
+        assert!(!outcome("TestParse").matches(""));
+    }
+
+    #[test]
+    fn test_parse_libtest_text_and_json() {
+        let text = "test a::one ... ok\ntest a::two ... FAILED\ntest a::three ... ignored, slow\n\
+                    test result: FAILED. 1 passed";
+        let statuses: Vec<_> = parse_libtest(text).into_iter().map(|t| t.status).collect();
+        assert_eq!(
+            statuses,
+            vec![TestStatus::Passed, TestStatus::Failed, TestStatus::Skipped]
+        );
+
+        let json = r#"{ "type": "suite", "event": "started", "test_count": 1 }
+{ "type": "test", "event": "started", "name": "a::one" }
+{ "type": "test", "name": "a::one", "event": "ok" }"#;
+        assert_eq!(parse_libtest(json), vec![outcome("a::one")]);
+    }
+
+    #[test]
+    fn test_parse_go_json() {
+        let output = r#"{"Action":"run","Package":"p","Test":"TestA"}
+{"Action":"output","Package":"p","Test":"TestA","Output":"ok\n"}
+{"Action":"pass","Package":"p","Test":"TestA","Elapsed":0}
+{"Action":"skip","Package":"p","Test":"TestB","Elapsed":0}
+{"Action":"fail","Package":"p","Elapsed":0}"#;
+        let outcomes = parse_go_json(output);
+        assert_eq!(outcomes.len(), 2);
+        assert_eq!(outcomes[0], outcome("TestA"));
+        assert_eq!(outcomes[1].status, TestStatus::Skipped);
+    }
+
+    #[test]
+    fn test_parse_junit() {
+        let xml = r#"<?xml version="1.0"?>
+<testsuites><testsuite name="pytest">
+  <testcase classname="tests.test_math" name="test_add" time="0.01"/>
+  <testcase classname="tests.test_math" name="test_sub"><failure message="boom"/></testcase>
+  <testcase classname="tests.test_math" name="test_div"><skipped/></testcase>
+</testsuite></testsuites>"#;
+        let outcomes = parse_junit(xml).unwrap();
+        assert_eq!(outcomes[0], outcome("tests.test_math.test_add"));
+        assert_eq!(outcomes[1].status, TestStatus::Failed);
+        assert_eq!(outcomes[2].status, TestStatus::Skipped);
+        assert!(parse_junit("<testsuite>").is_err());
+    }
+
+    #[test]
+    fn test_runner_args() {
+        let report = Path::new("/tmp/r.xml");
+        assert_eq!(
+            TestRunner::Go.args("TestParse/empty", &[], report),
+            ["test", "-json", "-run", "^TestParse$/^empty$", "./..."]
+        );
+        assert_eq!(
+            TestRunner::Pytest.args("TestMath.test_add", &[], report)[4..],
+            ["-k", "test_add"]
+        );
+        assert_eq!(
+            TestRunner::Cargo.args("parse_args", &["-p".into(), "cli".into()], report),
+            ["test", "parse_args", "-p", "cli"]
+        );
+    }
+
+    #[test]
+    fn test_judge_needs_a_passing_match() {
+        let run = |code| CommandRun {
+            code: Some(code),
+            timed_out: false,
+            stdout: String::new(),
+            stderr: String::new(),
+        };
+        let skipped = TestOutcome {
+            name: "a::slow".to_string(),
+            status: TestStatus::Skipped,
+        };
+
+        let verdict = |name, outcomes: &[TestOutcome]| {
+            judge(TestRunner::Cargo, name, &run(0), None, outcomes).0
+        };
+        assert_eq!(verdict("one", &[outcome("a::one")]), Verdict::Confirmed);
+        assert_eq!(verdict("two", &[outcome("a::one")]), Verdict::Refuted);
+        assert_eq!(verdict("slow", &[skipped]), Verdict::Refuted);
+
+        let (_, details) = judge(TestRunner::Cargo, "x", &run(101), None, &[]);
+        assert_eq!(
+            details.unwrap(),
+            "No test matching 'x' ran (cargo exited with code 101)"
+        );
+    }
+}
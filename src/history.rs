@@ -0,0 +1,422 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Persistent verification history
+//!
+//! A [`HistoryStore`] appends one JSON line per verified claim to
+//! `history.jsonl` in a directory of your choosing. The file is append-only,
+//! so several processes (CLI runs, hooks, the MCP server) can share it.
+//! [`HistoryStats`] turns the records into confirmation rates per source,
+//! evidence type and session, which is what `dyadt stats` prints.
+//!
+//! ```rust
+//! use did_you_actually_do_that::history::{HistoryStats, HistoryStore};
+//! use did_you_actually_do_that::{Claim, EvidenceSpec, Verifier};
+//!
+//! let dir = std::env::temp_dir().join("dyadt-history-doc");
+//! let store = HistoryStore::open(&dir).unwrap().for_session("demo");
+//! let verifier = Verifier::new().with_history(store.clone());
+//!
+//! verifier.verify(&Claim::new("Wrote the file").with_source("my-agent").with_evidence(
+//!     EvidenceSpec::FileExists { path: "/nonexistent/file.txt".to_string() },
+//! ));
+//!
+//! let stats = HistoryStats::from_records(&store.records().unwrap(), None);
+//! assert!(stats.by_source["my-agent"].refuted >= 1);
+//! # std::fs::remove_dir_all(&dir).unwrap();
+//! ```
+
+use crate::{EvidenceSpec, Verdict, VerificationReport};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+
+/// Environment variable naming the history directory
+pub const HISTORY_ENV: &str = "DYADT_HISTORY";
+
+/// File name of the log inside the history directory
+const HISTORY_FILE: &str = "history.jsonl";
+
+/// Key used in breakdowns for records without a source or session
+const UNKNOWN: &str = "(none)";
+
+/// One verified claim, as stored in the history log
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HistoryRecord {
+    pub recorded_at: DateTime<Utc>,
+    pub claim_id: String,
+    pub description: String,
+    #[serde(default)]
+    pub source: Option<String>,
+    #[serde(default)]
+    pub session: Option<String>,
+    pub verdict: Verdict,
+    /// Top-level evidence and how each piece was judged
+    #[serde(default)]
+    pub evidence: Vec<EvidenceOutcome>,
+}
+
+/// Verdict for one piece of top-level evidence in a [`HistoryRecord`]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EvidenceOutcome {
+    pub evidence_type: String,
+    pub verdict: Verdict,
+}
+
+impl HistoryRecord {
+    /// Summarise a report for the history log
+    pub fn from_report(report: &VerificationReport, session: Option<&str>) -> Self {
+        Self {
+            recorded_at: report.verified_at,
+            claim_id: report.claim.id.clone(),
+            description: report.claim.description.clone(),
+            source: report.claim.source.clone(),
+            session: session.map(str::to_string),
+            verdict: report.overall_verdict,
+            evidence: report
+                .evidence_results
+                .iter()
+                .map(|result| EvidenceOutcome {
+                    evidence_type: history_type_name(&result.spec),
+                    verdict: result.verdict,
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Custom checkers are broken out by name; everything else by variant
+fn history_type_name(spec: &EvidenceSpec) -> String {
+    match spec {
+        EvidenceSpec::Custom { name, .. } => format!("Custom:{}", name),
+        other => other.type_name().to_string(),
+    }
+}
+
+/// Append-only JSONL store of verification results
+#[derive(Debug, Clone)]
+pub struct HistoryStore {
+    path: PathBuf,
+    session: Option<String>,
+}
+
+impl HistoryStore {
+    /// Open (creating if needed) the history directory `dir`
+    pub fn open(dir: impl AsRef<Path>) -> io::Result<Self> {
+        fs::create_dir_all(dir.as_ref())?;
+        Ok(Self {
+            path: dir.as_ref().join(HISTORY_FILE),
+            session: None,
+        })
+    }
+
+    /// Open the directory named by `DYADT_HISTORY`, if it is set
+    pub fn from_env() -> io::Result<Option<Self>> {
+        match std::env::var_os(HISTORY_ENV) {
+            Some(dir) if !dir.is_empty() => Self::open(dir).map(Some),
+            _ => Ok(None),
+        }
+    }
+
+    /// A handle on the same log for an MCP server process started now; each
+    /// server process is one session, named after its start time
+    pub fn for_mcp_session(&self) -> Self {
+        self.for_session(format!("mcp-{}", Utc::now().format("%Y%m%dT%H%M%SZ")))
+    }
+
+    /// A handle on the same log that tags records with `session`
+    pub fn for_session(&self, session: impl Into<String>) -> Self {
+        Self {
+            path: self.path.clone(),
+            session: Some(session.into()),
+        }
+    }
+
+    /// Path of the JSONL log
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Session attached to records written through this handle
+    pub fn session(&self) -> Option<&str> {
+        self.session.as_deref()
+    }
+
+    /// Append a report, tagged with this handle's session
+    pub fn record(&self, report: &VerificationReport) -> io::Result<()> {
+        self.record_in_session(report, self.session.as_deref())
+    }
+
+    /// Append a report tagged with an explicit session
+    pub fn record_in_session(
+        &self,
+        report: &VerificationReport,
+        session: Option<&str>,
+    ) -> io::Result<()> {
+        let mut line = serde_json::to_string(&HistoryRecord::from_report(report, session))?;
+        line.push('\n');
+
+        // One write per record: appends of a single buffer do not interleave
+        // with other writers of the same file.
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        file.write_all(line.as_bytes())
+    }
+
+    /// Read every record, skipping lines that fail to parse
+    pub fn records(&self) -> io::Result<Vec<HistoryRecord>> {
+        let file = match fs::File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e),
+        };
+
+        let mut records = Vec::new();
+        for line in BufReader::new(file).lines() {
+            let line = line?;
+            if let Ok(record) = serde_json::from_str(&line) {
+                records.push(record);
+            }
+        }
+        Ok(records)
+    }
+}
+
+/// Verdict counts for one group of claims or evidence
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Tally {
+    pub total: usize,
+    pub confirmed: usize,
+    pub refuted: usize,
+    pub inconclusive: usize,
+    pub unverifiable: usize,
+}
+
+impl Tally {
+    fn add(&mut self, verdict: Verdict) {
+        self.total += 1;
+        match verdict {
+            Verdict::Confirmed => self.confirmed += 1,
+            Verdict::Refuted => self.refuted += 1,
+            Verdict::Inconclusive => self.inconclusive += 1,
+            Verdict::Unverifiable => self.unverifiable += 1,
+        }
+    }
+
+    /// Fraction of entries that were confirmed (0.0 when empty)
+    pub fn confirmation_rate(&self) -> f64 {
+        if self.total == 0 {
+            0.0
+        } else {
+            self.confirmed as f64 / self.total as f64
+        }
+    }
+}
+
+/// Confirmation rates computed from history records
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HistoryStats {
+    /// Start of the window, if records were filtered
+    pub since: Option<DateTime<Utc>>,
+    /// Overall claim verdicts
+    pub claims: Tally,
+    /// Claim verdicts per claim source
+    pub by_source: BTreeMap<String, Tally>,
+    /// Evidence verdicts per evidence type
+    pub by_evidence_type: BTreeMap<String, Tally>,
+    /// Claim verdicts per session
+    pub by_session: BTreeMap<String, Tally>,
+}
+
+impl HistoryStats {
+    /// Compute stats over records made at or after `since`
+    pub fn from_records(records: &[HistoryRecord], since: Option<DateTime<Utc>>) -> Self {
+        let mut stats = Self {
+            since,
+            ..Self::default()
+        };
+
+        for record in records {
+            if since.is_some_and(|since| record.recorded_at < since) {
+                continue;
+            }
+
+            stats.claims.add(record.verdict);
+            stats
+                .by_source
+                .entry(record.source.as_deref().unwrap_or(UNKNOWN).to_string())
+                .or_default()
+                .add(record.verdict);
+            stats
+                .by_session
+                .entry(record.session.as_deref().unwrap_or(UNKNOWN).to_string())
+                .or_default()
+                .add(record.verdict);
+            for evidence in &record.evidence {
+                stats
+                    .by_evidence_type
+                    .entry(evidence.evidence_type.clone())
+                    .or_default()
+                    .add(evidence.verdict);
+            }
+        }
+
+        stats
+    }
+}
+
+/// Parse a `--since` value: a relative window like `30m`, `12h`, `7d`, `2w`,
+/// or an RFC 3339 timestamp
+pub fn parse_since(value: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>, String> {
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(value) {
+        return Ok(timestamp.with_timezone(&Utc));
+    }
+
+    let invalid = || {
+        format!(
+            "Invalid time window: {} (use e.g. 12h, 7d or RFC 3339)",
+            value
+        )
+    };
+    let split = value
+        .char_indices()
+        .last()
+        .map(|(i, _)| i)
+        .ok_or_else(invalid)?;
+    let (amount, unit) = value.split_at(split);
+    let amount: i64 = amount
+        .parse()
+        .ok()
+        .filter(|amount| *amount >= 0)
+        .ok_or_else(invalid)?;
+    let window = match unit {
+        "s" => Duration::try_seconds(amount),
+        "m" => Duration::try_minutes(amount),
+        "h" => Duration::try_hours(amount),
+        "d" => Duration::try_days(amount),
+        "w" => Duration::try_weeks(amount),
+        _ => return Err(invalid()),
+    };
+    window
+        .and_then(|window| now.checked_sub_signed(window))
+        .ok_or_else(invalid)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Claim, Verifier};
+    use tempfile::tempdir;
+
+    fn record(source: Option<&str>, session: Option<&str>, verdict: Verdict) -> HistoryRecord {
+        HistoryRecord {
+            recorded_at: Utc::now(),
+            claim_id: "id".to_string(),
+            description: "claim".to_string(),
+            source: source.map(str::to_string),
+            session: session.map(str::to_string),
+            verdict,
+            evidence: vec![EvidenceOutcome {
+                evidence_type: "FileExists".to_string(),
+                verdict,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_store_round_trips_reports() {
+        let dir = tempdir().unwrap();
+        let store = HistoryStore::open(dir.path()).unwrap().for_session("s1");
+        let verifier = Verifier::new();
+
+        let claim = Claim::new("Missing file")
+            .with_source("agent")
+            .with_evidence(EvidenceSpec::FileExists {
+                path: "/nonexistent/file.txt".to_string(),
+            });
+        store.record(&verifier.verify(&claim)).unwrap();
+        store
+            .record_in_session(&verifier.verify(&claim), None)
+            .unwrap();
+
+        let records = store.records().unwrap();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].session.as_deref(), Some("s1"));
+        assert_eq!(records[0].source.as_deref(), Some("agent"));
+        assert_eq!(records[0].verdict, Verdict::Refuted);
+        assert_eq!(records[0].evidence[0].evidence_type, "FileExists");
+        assert!(records[1].session.is_none());
+
+        let mcp = store.for_mcp_session();
+        assert_eq!(mcp.path(), store.path());
+        let session = mcp.session().unwrap();
+        assert!(
+            session.starts_with("mcp-") && session.ends_with('Z'),
+            "{}",
+            session
+        );
+    }
+
+    #[test]
+    fn test_records_skip_malformed_lines() {
+        let dir = tempdir().unwrap();
+        let store = HistoryStore::open(dir.path()).unwrap();
+        assert!(store.records().unwrap().is_empty());
+
+        let line = serde_json::to_string(&record(None, None, Verdict::Confirmed)).unwrap();
+        fs::write(store.path(), format!("not json\n{}\n", line)).unwrap();
+        assert_eq!(store.records().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_stats_group_by_source_session_and_type() {
+        let records = vec![
+            record(Some("hook"), Some("a"), Verdict::Confirmed),
+            record(Some("hook"), Some("a"), Verdict::Refuted),
+            record(Some("mcp"), Some("b"), Verdict::Confirmed),
+            record(None, None, Verdict::Unverifiable),
+        ];
+        let stats = HistoryStats::from_records(&records, None);
+
+        assert_eq!(stats.claims.total, 4);
+        assert_eq!(stats.by_source["hook"].refuted, 1);
+        assert_eq!(stats.by_source["hook"].confirmation_rate(), 0.5);
+        assert_eq!(stats.by_source[UNKNOWN].unverifiable, 1);
+        assert_eq!(stats.by_session["b"].confirmed, 1);
+        assert_eq!(stats.by_evidence_type["FileExists"].total, 4);
+    }
+
+    #[test]
+    fn test_stats_respect_window() {
+        let mut old = record(Some("hook"), None, Verdict::Refuted);
+        old.recorded_at = Utc::now() - Duration::days(30);
+        let records = vec![old, record(Some("hook"), None, Verdict::Confirmed)];
+
+        let since = parse_since("7d", Utc::now()).unwrap();
+        let stats = HistoryStats::from_records(&records, Some(since));
+        assert_eq!(stats.claims.total, 1);
+        assert_eq!(stats.by_source["hook"].confirmation_rate(), 1.0);
+    }
+
+    #[test]
+    fn test_parse_since() {
+        let now = Utc::now();
+        assert_eq!(parse_since("12h", now).unwrap(), now - Duration::hours(12));
+        assert_eq!(
+            parse_since("2026-01-01T00:00:00Z", now)
+                .unwrap()
+                .to_rfc3339(),
+            "2026-01-01T00:00:00+00:00"
+        );
+        assert!(parse_since("", now).is_err());
+        assert!(parse_since("7y", now).is_err());
+        assert!(parse_since("xd", now).is_err());
+        assert!(parse_since("7é", now).is_err());
+        assert!(parse_since("-7d", now).is_err());
+        assert!(parse_since("9999999999999d", now).is_err());
+        assert!(parse_since("999999999w", now).is_err());
+    }
+}
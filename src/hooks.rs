@@ -31,7 +31,8 @@
 //! - `SessionEnd` - Generate verification report at end of session
 
 use crate::claim_extractor::{ClaimExtractor, ExtractionContext, ToolCall};
-use crate::{Verdict, VerificationReport, Verifier};
+use crate::history::HistoryStore;
+use crate::{Verdict, VerificationError, VerificationReport, Verifier};
 use chrono::Utc;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -83,6 +84,21 @@ pub struct HookOutput {
     pub summary: String,
 }
 
+impl HookOutput {
+    /// Output for a batch that could not be verified at all
+    fn invalid_claims(error: VerificationError) -> Self {
+        Self {
+            success: false,
+            verdict: "InvalidClaims".to_string(),
+            claims_verified: 0,
+            claims_confirmed: 0,
+            claims_refuted: 0,
+            reports: None,
+            summary: error.to_string(),
+        }
+    }
+}
+
 /// Hook handler for Claude Code integration
 pub struct HookHandler {
     extractor: ClaimExtractor,
@@ -98,10 +114,30 @@ impl Default for HookHandler {
 
 impl HookHandler {
     /// Create a new hook handler
+    ///
+    /// Verified claims are recorded in the history named by `DYADT_HISTORY`,
+    /// if set, tagged with the hook input's session id. If that history
+    /// cannot be opened, a warning goes to stderr and nothing is recorded.
     pub fn new() -> Self {
+        let verifier = match HistoryStore::from_env() {
+            Ok(Some(store)) => Verifier::new().with_history(store),
+            Ok(None) => Verifier::new(),
+            Err(e) => {
+                eprintln!(
+                    "Warning: cannot open history from DYADT_HISTORY, not recording: {}",
+                    e
+                );
+                Verifier::new()
+            }
+        };
+        Self::with_verifier(verifier)
+    }
+
+    /// Create a hook handler that verifies claims with the given verifier
+    pub fn with_verifier(verifier: Verifier) -> Self {
         Self {
             extractor: ClaimExtractor::new(),
-            verifier: Verifier::new(),
+            verifier,
             session_start: Utc::now().to_rfc3339(),
         }
     }
@@ -165,10 +201,13 @@ impl HookHandler {
         }
 
         // Verify all claims
-        let reports: Vec<VerificationReport> = claims
-            .iter()
-            .map(|claim| self.verifier.verify(claim))
-            .collect();
+        let reports = match self
+            .verifier
+            .verify_batch_in_session(&claims, input.session_id.as_deref())
+        {
+            Ok(reports) => reports,
+            Err(e) => return HookOutput::invalid_claims(e),
+        };
 
         let confirmed = reports
             .iter()
@@ -231,10 +270,13 @@ impl HookHandler {
             };
         }
 
-        let reports: Vec<VerificationReport> = claims
-            .iter()
-            .map(|claim| self.verifier.verify(claim))
-            .collect();
+        let reports = match self
+            .verifier
+            .verify_batch_in_session(&claims, input.session_id.as_deref())
+        {
+            Ok(reports) => reports,
+            Err(e) => return HookOutput::invalid_claims(e),
+        };
 
         let confirmed = reports
             .iter()
@@ -302,10 +344,13 @@ impl HookHandler {
             };
         }
 
-        let reports: Vec<VerificationReport> = claims
-            .iter()
-            .map(|claim| self.verifier.verify(claim))
-            .collect();
+        let reports = match self
+            .verifier
+            .verify_batch_in_session(&claims, input.session_id.as_deref())
+        {
+            Ok(reports) => reports,
+            Err(e) => return HookOutput::invalid_claims(e),
+        };
 
         let total = reports.len();
         let confirmed = reports
@@ -399,6 +444,35 @@ mod tests {
         assert!(output.claims_refuted > 0);
     }
 
+    #[test]
+    fn test_hook_records_history_with_session() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = HistoryStore::open(dir.path()).unwrap();
+        let handler = HookHandler::with_verifier(Verifier::new().with_history(store.clone()));
+
+        let mut args = HashMap::new();
+        args.insert(
+            "file_path".to_string(),
+            serde_json::json!("/nonexistent/path/test.txt"),
+        );
+        let input = HookInput {
+            event: HookEvent::PostToolExecution,
+            tool_name: Some("Write".to_string()),
+            tool_args: Some(args),
+            tool_result: None,
+            working_directory: None,
+            session_id: Some("session-42".to_string()),
+        };
+        handler.handle(&input);
+
+        let records = store.records().unwrap();
+        assert!(!records.is_empty());
+        assert!(records
+            .iter()
+            .all(|r| r.session.as_deref() == Some("session-42")));
+        assert_eq!(records[0].source.as_deref(), Some("claude-code-hook"));
+    }
+
     #[test]
     fn test_parse_hook_input() {
         let json = r#"{
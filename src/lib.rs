@@ -47,20 +47,31 @@
 //! - `FileWithHash` - Verify file exists with specific SHA-256 hash
 //! - `FileContains` - Check if file contains a substring
 //! - `FileMatchesRegex` - Check if file matches a regex pattern
+//! - `FileLineRange` / `FileUnifiedDiffApplied` - Check exact lines or a diff's hunks
 //! - `FileJsonPath` - Verify JSON value at path
+//! - `FileStructuredPath` - Same query against TOML, YAML, INI or dotenv files
+//! - `SymbolDefined` - Parse a source file and look for a named item
 //! - `DirectoryExists` - Check if directory exists
 //! - `CommandSucceeds` - Run a command and check it succeeds
+//! - `TestPasses` - Run a named test with cargo, pytest or go and check it passed
 //! - `GitClean` - Check if git working directory is clean
 //! - `GitCommitExists` - Verify a git commit exists
 //! - `GitBranchExists` - Verify a git branch exists
+//! - `FileChangedInCommit` / `CommitTouchesPaths` - Check what a commit (or range) changed
+//! - `CommitDiffMatches` / `CommitMessageMatches` - Match a commit's diff lines or message
+//! - `CommitIsAncestor` - Check HEAD descends from a commit
 //! - `FileModifiedAfter` - Check file was modified after timestamp
 //! - `EnvVar` - Check environment variable value
+//! - `FileAbsent` / `DirectoryAbsent` / `GitBranchAbsent` / `EnvVarAbsent` - Check something was removed
+//! - `AllOf` / `AnyOf` / `Not` / `AtLeast` - Combine nested evidence
+//! - `Weighted` - Give evidence a weight, or make it required, for [`AggregationPolicy`]
 //! - `Custom` - Extensible custom checks
 //!
 //! ## Features
 //!
 //! - `async` - Enable async verification for network-based evidence checks (HTTP, TCP)
 //! - `watch` - Enable watch mode for continuous verification
+//! - `libgit2` - Answer repository queries in-process instead of running `git`
 
 #[cfg(feature = "async")]
 pub mod async_checks;
@@ -68,17 +79,44 @@ pub mod async_checks;
 #[cfg(feature = "watch")]
 pub mod watch;
 
+pub mod baseline;
+pub mod checker;
 pub mod claim_extractor;
+mod exec;
+mod git;
+pub mod harness;
+pub mod history;
 pub mod hooks;
+pub mod manifest;
 pub mod mcp_server;
-
+pub mod patch;
+pub mod policy;
+pub mod query;
+pub mod region;
+pub mod report;
+pub mod schema;
+pub mod snapshot;
+pub mod source;
+pub mod structured;
+
+use checker::{CheckOutcome, EvidenceChecker, FnChecker};
 use chrono::{DateTime, Utc};
+use harness::TestRunner;
+use history::HistoryStore;
+use policy::ExecutionPolicy;
+use query::JsonOp;
 use regex::Regex;
+use region::Region;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
-use std::collections::HashMap;
+use source::{RustParser, SourceParser, SymbolKind};
+use std::collections::{BTreeMap, HashMap};
 use std::path::Path;
-use std::process::Command;
+use std::process::Output;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+use structured::ConfigFormat;
 use thiserror::Error;
 
 /// Errors that can occur during verification
@@ -150,15 +188,30 @@ impl Verdict {
 /// let cmd_evidence = EvidenceSpec::CommandSucceeds {
 ///     command: "cargo".to_string(),
 ///     args: vec!["test".to_string()],
+///     cwd: Some("/path/to/project".to_string()),
+///     env: Default::default(),
+///     timeout_secs: Some(600),
+///     expected_exit_code: 0,
+///     stdout_contains: Some("test result: ok".to_string()),
+///     stdout_matches: None,
 /// };
 ///
 /// // Git repository is clean
 /// let git_evidence = EvidenceSpec::GitClean {
 ///     repo_path: Some("/path/to/repo".to_string()),
 /// };
+///
+/// // Config lives in either of two places
+/// let either_evidence = EvidenceSpec::AnyOf {
+///     evidence: vec![
+///         EvidenceSpec::FileExists { path: "config.toml".to_string() },
+///         EvidenceSpec::FileExists { path: "config.yaml".to_string() },
+///     ],
+/// };
 /// ```
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(tag = "type", content = "spec")]
+#[schemars(description = "Something that should be true if a claim is accurate")]
 pub enum EvidenceSpec {
     /// A file should exist at the given path
     FileExists { path: String },
@@ -172,19 +225,99 @@ pub enum EvidenceSpec {
     /// A file should match a regular expression pattern
     FileMatchesRegex { path: String, pattern: String },
 
-    /// A JSON file should have a value at the given path
-    /// Path format: `.field.nested\[0\].value`
+    /// Lines `start..=end` (1-based) of a file should be exactly `expected`.
+    /// Without `end` the range spans as many lines as `expected` has.
+    FileLineRange {
+        path: String,
+        start: usize,
+        #[serde(default)]
+        end: Option<usize>,
+        expected: String,
+    },
+
+    /// Every hunk of a unified diff should be present in a file, i.e. the
+    /// diff reverse-applies cleanly; see [`patch`]
+    FileUnifiedDiffApplied { path: String, diff: String },
+
+    /// A JSON file should have a value at the given path that satisfies `op`
+    /// (equality by default). The path is RFC 9535 JSONPath (`$.a[?@.b > 1]`)
+    /// or the legacy dotted form (`.field.nested\[0\].value`); see [`query`].
     FileJsonPath {
         path: String,
         json_path: String,
+        #[serde(default)]
+        expected: serde_json::Value,
+        #[serde(default)]
+        op: JsonOp,
+    },
+
+    /// Like `FileJsonPath`, for TOML, YAML, INI, dotenv or JSON files. The
+    /// format is detected from the file name unless given; see [`structured`].
+    FileStructuredPath {
+        path: String,
+        query: String,
+        #[serde(default)]
+        format: Option<ConfigFormat>,
+        #[serde(default)]
         expected: serde_json::Value,
+        #[serde(default)]
+        op: JsonOp,
+    },
+
+    /// A source file should define a named item, e.g. `Config::validate`.
+    /// The parser is chosen by `language` or the file extension; see [`source`].
+    SymbolDefined {
+        path: String,
+        symbol: String,
+        #[serde(default)]
+        kind: Option<SymbolKind>,
+        #[serde(default)]
+        language: Option<String>,
     },
 
     /// A directory should exist
     DirectoryExists { path: String },
 
-    /// A command should succeed (exit code 0)
-    CommandSucceeds { command: String, args: Vec<String> },
+    /// A command should succeed (exit code 0 unless `expected_exit_code` says otherwise)
+    CommandSucceeds {
+        command: String,
+        args: Vec<String>,
+        /// Working directory (defaults to the verifier's current directory)
+        #[serde(default)]
+        cwd: Option<String>,
+        /// Extra environment variables set for the command
+        #[serde(default)]
+        env: BTreeMap<String, String>,
+        /// Kill the command and report Inconclusive after this many seconds
+        #[serde(default)]
+        timeout_secs: Option<u64>,
+        /// Exit code that counts as success
+        #[serde(default)]
+        expected_exit_code: i32,
+        /// Substring that stdout must contain
+        #[serde(default)]
+        stdout_contains: Option<String>,
+        /// Regular expression that stdout must match
+        #[serde(default)]
+        stdout_matches: Option<String>,
+    },
+
+    /// A named test should run and pass. Unlike `CommandSucceeds` this reads
+    /// the harness output, so a filter matching no tests is Refuted.
+    TestPasses {
+        /// Test name, optionally qualified: `parse_args`, `cli::tests::parse_args`
+        name: String,
+        #[serde(default)]
+        runner: TestRunner,
+        /// Extra runner arguments, e.g. `["-p", "my-crate"]` or `["tests/"]`
+        #[serde(default)]
+        args: Vec<String>,
+        #[serde(default)]
+        cwd: Option<String>,
+        /// Kill the runner and report Inconclusive after this many seconds
+        #[serde(default)]
+        timeout_secs: Option<u64>,
+    },
 
     /// Git working directory should be clean (no uncommitted changes)
     GitClean {
@@ -209,6 +342,50 @@ pub enum EvidenceSpec {
         repo_path: Option<String>,
     },
 
+    /// A commit (or range like `main..HEAD`) should change the given path
+    FileChangedInCommit {
+        commit: String,
+        path: String,
+        #[serde(default)]
+        repo_path: Option<String>,
+    },
+
+    /// A commit (or range) should change every one of the given paths
+    CommitTouchesPaths {
+        commit: String,
+        paths: Vec<String>,
+        #[serde(default)]
+        repo_path: Option<String>,
+    },
+
+    /// A commit's (or range's) diff should add or remove a line matching a regex
+    CommitDiffMatches {
+        commit: String,
+        pattern: String,
+        #[serde(default)]
+        side: DiffSide,
+        /// Only consider changes to this path
+        #[serde(default)]
+        path: Option<String>,
+        #[serde(default)]
+        repo_path: Option<String>,
+    },
+
+    /// A commit message (any commit, for a range) should match a regex
+    CommitMessageMatches {
+        commit: String,
+        pattern: String,
+        #[serde(default)]
+        repo_path: Option<String>,
+    },
+
+    /// HEAD should be the given commit or descend from it
+    CommitIsAncestor {
+        commit: String,
+        #[serde(default)]
+        repo_path: Option<String>,
+    },
+
     /// File should have been modified after a given timestamp
     FileModifiedAfter {
         path: String,
@@ -219,19 +396,284 @@ pub enum EvidenceSpec {
     /// Environment variable should have expected value
     EnvVar { name: String, expected: String },
 
-    /// Custom predicate (for extensibility)
+    /// Nothing should exist at the given path (e.g. after a delete)
+    FileAbsent { path: String },
+
+    /// No directory should exist at the given path
+    DirectoryAbsent { path: String },
+
+    /// Git branch should not exist (e.g. after `git branch -d`)
+    GitBranchAbsent {
+        branch: String,
+        #[serde(default)]
+        repo_path: Option<String>,
+    },
+
+    /// Environment variable should not be set
+    EnvVarAbsent { name: String },
+
+    /// Every nested piece of evidence should hold
+    AllOf { evidence: Vec<EvidenceSpec> },
+
+    /// At least one nested piece of evidence should hold
+    AnyOf { evidence: Vec<EvidenceSpec> },
+
+    /// The nested evidence should NOT hold
+    Not { evidence: Box<EvidenceSpec> },
+
+    /// At least `n` nested pieces of evidence should hold
+    AtLeast {
+        n: usize,
+        evidence: Vec<EvidenceSpec>,
+    },
+
+    /// The nested evidence, with a weight and a `required` flag used when a
+    /// claim's top-level evidence is aggregated (see [`AggregationPolicy`]).
+    /// Its own verdict is the nested verdict. Only allowed at the top level
+    /// of a claim's evidence; nested anywhere else it is Unverifiable.
+    Weighted {
+        evidence: Box<EvidenceSpec>,
+        #[serde(default = "default_weight")]
+        weight: f64,
+        /// Under a non-strict policy, the claim cannot be Confirmed unless
+        /// this evidence is, and is Refuted if this evidence is
+        #[serde(default)]
+        required: bool,
+    },
+
+    /// Custom predicate checked by a registered [`checker::EvidenceChecker`]
     Custom {
         name: String,
-        params: HashMap<String, String>,
+        /// Checker-specific parameters, passed to the checker as-is; the
+        /// checker validates them itself
+        #[serde(default)]
+        params: serde_json::Value,
     },
 }
 
+impl EvidenceSpec {
+    /// Evidence nested directly inside this composite
+    fn nested(&self) -> Vec<&EvidenceSpec> {
+        match self {
+            EvidenceSpec::AllOf { evidence }
+            | EvidenceSpec::AnyOf { evidence }
+            | EvidenceSpec::AtLeast { evidence, .. } => evidence.iter().collect(),
+            EvidenceSpec::Not { evidence } | EvidenceSpec::Weighted { evidence, .. } => {
+                vec![evidence]
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// Whether a `Weighted` appears anywhere below this evidence, where its
+    /// weight would be ignored
+    fn has_nested_weighted(&self) -> bool {
+        self.nested()
+            .into_iter()
+            .any(|n| matches!(n, EvidenceSpec::Weighted { .. }) || n.has_nested_weighted())
+    }
+    /// The variant name, as used in the serialized `type` tag
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            EvidenceSpec::FileExists { .. } => "FileExists",
+            EvidenceSpec::FileWithHash { .. } => "FileWithHash",
+            EvidenceSpec::FileContains { .. } => "FileContains",
+            EvidenceSpec::FileMatchesRegex { .. } => "FileMatchesRegex",
+            EvidenceSpec::FileLineRange { .. } => "FileLineRange",
+            EvidenceSpec::FileUnifiedDiffApplied { .. } => "FileUnifiedDiffApplied",
+            EvidenceSpec::FileJsonPath { .. } => "FileJsonPath",
+            EvidenceSpec::FileStructuredPath { .. } => "FileStructuredPath",
+            EvidenceSpec::SymbolDefined { .. } => "SymbolDefined",
+            EvidenceSpec::DirectoryExists { .. } => "DirectoryExists",
+            EvidenceSpec::CommandSucceeds { .. } => "CommandSucceeds",
+            EvidenceSpec::TestPasses { .. } => "TestPasses",
+            EvidenceSpec::GitClean { .. } => "GitClean",
+            EvidenceSpec::GitCommitExists { .. } => "GitCommitExists",
+            EvidenceSpec::GitBranchExists { .. } => "GitBranchExists",
+            EvidenceSpec::FileChangedInCommit { .. } => "FileChangedInCommit",
+            EvidenceSpec::CommitTouchesPaths { .. } => "CommitTouchesPaths",
+            EvidenceSpec::CommitDiffMatches { .. } => "CommitDiffMatches",
+            EvidenceSpec::CommitMessageMatches { .. } => "CommitMessageMatches",
+            EvidenceSpec::CommitIsAncestor { .. } => "CommitIsAncestor",
+            EvidenceSpec::FileModifiedAfter { .. } => "FileModifiedAfter",
+            EvidenceSpec::EnvVar { .. } => "EnvVar",
+            EvidenceSpec::FileAbsent { .. } => "FileAbsent",
+            EvidenceSpec::DirectoryAbsent { .. } => "DirectoryAbsent",
+            EvidenceSpec::GitBranchAbsent { .. } => "GitBranchAbsent",
+            EvidenceSpec::EnvVarAbsent { .. } => "EnvVarAbsent",
+            EvidenceSpec::AllOf { .. } => "AllOf",
+            EvidenceSpec::AnyOf { .. } => "AnyOf",
+            EvidenceSpec::Not { .. } => "Not",
+            EvidenceSpec::AtLeast { .. } => "AtLeast",
+            EvidenceSpec::Weighted { .. } => "Weighted",
+            EvidenceSpec::Custom { .. } => "Custom",
+        }
+    }
+}
+
+/// Which lines of a diff `CommitDiffMatches` searches
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum DiffSide {
+    /// Added lines only
+    Added,
+    /// Removed lines only
+    Removed,
+    /// Both added and removed lines
+    #[default]
+    Any,
+}
+
+fn default_weight() -> f64 {
+    1.0
+}
+
+/// How the verdicts of a claim's top-level evidence combine into its
+/// overall verdict
+///
+/// Weights come from [`EvidenceSpec::Weighted`]; other evidence has weight
+/// one. The claim's confidence is the share of the total weight carried by
+/// Confirmed evidence, whichever policy is used.
+///
+/// ```rust
+/// use did_you_actually_do_that::{AggregationPolicy, Claim, EvidenceSpec, Verdict, Verifier};
+///
+/// let claim = Claim::new("Set up the workspace")
+///     .with_evidence(EvidenceSpec::DirectoryExists { path: "/".into() })
+///     .with_evidence(EvidenceSpec::DirectoryExists { path: "/tmp".into() })
+///     .with_evidence(EvidenceSpec::Custom { name: "unknown".into(), params: Default::default() });
+///
+/// let strict = Verifier::new().verify(&claim);
+/// assert_eq!(strict.overall_verdict, Verdict::Inconclusive);
+///
+/// let lenient = Verifier::new()
+///     .with_aggregation(AggregationPolicy::Majority)
+///     .verify(&claim);
+/// assert_eq!(lenient.overall_verdict, Verdict::Confirmed);
+/// assert!((lenient.confidence - 2.0 / 3.0).abs() < 1e-9);
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AggregationPolicy {
+    /// Every piece of evidence must be Confirmed, and any Refuted evidence
+    /// refutes the claim. Weights are ignored.
+    #[default]
+    Strict,
+    /// Confirmed evidence must carry more than half of the total weight
+    Majority,
+    /// Confirmed evidence must carry at least this share (above 0.0, up to
+    /// 1.0) of the total weight
+    WeightedThreshold(f64),
+}
+
+impl AggregationPolicy {
+    /// Overall verdict and confidence for a claim's top-level evidence results
+    pub fn aggregate(self, results: &[EvidenceResult]) -> (Verdict, f64) {
+        let verdict = match self {
+            AggregationPolicy::Strict => all_of(results),
+            AggregationPolicy::Majority => weighted(results, |share| share > 0.5),
+            AggregationPolicy::WeightedThreshold(threshold) => {
+                weighted(results, |share| share >= threshold)
+            }
+        };
+        (verdict, weight_share(results, |v| v == Verdict::Confirmed))
+    }
+}
+
+impl std::str::FromStr for AggregationPolicy {
+    type Err = String;
+
+    /// `strict`, `majority`, or `threshold=0.8`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "strict" => Ok(AggregationPolicy::Strict),
+            "majority" => Ok(AggregationPolicy::Majority),
+            _ => {
+                let threshold = s
+                    .strip_prefix("threshold=")
+                    .and_then(|t| t.parse::<f64>().ok())
+                    .filter(|t| *t > 0.0 && *t <= 1.0)
+                    .ok_or_else(|| {
+                        format!(
+                            "Unknown aggregation '{}' (expected strict, majority or threshold=T with 0 < T <= 1)",
+                            s
+                        )
+                    })?;
+                Ok(AggregationPolicy::WeightedThreshold(threshold))
+            }
+        }
+    }
+}
+
+/// Non-strict aggregation, where `passes` judges the Confirmed share of
+/// the weight. No share of 0 passes, whatever the threshold, so a claim
+/// with nothing Confirmed is never Confirmed.
+fn weighted(results: &[EvidenceResult], passes: impl Fn(f64) -> bool) -> Verdict {
+    let passes = |share: f64| share > 0.0 && passes(share);
+    let required = || results.iter().filter(|r| weight_of(r).1);
+
+    if results.iter().all(|r| r.verdict == Verdict::Unverifiable) {
+        Verdict::Unverifiable
+    } else if required().any(|r| r.verdict == Verdict::Refuted) {
+        Verdict::Refuted
+    } else if passes(weight_share(results, |v| v == Verdict::Confirmed)) {
+        if required().all(|r| r.verdict == Verdict::Confirmed) {
+            Verdict::Confirmed
+        } else {
+            Verdict::Inconclusive
+        }
+    } else if !passes(weight_share(results, |v| v != Verdict::Refuted)) {
+        // Not even confirming all undecided evidence would be enough
+        Verdict::Refuted
+    } else {
+        Verdict::Inconclusive
+    }
+}
+
+/// Share of the total weight carried by results whose verdict is kept
+fn weight_share(results: &[EvidenceResult], keep: impl Fn(Verdict) -> bool) -> f64 {
+    let total: f64 = results.iter().map(|r| weight_of(r).0).sum();
+    if total <= 0.0 {
+        return 0.0;
+    }
+    // Fold from +0.0: an empty float `sum` is -0.0, which prints as "-0%"
+    let kept = results
+        .iter()
+        .filter(|r| keep(r.verdict))
+        .map(|r| weight_of(r).0)
+        .fold(0.0, |acc, w| acc + w);
+    kept / total
+}
+
+/// Weight and `required` flag of a top-level evidence result
+fn weight_of(result: &EvidenceResult) -> (f64, bool) {
+    match &result.spec {
+        EvidenceSpec::Weighted {
+            weight, required, ..
+        } => (
+            if weight.is_finite() {
+                weight.max(0.0)
+            } else {
+                0.0
+            },
+            *required,
+        ),
+        _ => (1.0, false),
+    }
+}
+
 /// A claim that some action was performed
 ///
 /// Claims are the core unit of verification. Each claim has a description
 /// and a list of evidence specifications that should be true if the claim
 /// is accurate.
 ///
+/// Unless given one, a claim's id is its [`content_id`](Claim::content_id),
+/// so the same claim gets the same id on every run and history, baselines
+/// and reports can be matched up across runs. The builder methods keep a
+/// derived id in step with the content; [`IdScheme::Uuid7`] gives every
+/// claim a fresh id instead.
+///
 /// # Examples
 ///
 /// ```rust
@@ -253,10 +695,14 @@ pub enum EvidenceSpec {
 ///     })
 ///     .with_source("my-ai-assistant");
 /// ```
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+#[schemars(
+    description = "A claim that some action was performed, with the evidence that should hold if it was"
+)]
 pub struct Claim {
-    /// Unique identifier for this claim (auto-generated if not provided)
-    #[serde(default = "Claim::default_id")]
+    /// Identifier for this claim (derived from its description, evidence
+    /// and source if not provided)
+    #[serde(default)]
     pub id: String,
 
     /// Human-readable description of what was claimed
@@ -271,40 +717,221 @@ pub struct Claim {
 
     /// Optional context about who/what made the claim
     pub source: Option<String>,
+
+    /// Ids of claims that must hold for this one to be worth checking.
+    /// [`Verifier::verify_batch`] checks those first and skips this claim
+    /// if any of them is refuted.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<String>,
+
+    /// Known failures to mark as suppressed in SARIF reports, rather than
+    /// report as new problems
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub suppressions: Vec<Suppression>,
+}
+
+/// An accepted failure of some of a claim's evidence
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+pub struct Suppression {
+    /// SARIF rule ids this applies to (`file-exists`, `command-succeeds`,
+    /// ...); all of them if empty
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rules: Vec<String>,
+
+    /// Why the failure is acceptable
+    pub justification: String,
+}
+
+impl Suppression {
+    /// Whether this suppresses failures reported under `rule_id`
+    pub fn covers(&self, rule_id: &str) -> bool {
+        self.rules.is_empty() || self.rules.iter().any(|r| r == rule_id)
+    }
+}
+
+impl<'de> Deserialize<'de> for Claim {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        /// A claim as written, before a missing id is derived
+        #[derive(Deserialize)]
+        struct Written {
+            #[serde(default)]
+            id: Option<String>,
+            description: String,
+            #[serde(default = "Utc::now")]
+            timestamp: DateTime<Utc>,
+            evidence: Vec<EvidenceSpec>,
+            #[serde(default)]
+            source: Option<String>,
+            #[serde(default)]
+            depends_on: Vec<String>,
+            #[serde(default)]
+            suppressions: Vec<Suppression>,
+        }
+
+        let written = Written::deserialize(deserializer)?;
+        let mut claim = Claim {
+            id: String::new(),
+            description: written.description,
+            timestamp: written.timestamp,
+            evidence: written.evidence,
+            source: written.source,
+            depends_on: written.depends_on,
+            suppressions: written.suppressions,
+        };
+        claim.id = match written.id {
+            Some(id) => id,
+            None => claim.content_id(),
+        };
+        Ok(claim)
+    }
 }
 
 impl Claim {
     pub fn new(description: impl Into<String>) -> Self {
-        let description_string = description.into();
-        let claim_id = Self::generate_id(&description_string);
-        Self {
-            id: claim_id,
-            description: description_string,
+        let mut claim = Self {
+            id: String::new(),
+            description: description.into(),
             timestamp: Utc::now(),
             evidence: Vec::new(),
             source: None,
-        }
+            depends_on: Vec::new(),
+            suppressions: Vec::new(),
+        };
+        claim.id = claim.content_id();
+        claim
+    }
+
+    pub fn with_evidence(self, evidence: EvidenceSpec) -> Self {
+        self.change_content(|claim| claim.evidence.push(evidence))
+    }
+
+    pub fn with_source(self, source: impl Into<String>) -> Self {
+        self.change_content(|claim| claim.source = Some(source.into()))
+    }
+
+    /// Use `id` instead of a derived id
+    pub fn with_id(mut self, id: impl Into<String>) -> Self {
+        self.id = id.into();
+        self
+    }
+
+    /// Replace the id with one from `scheme`
+    pub fn with_id_scheme(mut self, scheme: IdScheme) -> Self {
+        self.id = scheme.id_for(&self);
+        self
+    }
+
+    /// A stable id for this claim: a hash of a canonical serialization of
+    /// its description, evidence and source. Timestamps, dependencies and
+    /// JSON formatting (key order, whitespace, omitted defaults) do not
+    /// affect it.
+    pub fn content_id(&self) -> String {
+        let content = serde_json::json!({
+            "description": self.description,
+            "evidence": self.evidence,
+            "source": self.source,
+        });
+        let mut canonical = String::new();
+        write_canonical(&content, &mut canonical);
+        hex::encode(&Sha256::digest(canonical.as_bytes())[..8])
     }
 
-    fn default_id() -> String {
-        Self::generate_id("unnamed-claim")
+    /// Apply `change`, keeping a derived id in step with the new content
+    fn change_content(mut self, change: impl FnOnce(&mut Self)) -> Self {
+        let derived = self.id == self.content_id();
+        change(&mut self);
+        if derived {
+            self.id = self.content_id();
+        }
+        self
     }
 
-    pub fn with_evidence(mut self, evidence: EvidenceSpec) -> Self {
-        self.evidence.push(evidence);
+    /// Make this claim depend on the claim with id `claim_id`
+    pub fn depends_on(mut self, claim_id: impl Into<String>) -> Self {
+        self.depends_on.push(claim_id.into());
         self
     }
 
-    pub fn with_source(mut self, source: impl Into<String>) -> Self {
-        self.source = Some(source.into());
+    /// Mark failures under the SARIF `rules` (all rules if empty) as
+    /// accepted, for `justification`
+    pub fn with_suppression(
+        mut self,
+        rules: impl IntoIterator<Item = impl Into<String>>,
+        justification: impl Into<String>,
+    ) -> Self {
+        self.suppressions.push(Suppression {
+            rules: rules.into_iter().map(Into::into).collect(),
+            justification: justification.into(),
+        });
         self
     }
+}
+
+/// JSON with object keys sorted and no whitespace
+pub(crate) fn write_canonical(value: &serde_json::Value, out: &mut String) {
+    match value {
+        serde_json::Value::Object(object) => {
+            let mut entries: Vec<_> = object.iter().collect();
+            entries.sort_by(|a, b| a.0.cmp(b.0));
+            out.push('{');
+            for (n, (key, item)) in entries.into_iter().enumerate() {
+                if n > 0 {
+                    out.push(',');
+                }
+                out.push_str(&serde_json::Value::String(key.clone()).to_string());
+                out.push(':');
+                write_canonical(item, out);
+            }
+            out.push('}');
+        }
+        serde_json::Value::Array(items) => {
+            out.push('[');
+            for (n, item) in items.iter().enumerate() {
+                if n > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, out);
+            }
+            out.push(']');
+        }
+        scalar => out.push_str(&scalar.to_string()),
+    }
+}
+
+/// How claims that are not given an id get one
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum IdScheme {
+    /// [`Claim::content_id`]: the same claim always gets the same id
+    #[default]
+    Content,
+    /// A fresh, time-ordered UUIDv7, so every verification of a claim is
+    /// a distinct event
+    Uuid7,
+}
+
+impl IdScheme {
+    /// An id for `claim` under this scheme
+    pub fn id_for(self, claim: &Claim) -> String {
+        match self {
+            IdScheme::Content => claim.content_id(),
+            IdScheme::Uuid7 => uuid::Uuid::now_v7().to_string(),
+        }
+    }
+}
 
-    fn generate_id(description: &str) -> String {
-        let mut hasher = Sha256::new();
-        hasher.update(description.as_bytes());
-        hasher.update(Utc::now().timestamp().to_le_bytes());
-        hex::encode(&hasher.finalize()[..8])
+impl std::str::FromStr for IdScheme {
+    type Err = String;
+
+    /// `content` or `uuid7`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "content" => Ok(IdScheme::Content),
+            "uuid7" | "uuidv7" => Ok(IdScheme::Uuid7),
+            other => Err(format!(
+                "Unknown id scheme '{}' (expected content or uuid7)",
+                other
+            )),
+        }
     }
 }
 
@@ -314,6 +941,15 @@ pub struct EvidenceResult {
     pub spec: EvidenceSpec,
     pub verdict: Verdict,
     pub details: Option<String>,
+    /// Results of nested evidence (only set for composite specs)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub children: Vec<EvidenceResult>,
+    /// Named supporting data produced by the checker
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub artifacts: BTreeMap<String, serde_json::Value>,
+    /// Where in the evidence's file the check matched, or nearly did
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub region: Option<Region>,
 }
 
 /// Complete verification report for a claim
@@ -322,7 +958,13 @@ pub struct VerificationReport {
     pub claim: Claim,
     pub evidence_results: Vec<EvidenceResult>,
     pub overall_verdict: Verdict,
+    /// Share of the evidence weight that was Confirmed, from 0.0 to 1.0
+    #[serde(default)]
+    pub confidence: f64,
     pub verified_at: DateTime<Utc>,
+    /// Why the claim's evidence was not checked, e.g. a dependency was refuted
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skipped: Option<String>,
 }
 
 impl VerificationReport {
@@ -367,9 +1009,11 @@ impl VerificationReport {
 ///
 /// ## Custom Checker
 ///
+/// Closures receive the `params` object flattened to strings. Implement
+/// [`checker::EvidenceChecker`] for typed parameters, details and artifacts.
+///
 /// ```rust
 /// use did_you_actually_do_that::{Claim, EvidenceSpec, Verifier, Verdict};
-/// use std::collections::HashMap;
 ///
 /// let mut verifier = Verifier::new();
 ///
@@ -387,25 +1031,47 @@ impl VerificationReport {
 ///     Ok(Verdict::Unverifiable)
 /// });
 ///
-/// let mut params = HashMap::new();
-/// params.insert("number".to_string(), "42".to_string());
-///
 /// let claim = Claim::new("Number is even")
 ///     .with_evidence(EvidenceSpec::Custom {
 ///         name: "is_even".to_string(),
-///         params,
+///         params: serde_json::json!({ "number": 42 }),
 ///     });
 ///
 /// let report = verifier.verify(&claim);
 /// assert_eq!(report.overall_verdict, Verdict::Confirmed);
 /// ```
+///
+/// ## Parallel Verification
+///
+/// ```rust
+/// use did_you_actually_do_that::{Claim, EvidenceSpec, Verifier};
+///
+/// let verifier = Verifier::new().with_jobs(4);
+/// let claims: Vec<Claim> = (0..8)
+///     .map(|i| {
+///         Claim::new(format!("Claim {}", i)).with_evidence(EvidenceSpec::DirectoryExists {
+///             path: "/tmp".to_string(),
+///         })
+///     })
+///     .collect();
+///
+/// // Reports come back in the same order as the claims
+/// let reports = verifier.verify_batch(&claims).unwrap();
+/// assert_eq!(reports[3].claim.description, "Claim 3");
+/// ```
 pub struct Verifier {
     /// Custom evidence checkers for extensibility
-    #[allow(clippy::type_complexity)]
-    custom_checkers: HashMap<
-        String,
-        Box<dyn Fn(&HashMap<String, String>) -> Result<Verdict, VerificationError>>,
-    >,
+    custom_checkers: HashMap<String, Box<dyn EvidenceChecker>>,
+    /// Maximum number of worker threads used for verification
+    jobs: usize,
+    /// Which programs command-running evidence may start
+    policy: ExecutionPolicy,
+    /// Where verified reports are recorded, if anywhere
+    history: Option<HistoryStore>,
+    /// Parsers for `SymbolDefined`, later registrations first
+    source_parsers: Vec<Box<dyn SourceParser>>,
+    /// How evidence verdicts combine into a claim's verdict
+    aggregation: AggregationPolicy,
 }
 
 impl Default for Verifier {
@@ -418,19 +1084,197 @@ impl Verifier {
     pub fn new() -> Self {
         Self {
             custom_checkers: HashMap::new(),
+            jobs: 1,
+            policy: ExecutionPolicy::default(),
+            history: None,
+            source_parsers: vec![Box::new(RustParser)],
+            aggregation: AggregationPolicy::default(),
+        }
+    }
+
+    /// Combine evidence verdicts with `policy` instead of requiring all
+    pub fn with_aggregation(mut self, policy: AggregationPolicy) -> Self {
+        self.aggregation = policy;
+        self
+    }
+
+    /// How evidence verdicts combine into a claim's verdict
+    pub fn aggregation(&self) -> AggregationPolicy {
+        self.aggregation
+    }
+
+    /// Record every verified claim in `store`
+    pub fn with_history(mut self, store: HistoryStore) -> Self {
+        self.history = Some(store);
+        self
+    }
+
+    /// The history store reports are recorded in, if any
+    pub fn history(&self) -> Option<&HistoryStore> {
+        self.history.as_ref()
+    }
+
+    /// Restrict which programs evidence checks may execute
+    pub fn with_policy(mut self, policy: ExecutionPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// The execution policy applied to command-running evidence
+    pub fn policy(&self) -> &ExecutionPolicy {
+        &self.policy
+    }
+
+    /// Unverifiable result for evidence that was not checked: refused by
+    /// the execution policy, or malformed
+    fn blocked(&self, evidence: &EvidenceSpec, reason: String) -> EvidenceResult {
+        EvidenceResult {
+            spec: evidence.clone(),
+            verdict: Verdict::Unverifiable,
+            details: Some(reason),
+            children: Vec::new(),
+            artifacts: BTreeMap::new(),
+            region: None,
+        }
+    }
+
+    /// Run git under the execution policy
+    ///
+    /// Errors carry the details to report: either the policy's reason for
+    /// blocking git, or why it could not be started.
+    fn git(&self, args: &[&str]) -> Result<Output, String> {
+        git::run_cli(&self.policy, args)
+    }
+
+    /// Backend for repository queries: libgit2 if built in, else the CLI
+    fn git_backend(&self) -> Box<dyn git::GitBackend + '_> {
+        #[cfg(feature = "libgit2")]
+        return Box::new(git::Libgit2Backend::new(&self.policy));
+        #[cfg(not(feature = "libgit2"))]
+        Box::new(git::CliBackend::new(&self.policy))
+    }
+
+    /// Run `git -C repo args` and return stdout. A failing git command is
+    /// Refuted (bad revision) unless `repo` is not a repository at all.
+    fn git_stdout(&self, repo: &str, args: &[&str]) -> Result<String, (Verdict, String)> {
+        let mut full = vec!["-C", repo];
+        full.extend_from_slice(args);
+        let output = self
+            .git(&full)
+            .map_err(|reason| (Verdict::Unverifiable, reason))?;
+
+        if output.status.success() {
+            Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+        } else {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            let message = stderr.lines().next().unwrap_or("git failed").to_string();
+            if stderr.contains("not a git repository") {
+                Err((Verdict::Unverifiable, message))
+            } else {
+                Err((Verdict::Refuted, message))
+            }
         }
     }
 
-    /// Register a custom evidence checker
+    /// Diffstat of the changes made by `commit`, limited to `pathspecs`
+    fn git_diffstat(
+        &self,
+        repo: &str,
+        commit: &str,
+        pathspecs: &[&str],
+    ) -> Result<git::DiffStat, (Verdict, String)> {
+        git::check_revision(commit).map_err(|e| (Verdict::Unverifiable, e))?;
+        let mut args = git::changes_args(commit).to_vec();
+        args.extend(["--numstat", "-z", "--no-renames", "--"]);
+        args.extend_from_slice(pathspecs);
+        self.git_stdout(repo, &args)
+            .map(|out| git::DiffStat::parse_numstat(&out))
+    }
+
+    /// Set the number of worker threads (0 = one per available CPU)
+    pub fn with_jobs(mut self, jobs: usize) -> Self {
+        self.jobs = if jobs == 0 {
+            std::thread::available_parallelism()
+                .map(|n| n.get())
+                .unwrap_or(1)
+        } else {
+            jobs
+        };
+        self
+    }
+
+    /// Number of worker threads used for verification
+    pub fn jobs(&self) -> usize {
+        self.jobs
+    }
+
+    /// Register a custom evidence checker from a closure
     pub fn register_checker<F>(&mut self, name: impl Into<String>, checker: F)
     where
-        F: Fn(&HashMap<String, String>) -> Result<Verdict, VerificationError> + 'static,
+        F: Fn(&HashMap<String, String>) -> Result<Verdict, VerificationError>
+            + Send
+            + Sync
+            + 'static,
     {
-        self.custom_checkers.insert(name.into(), Box::new(checker));
+        let name = name.into();
+        self.custom_checkers.insert(
+            name.clone(),
+            Box::new(FnChecker {
+                name,
+                func: checker,
+            }),
+        );
+    }
+
+    /// Register a custom evidence checker under its own name
+    pub fn register(&mut self, checker: impl EvidenceChecker + 'static) {
+        self.custom_checkers
+            .insert(checker.name().to_string(), Box::new(checker));
+    }
+
+    /// Register a parser for `SymbolDefined`, taking precedence over any
+    /// existing parser for the same language or extensions
+    pub fn register_source_parser(&mut self, parser: impl SourceParser + 'static) {
+        self.source_parsers.insert(0, Box::new(parser));
+    }
+
+    /// The parser for `language`, or for the extension of `path`
+    fn source_parser(&self, path: &str, language: Option<&str>) -> Option<&dyn SourceParser> {
+        let extension = Path::new(path).extension().and_then(|e| e.to_str());
+        self.source_parsers
+            .iter()
+            .find(|p| match language {
+                Some(language) => p.language().eq_ignore_ascii_case(language),
+                None => extension.is_some_and(|ext| p.extensions().contains(&ext)),
+            })
+            .map(|p| p.as_ref())
+    }
+
+    /// Look up a registered custom checker by name
+    pub fn checker(&self, name: &str) -> Option<&dyn EvidenceChecker> {
+        self.custom_checkers.get(name).map(|c| c.as_ref())
+    }
+
+    /// All registered custom checkers, sorted by name
+    pub fn custom_checkers(&self) -> Vec<&dyn EvidenceChecker> {
+        let mut checkers: Vec<&dyn EvidenceChecker> =
+            self.custom_checkers.values().map(|c| c.as_ref()).collect();
+        checkers.sort_by(|a, b| a.name().cmp(b.name()));
+        checkers
     }
 
     /// Verify a single piece of evidence
     pub fn check_evidence(&self, evidence: &EvidenceSpec) -> EvidenceResult {
+        if evidence.has_nested_weighted() {
+            let error = VerificationError::InvalidClaim(
+                "Weighted evidence is only allowed at the top level of a claim".to_string(),
+            );
+            return self.blocked(evidence, error.to_string());
+        }
+
+        let mut children = Vec::new();
+        let mut artifacts = BTreeMap::new();
+        let mut region = None;
         let (verdict, details) = match evidence {
             EvidenceSpec::FileExists { path } => {
                 if Path::new(path).exists() {
@@ -461,25 +1305,32 @@ impl Verifier {
             },
 
             EvidenceSpec::FileContains { path, substring } => match std::fs::read_to_string(path) {
-                Ok(contents) => {
-                    if contents.contains(substring) {
+                Ok(contents) => match contents.find(substring.as_str()) {
+                    Some(at) => {
+                        region = Some(Region::from_offsets(&contents, at, at + substring.len()));
                         (Verdict::Confirmed, Some("Substring found".to_string()))
-                    } else {
+                    }
+                    None => {
+                        region = Region::closest_line(&contents, substring);
                         (Verdict::Refuted, Some("Substring not found".to_string()))
                     }
-                }
+                },
                 Err(e) => (Verdict::Refuted, Some(format!("Cannot read file: {}", e))),
             },
 
             EvidenceSpec::FileMatchesRegex { path, pattern } => match Regex::new(pattern) {
                 Ok(re) => match std::fs::read_to_string(path) {
-                    Ok(contents) => {
-                        if re.is_match(&contents) {
+                    Ok(contents) => match re.find(&contents) {
+                        Some(found) => {
+                            region =
+                                Some(Region::from_offsets(&contents, found.start(), found.end()));
                             (Verdict::Confirmed, Some("Pattern matched".to_string()))
-                        } else {
+                        }
+                        None => {
+                            region = Region::closest_to_pattern(&contents, pattern);
                             (Verdict::Refuted, Some("Pattern not matched".to_string()))
                         }
-                    }
+                    },
                     Err(e) => (Verdict::Refuted, Some(format!("Cannot read file: {}", e))),
                 },
                 Err(e) => (
@@ -492,30 +1343,124 @@ impl Verifier {
                 path,
                 json_path,
                 expected,
+                op,
             } => match std::fs::read_to_string(path) {
                 Ok(contents) => match serde_json::from_str::<serde_json::Value>(&contents) {
-                    Ok(json) => match extract_json_path(&json, json_path) {
-                        Some(actual) => {
-                            if actual == expected {
-                                (Verdict::Confirmed, Some("JSON path matches".to_string()))
-                            } else {
-                                (
-                                    Verdict::Refuted,
-                                    Some(format!(
-                                        "JSON path mismatch: expected {:?}, got {:?}",
-                                        expected, actual
-                                    )),
-                                )
-                            }
+                    Ok(json) => query::check_document(&json, json_path, *op, expected),
+                    Err(e) => (Verdict::Refuted, Some(format!("Invalid JSON: {}", e))),
+                },
+                Err(e) => (Verdict::Refuted, Some(format!("Cannot read file: {}", e))),
+            },
+
+            EvidenceSpec::FileStructuredPath {
+                path,
+                query,
+                format,
+                expected,
+                op,
+            } => match format.or_else(|| ConfigFormat::detect(path)) {
+                Some(format) => match std::fs::read_to_string(path) {
+                    Ok(contents) => match format.parse(&contents) {
+                        Ok(document) => query::check_document(&document, query, *op, expected),
+                        Err(e) => (Verdict::Refuted, Some(format!("Invalid {}: {}", format, e))),
+                    },
+                    Err(e) => (Verdict::Refuted, Some(format!("Cannot read file: {}", e))),
+                },
+                None => (
+                    Verdict::Unverifiable,
+                    Some(format!(
+                        "Cannot detect the format of {}; set `format`",
+                        path
+                    )),
+                ),
+            },
+
+            EvidenceSpec::FileLineRange {
+                path,
+                start,
+                end,
+                expected,
+            } => match std::fs::read_to_string(path) {
+                Ok(text) => {
+                    if *start > 0 {
+                        let last = patch::line_range_end(*start, *end, expected);
+                        region = Some(Region::lines(*start, last));
+                    }
+                    patch::check_line_range(&text, *start, *end, expected)
+                }
+                Err(e) => (Verdict::Refuted, Some(format!("Cannot read file: {}", e))),
+            },
+
+            EvidenceSpec::FileUnifiedDiffApplied { path, diff } => {
+                match std::fs::read_to_string(path) {
+                    Ok(text) => patch::check_diff_applied(&text, diff),
+                    Err(e) => (Verdict::Refuted, Some(format!("Cannot read file: {}", e))),
+                }
+            }
+
+            EvidenceSpec::SymbolDefined {
+                path,
+                symbol,
+                kind,
+                language,
+            } => match self.source_parser(path, language.as_deref()) {
+                Some(parser) => match std::fs::read_to_string(path) {
+                    Ok(source) => match parser.symbols(&source) {
+                        Ok(symbols) => {
+                            let named: Vec<_> =
+                                symbols.iter().filter(|s| s.matches(symbol)).collect();
+                            let found = named.iter().find(|s| kind.is_none_or(|k| s.kind == k));
+                            region = found
+                                .or(named.first())
+                                .and_then(|s| s.line)
+                                .map(|line| Region::lines(line, line));
+                            match found {
+                                Some(found) => (
+                                    Verdict::Confirmed,
+                                    Some(format!(
+                                        "Found {} {} at {}:{}",
+                                        found.kind,
+                                        found.qualified_name(),
+                                        path,
+                                        found.line.map_or("?".to_string(), |l| l.to_string())
+                                    )),
+                                ),
+                                None => match (named.first(), kind) {
+                                    (Some(other), Some(kind)) => (
+                                        Verdict::Refuted,
+                                        Some(format!(
+                                            "{} is a {}, expected {}",
+                                            other.qualified_name(),
+                                            other.kind,
+                                            kind
+                                        )),
+                                    ),
+                                    _ => (
+                                        Verdict::Refuted,
+                                        Some(format!("Symbol {} not defined in {}", symbol, path)),
+                                    ),
+                                },
+                            }
                         }
-                        None => (
+                        Err(e) => (
                             Verdict::Refuted,
-                            Some(format!("JSON path not found: {}", json_path)),
+                            Some(format!(
+                                "Cannot parse {} as {}: {}",
+                                path,
+                                parser.language(),
+                                e
+                            )),
                         ),
                     },
-                    Err(e) => (Verdict::Refuted, Some(format!("Invalid JSON: {}", e))),
+                    Err(e) => (Verdict::Refuted, Some(format!("Cannot read file: {}", e))),
                 },
-                Err(e) => (Verdict::Refuted, Some(format!("Cannot read file: {}", e))),
+                None => (
+                    Verdict::Unverifiable,
+                    Some(format!(
+                        "No source parser for {}",
+                        language.as_deref().unwrap_or(path)
+                    )),
+                ),
             },
 
             EvidenceSpec::DirectoryExists { path } => {
@@ -533,126 +1478,406 @@ impl Verifier {
                 }
             }
 
-            EvidenceSpec::CommandSucceeds { command, args } => {
-                match Command::new(command).args(args).output() {
-                    Ok(output) => {
-                        if output.status.success() {
-                            (Verdict::Confirmed, Some("Command succeeded".to_string()))
-                        } else {
-                            (
-                                Verdict::Refuted,
-                                Some(format!(
-                                    "Command failed with exit code: {:?}",
-                                    output.status.code()
-                                )),
-                            )
+            EvidenceSpec::CommandSucceeds {
+                command,
+                args,
+                cwd,
+                env,
+                timeout_secs,
+                expected_exit_code,
+                stdout_contains,
+                stdout_matches,
+            } => {
+                let mut cmd = match self.policy.command(command) {
+                    Ok(cmd) => cmd,
+                    Err(reason) => return self.blocked(evidence, reason),
+                };
+                cmd.args(args).envs(env);
+                if let Some(dir) = cwd {
+                    cmd.current_dir(dir);
+                }
+
+                match exec::run(&mut cmd, timeout_secs.map(Duration::from_secs)) {
+                    Ok(run) => {
+                        for (name, output) in [("stdout", &run.stdout), ("stderr", &run.stderr)] {
+                            if !output.is_empty() {
+                                artifacts.insert(
+                                    name.to_string(),
+                                    serde_json::Value::String(exec::tail(
+                                        output,
+                                        exec::OUTPUT_TAIL_BYTES,
+                                    )),
+                                );
+                            }
                         }
+                        check_command_run(
+                            &run,
+                            *timeout_secs,
+                            *expected_exit_code,
+                            stdout_contains.as_deref(),
+                            stdout_matches.as_deref(),
+                        )
                     }
+                    // With isolation a spawn failure may come from setting up
+                    // the sandbox, which says nothing about the claim itself
+                    Err(e) if self.policy.isolation().is_some() => (
+                        Verdict::Unverifiable,
+                        Some(format!("Command could not be started in isolation: {}", e)),
+                    ),
                     Err(e) => (Verdict::Refuted, Some(format!("Command error: {}", e))),
                 }
             }
 
-            EvidenceSpec::GitClean { repo_path } => {
-                let path = repo_path.as_deref().unwrap_or(".");
-                match Command::new("git")
-                    .args(["-C", path, "status", "--porcelain"])
-                    .output()
-                {
-                    Ok(output) => {
-                        if output.status.success() {
-                            let stdout = String::from_utf8_lossy(&output.stdout);
-                            if stdout.trim().is_empty() {
-                                (
-                                    Verdict::Confirmed,
-                                    Some("Working directory is clean".to_string()),
-                                )
-                            } else {
-                                (
-                                    Verdict::Refuted,
-                                    Some(format!("Uncommitted changes:\n{}", stdout.trim())),
-                                )
+            EvidenceSpec::TestPasses {
+                name,
+                runner,
+                args,
+                cwd,
+                timeout_secs,
+            } => {
+                let mut cmd = match self.policy.command(runner.program()) {
+                    Ok(cmd) => cmd,
+                    Err(reason) => return self.blocked(evidence, reason),
+                };
+                // Removed, with the report, at the end of this arm
+                let report_dir = match harness::report_dir() {
+                    Ok(dir) => dir,
+                    Err(e) => {
+                        return self.blocked(
+                            evidence,
+                            format!("Cannot create a directory for the test report: {}", e),
+                        )
+                    }
+                };
+                let report = report_dir.path().join(harness::REPORT_FILE);
+                cmd.args(runner.args(name, args, &report));
+                if let Some(dir) = cwd {
+                    cmd.current_dir(dir);
+                }
+
+                let checked = match exec::run(&mut cmd, timeout_secs.map(Duration::from_secs)) {
+                    Ok(run) => match runner.outcomes(&run, &report) {
+                        Ok(outcomes) => {
+                            let matching: Vec<_> =
+                                outcomes.iter().filter(|t| t.matches(name)).collect();
+                            artifacts.insert("tests".to_string(), serde_json::json!(matching));
+                            if !run.stderr.is_empty() {
+                                artifacts.insert(
+                                    "stderr".to_string(),
+                                    serde_json::Value::String(exec::tail(
+                                        &run.stderr,
+                                        exec::OUTPUT_TAIL_BYTES,
+                                    )),
+                                );
                             }
-                        } else {
-                            (
-                                Verdict::Refuted,
-                                Some("Not a git repository or git error".to_string()),
-                            )
+                            harness::judge(*runner, name, &run, *timeout_secs, &outcomes)
                         }
-                    }
+                        Err(e) => (Verdict::Refuted, Some(e)),
+                    },
                     Err(e) => (
                         Verdict::Unverifiable,
-                        Some(format!("Git not available: {}", e)),
+                        Some(format!("Cannot run {}: {}", runner.program(), e)),
                     ),
+                };
+                drop(report_dir);
+                checked
+            }
+
+            EvidenceSpec::GitClean { repo_path } => {
+                let path = repo_path.as_deref().unwrap_or(".");
+                match self.git_backend().status(path) {
+                    Ok(entries) if entries.is_empty() => (
+                        Verdict::Confirmed,
+                        Some("Working directory is clean".to_string()),
+                    ),
+                    Ok(entries) => {
+                        let lines: Vec<String> = entries
+                            .iter()
+                            .map(|e| format!("{} {}", e.code, e.path))
+                            .collect();
+                        (
+                            Verdict::Refuted,
+                            Some(format!("Uncommitted changes:\n{}", lines.join("\n"))),
+                        )
+                    }
+                    Err(git::GitError::Failed) => (
+                        Verdict::Refuted,
+                        Some("Not a git repository or git error".to_string()),
+                    ),
+                    Err(git::GitError::Unavailable(reason)) => {
+                        (Verdict::Unverifiable, Some(reason))
+                    }
                 }
             }
 
             EvidenceSpec::GitCommitExists { commit, repo_path } => {
                 let path = repo_path.as_deref().unwrap_or(".");
-                match Command::new("git")
-                    .args(["-C", path, "cat-file", "-t", commit])
-                    .output()
-                {
-                    Ok(output) => {
-                        if output.status.success() {
-                            let obj_type = String::from_utf8_lossy(&output.stdout);
-                            if obj_type.trim() == "commit" {
+                match self.git_backend().object_type(path, commit) {
+                    Ok(Some(kind)) if kind == "commit" => (
+                        Verdict::Confirmed,
+                        Some(format!("Commit {} exists", commit)),
+                    ),
+                    Ok(Some(kind)) => (
+                        Verdict::Refuted,
+                        Some(format!("{} is a {}, not a commit", commit, kind)),
+                    ),
+                    Ok(None) | Err(git::GitError::Failed) => (
+                        Verdict::Refuted,
+                        Some(format!("Commit {} not found", commit)),
+                    ),
+                    Err(git::GitError::Unavailable(reason)) => {
+                        (Verdict::Unverifiable, Some(reason))
+                    }
+                }
+            }
+
+            EvidenceSpec::GitBranchExists { branch, repo_path } => {
+                let path = repo_path.as_deref().unwrap_or(".");
+                match self.git_backend().branch_exists(path, branch) {
+                    Ok(true) => (
+                        Verdict::Confirmed,
+                        Some(format!("Branch {} exists", branch)),
+                    ),
+                    Ok(false) | Err(git::GitError::Failed) => (
+                        Verdict::Refuted,
+                        Some(format!("Branch {} not found", branch)),
+                    ),
+                    Err(git::GitError::Unavailable(reason)) => {
+                        (Verdict::Unverifiable, Some(reason))
+                    }
+                }
+            }
+
+            EvidenceSpec::FileChangedInCommit {
+                commit,
+                path,
+                repo_path,
+            } => {
+                let repo = repo_path.as_deref().unwrap_or(".");
+                match self.git_diffstat(repo, commit, &[path]) {
+                    Ok(stat) if stat.is_empty() => (
+                        Verdict::Refuted,
+                        Some(format!("{} does not change {}", commit, path)),
+                    ),
+                    Ok(stat) => (
+                        Verdict::Confirmed,
+                        Some(format!("{} changes {}: {}", commit, path, stat.summary())),
+                    ),
+                    Err((verdict, reason)) => (verdict, Some(reason)),
+                }
+            }
+
+            EvidenceSpec::CommitTouchesPaths {
+                commit,
+                paths,
+                repo_path,
+            } => {
+                let repo = repo_path.as_deref().unwrap_or(".");
+                let mut untouched = Vec::new();
+                let mut failure = None;
+                for path in paths {
+                    match self.git_diffstat(repo, commit, &[path]) {
+                        Ok(stat) if stat.is_empty() => untouched.push(path.as_str()),
+                        Ok(_) => {}
+                        Err(err) => {
+                            failure = Some(err);
+                            break;
+                        }
+                    }
+                }
+
+                if let Some((verdict, reason)) = failure {
+                    (verdict, Some(reason))
+                } else if paths.is_empty() {
+                    (Verdict::Unverifiable, Some("No paths given".to_string()))
+                } else {
+                    let refs: Vec<&str> = paths.iter().map(String::as_str).collect();
+                    let summary = self
+                        .git_diffstat(repo, commit, &refs)
+                        .map(|stat| stat.summary())
+                        .unwrap_or_default();
+                    if untouched.is_empty() {
+                        (
+                            Verdict::Confirmed,
+                            Some(format!(
+                                "{} touches all {} paths: {}",
+                                commit,
+                                paths.len(),
+                                summary
+                            )),
+                        )
+                    } else {
+                        (
+                            Verdict::Refuted,
+                            Some(format!(
+                                "{} does not touch {}; {}",
+                                commit,
+                                untouched.join(", "),
+                                summary
+                            )),
+                        )
+                    }
+                }
+            }
+
+            EvidenceSpec::CommitDiffMatches {
+                commit,
+                pattern,
+                side,
+                path,
+                repo_path,
+            } => match Regex::new(pattern) {
+                Ok(re) => {
+                    let repo = repo_path.as_deref().unwrap_or(".");
+                    let pathspecs: Vec<&str> = path.iter().map(String::as_str).collect();
+                    let patch = git::check_revision(commit)
+                        .map_err(|e| (Verdict::Unverifiable, e))
+                        .and_then(|_| {
+                            let mut args = git::changes_args(commit).to_vec();
+                            args.extend(["--patch", "--no-color", "--no-ext-diff", "-U0", "--"]);
+                            args.extend_from_slice(&pathspecs);
+                            self.git_stdout(repo, &args)
+                        });
+
+                    match patch {
+                        Ok(patch) => {
+                            let matches: Vec<&str> = git::changed_lines(&patch, *side)
+                                .into_iter()
+                                .filter(|line| re.is_match(line))
+                                .collect();
+                            let summary = self
+                                .git_diffstat(repo, commit, &pathspecs)
+                                .map(|stat| stat.summary())
+                                .unwrap_or_default();
+                            if matches.is_empty() {
                                 (
-                                    Verdict::Confirmed,
-                                    Some(format!("Commit {} exists", commit)),
+                                    Verdict::Refuted,
+                                    Some(format!(
+                                        "No changed line in {} matches /{}/; {}",
+                                        commit, pattern, summary
+                                    )),
                                 )
                             } else {
+                                let shown: Vec<&str> = matches.iter().take(10).copied().collect();
+                                artifacts.insert("matches".to_string(), serde_json::json!(shown));
                                 (
-                                    Verdict::Refuted,
+                                    Verdict::Confirmed,
                                     Some(format!(
-                                        "{} is a {}, not a commit",
+                                        "{} changed line(s) in {} match /{}/; {}",
+                                        matches.len(),
                                         commit,
-                                        obj_type.trim()
+                                        pattern,
+                                        summary
                                     )),
                                 )
                             }
-                        } else {
-                            (
-                                Verdict::Refuted,
-                                Some(format!("Commit {} not found", commit)),
-                            )
                         }
+                        Err((verdict, reason)) => (verdict, Some(reason)),
                     }
-                    Err(e) => (
-                        Verdict::Unverifiable,
-                        Some(format!("Git not available: {}", e)),
-                    ),
                 }
-            }
+                Err(e) => (
+                    Verdict::Unverifiable,
+                    Some(format!("Invalid regex pattern: {}", e)),
+                ),
+            },
 
-            EvidenceSpec::GitBranchExists { branch, repo_path } => {
-                let path = repo_path.as_deref().unwrap_or(".");
-                match Command::new("git")
-                    .args([
-                        "-C",
-                        path,
-                        "rev-parse",
-                        "--verify",
-                        &format!("refs/heads/{}", branch),
-                    ])
-                    .output()
-                {
-                    Ok(output) => {
-                        if output.status.success() {
+            EvidenceSpec::CommitMessageMatches {
+                commit,
+                pattern,
+                repo_path,
+            } => match Regex::new(pattern) {
+                Ok(re) => {
+                    let repo = repo_path.as_deref().unwrap_or(".");
+                    let log = git::check_revision(commit)
+                        .map_err(|e| (Verdict::Unverifiable, e))
+                        .and_then(|_| {
+                            let count = if git::is_range(commit) {
+                                "--max-count=-1"
+                            } else {
+                                "-1"
+                            };
+                            self.git_stdout(repo, &["log", count, "--format=%B%x00", commit])
+                        });
+
+                    match log {
+                        Ok(log) => {
+                            let summary = self
+                                .git_diffstat(repo, commit, &[])
+                                .map(|stat| stat.summary())
+                                .unwrap_or_default();
+                            let matching = log
+                                .split('\0')
+                                .map(str::trim)
+                                .find(|message| re.is_match(message));
+                            match matching {
+                                Some(message) => (
+                                    Verdict::Confirmed,
+                                    Some(format!(
+                                        "Message \"{}\" matches /{}/; {}",
+                                        message.lines().next().unwrap_or(""),
+                                        pattern,
+                                        summary
+                                    )),
+                                ),
+                                None => (
+                                    Verdict::Refuted,
+                                    Some(format!(
+                                        "No commit message in {} matches /{}/; {}",
+                                        commit, pattern, summary
+                                    )),
+                                ),
+                            }
+                        }
+                        Err((verdict, reason)) => (verdict, Some(reason)),
+                    }
+                }
+                Err(e) => (
+                    Verdict::Unverifiable,
+                    Some(format!("Invalid regex pattern: {}", e)),
+                ),
+            },
+
+            EvidenceSpec::CommitIsAncestor { commit, repo_path } => {
+                let repo = repo_path.as_deref().unwrap_or(".");
+                let checked = git::check_revision(commit);
+                match checked.and_then(|_| {
+                    self.git(&["-C", repo, "merge-base", "--is-ancestor", commit, "HEAD"])
+                }) {
+                    Ok(output) => match output.status.code() {
+                        Some(0) => {
+                            let range = format!("{}..HEAD", commit);
+                            let summary = self
+                                .git_diffstat(repo, &range, &[])
+                                .map(|stat| stat.summary())
+                                .unwrap_or_default();
                             (
                                 Verdict::Confirmed,
-                                Some(format!("Branch {} exists", branch)),
-                            )
-                        } else {
-                            (
-                                Verdict::Refuted,
-                                Some(format!("Branch {} not found", branch)),
+                                Some(format!(
+                                    "HEAD descends from {}; since then: {}",
+                                    commit, summary
+                                )),
                             )
                         }
-                    }
-                    Err(e) => (
-                        Verdict::Unverifiable,
-                        Some(format!("Git not available: {}", e)),
-                    ),
+                        Some(1) => (
+                            Verdict::Refuted,
+                            Some(format!("HEAD does not descend from {}", commit)),
+                        ),
+                        _ => {
+                            let stderr = String::from_utf8_lossy(&output.stderr);
+                            if stderr.contains("not a git repository") {
+                                (
+                                    Verdict::Unverifiable,
+                                    Some("Not a git repository or git error".to_string()),
+                                )
+                            } else {
+                                (
+                                    Verdict::Refuted,
+                                    Some(format!("Commit {} not found", commit)),
+                                )
+                            }
+                        }
+                    },
+                    Err(reason) => (Verdict::Unverifiable, Some(reason)),
                 }
             }
 
@@ -708,10 +1933,150 @@ impl Verifier {
                 ),
             },
 
+            EvidenceSpec::FileAbsent { path } => match std::fs::symlink_metadata(path) {
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                    (Verdict::Confirmed, Some(format!("File absent: {}", path)))
+                }
+                Err(e) => (
+                    Verdict::Unverifiable,
+                    Some(format!("Cannot check {}: {}", path, e)),
+                ),
+                Ok(meta) if meta.is_dir() => (
+                    Verdict::Refuted,
+                    Some(format!("Path still exists as a directory: {}", path)),
+                ),
+                Ok(_) => (
+                    Verdict::Refuted,
+                    Some(format!("File still exists: {}", path)),
+                ),
+            },
+
+            EvidenceSpec::DirectoryAbsent { path } => match std::fs::metadata(path) {
+                Ok(meta) if meta.is_dir() => (
+                    Verdict::Refuted,
+                    Some(format!("Directory still exists: {}", path)),
+                ),
+                Ok(_) => (
+                    Verdict::Confirmed,
+                    Some(format!("Directory absent (a file exists at {})", path)),
+                ),
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => (
+                    Verdict::Confirmed,
+                    Some(format!("Directory absent: {}", path)),
+                ),
+                Err(e) => (
+                    Verdict::Unverifiable,
+                    Some(format!("Cannot check {}: {}", path, e)),
+                ),
+            },
+
+            EvidenceSpec::GitBranchAbsent { branch, repo_path } => {
+                let path = repo_path.as_deref().unwrap_or(".");
+                match self.git_backend().branch_exists(path, branch) {
+                    Ok(true) => (
+                        Verdict::Refuted,
+                        Some(format!("Branch {} still exists", branch)),
+                    ),
+                    Ok(false) => (
+                        Verdict::Confirmed,
+                        Some(format!("Branch {} does not exist", branch)),
+                    ),
+                    Err(git::GitError::Failed) => (
+                        Verdict::Unverifiable,
+                        Some("Not a git repository or git error".to_string()),
+                    ),
+                    Err(git::GitError::Unavailable(reason)) => {
+                        (Verdict::Unverifiable, Some(reason))
+                    }
+                }
+            }
+
+            EvidenceSpec::EnvVarAbsent { name } => match std::env::var_os(name) {
+                None => (
+                    Verdict::Confirmed,
+                    Some(format!("Environment variable {} not set", name)),
+                ),
+                Some(_) => (
+                    Verdict::Refuted,
+                    Some(format!("Environment variable {} is set", name)),
+                ),
+            },
+
+            EvidenceSpec::AllOf { evidence: nested } => {
+                children = nested.iter().map(|e| self.check_evidence(e)).collect();
+                (
+                    all_of(&children),
+                    Some(format!(
+                        "{} of {} nested checks confirmed",
+                        count_verdict(&children, Verdict::Confirmed),
+                        children.len()
+                    )),
+                )
+            }
+
+            EvidenceSpec::AnyOf { evidence: nested } => {
+                children = nested.iter().map(|e| self.check_evidence(e)).collect();
+                (
+                    any_of(&children),
+                    Some(format!(
+                        "{} of {} nested checks confirmed",
+                        count_verdict(&children, Verdict::Confirmed),
+                        children.len()
+                    )),
+                )
+            }
+
+            EvidenceSpec::Weighted {
+                evidence: nested, ..
+            } => {
+                let inner = self.check_evidence(nested);
+                let (verdict, details) = (inner.verdict, inner.details.clone());
+                children.push(inner);
+                (verdict, details)
+            }
+
+            EvidenceSpec::Not { evidence: nested } => {
+                let inner = self.check_evidence(nested);
+                let (verdict, details) = match inner.verdict {
+                    Verdict::Confirmed => {
+                        (Verdict::Refuted, "Nested check was confirmed".to_string())
+                    }
+                    Verdict::Refuted => {
+                        (Verdict::Confirmed, "Nested check was refuted".to_string())
+                    }
+                    other => (other, format!("Nested check was {:?}", other)),
+                };
+                children.push(inner);
+                (verdict, Some(details))
+            }
+
+            EvidenceSpec::AtLeast {
+                n,
+                evidence: nested,
+            } => {
+                children = nested.iter().map(|e| self.check_evidence(e)).collect();
+                (
+                    at_least(*n, &children),
+                    Some(format!(
+                        "{} of {} nested checks confirmed (need {})",
+                        count_verdict(&children, Verdict::Confirmed),
+                        children.len(),
+                        n
+                    )),
+                )
+            }
+
             EvidenceSpec::Custom { name, params } => {
                 if let Some(checker) = self.custom_checkers.get(name) {
-                    match checker(params) {
-                        Ok(v) => (v, None),
+                    match checker.check(params, self) {
+                        Ok(CheckOutcome {
+                            verdict,
+                            details,
+                            artifacts: produced,
+                        }) => {
+                            artifacts = produced;
+                            (verdict, details)
+                        }
                         Err(e) => (Verdict::Unverifiable, Some(e.to_string())),
                     }
                 } else {
@@ -727,79 +2092,374 @@ impl Verifier {
             spec: evidence.clone(),
             verdict,
             details,
+            children,
+            artifacts,
+            region,
         }
     }
 
-    /// Verify a complete claim
+    /// Verify a complete claim, checking its evidence on up to `jobs` threads
     pub fn verify(&self, claim: &Claim) -> VerificationReport {
+        let report = self.verify_with_jobs(claim, self.jobs);
+        self.record(&report, None);
+        report
+    }
+
+    /// Verify many claims on up to `jobs` threads
+    ///
+    /// Claims are checked after the claims named in their `depends_on`.
+    /// A claim whose dependency was refuted (or itself skipped) is not
+    /// checked; its report is Inconclusive with the reason in `skipped`.
+    /// Reports are returned in the same order as `claims`, regardless of
+    /// which worker finished first.
+    ///
+    /// Fails with [`VerificationError::InvalidClaim`] if a dependency names
+    /// no claim in the batch or the dependencies form a cycle.
+    pub fn verify_batch(
+        &self,
+        claims: &[Claim],
+    ) -> Result<Vec<VerificationReport>, VerificationError> {
+        self.verify_batch_in_session(claims, None)
+    }
+
+    /// Like [`Verifier::verify_batch`], also passing each report to
+    /// `on_report` as soon as its claim is verified
+    ///
+    /// `on_report` sees reports in the order they finish, which with more
+    /// than one job need not be the order of `claims`; it may be called from
+    /// several worker threads at once. The returned reports are still in
+    /// claim order.
+    pub fn verify_batch_streaming<F>(
+        &self,
+        claims: &[Claim],
+        on_report: F,
+    ) -> Result<Vec<VerificationReport>, VerificationError>
+    where
+        F: Fn(&VerificationReport) + Sync,
+    {
+        self.verify_batch_inner(claims, None, &on_report)
+    }
+
+    /// Like [`Verifier::verify_batch`], recording history under `session`
+    /// instead of the store's own session
+    pub(crate) fn verify_batch_in_session(
+        &self,
+        claims: &[Claim],
+        session: Option<&str>,
+    ) -> Result<Vec<VerificationReport>, VerificationError> {
+        self.verify_batch_inner(claims, session, &|_| {})
+    }
+
+    fn verify_batch_inner(
+        &self,
+        claims: &[Claim],
+        session: Option<&str>,
+        on_report: &(dyn Fn(&VerificationReport) + Sync),
+    ) -> Result<Vec<VerificationReport>, VerificationError> {
+        let mut reports: Vec<Option<VerificationReport>> = vec![None; claims.len()];
+        // Ids of refuted or skipped claims, with what happened to them
+        let mut blocked: HashMap<&str, &str> = HashMap::new();
+
+        for level in dependency_levels(claims)? {
+            // Parallelise across claims only, so the worker bound is never exceeded
+            let done = parallel_map(&level, self.jobs, |&index| {
+                let claim = &claims[index];
+                let blocker = claim
+                    .depends_on
+                    .iter()
+                    .find_map(|id| blocked.get(id.as_str()).map(|what| (id, what)));
+                let report = match blocker {
+                    Some((id, what)) => VerificationReport {
+                        claim: claim.clone(),
+                        evidence_results: Vec::new(),
+                        overall_verdict: Verdict::Inconclusive,
+                        confidence: 0.0,
+                        verified_at: Utc::now(),
+                        skipped: Some(format!("Depends on claim '{}', which was {}", id, what)),
+                    },
+                    None => self.verify_with_jobs(claim, 1),
+                };
+                self.record(&report, session);
+                on_report(&report);
+                report
+            });
+
+            for (&index, report) in level.iter().zip(done) {
+                if report.skipped.is_some() {
+                    blocked.insert(&claims[index].id, "skipped");
+                } else if report.overall_verdict == Verdict::Refuted {
+                    blocked.insert(&claims[index].id, "refuted");
+                }
+                reports[index] = Some(report);
+            }
+        }
+
+        Ok(reports
+            .into_iter()
+            .map(|report| report.expect("every claim is in exactly one level"))
+            .collect())
+    }
+
+    /// Append a report to the history store, if one is configured
+    fn record(&self, report: &VerificationReport, session: Option<&str>) {
+        if let Some(store) = &self.history {
+            // History is best-effort: a full disk must not change a verdict
+            let _ = store.record_in_session(report, session.or(store.session()));
+        }
+    }
+
+    fn verify_with_jobs(&self, claim: &Claim, jobs: usize) -> VerificationReport {
         if claim.evidence.is_empty() {
             return VerificationReport {
                 claim: claim.clone(),
                 evidence_results: vec![],
                 overall_verdict: Verdict::Unverifiable,
+                confidence: 0.0,
                 verified_at: Utc::now(),
+                skipped: None,
             };
         }
 
-        let evidence_results: Vec<EvidenceResult> = claim
-            .evidence
-            .iter()
-            .map(|e| self.check_evidence(e))
-            .collect();
+        let evidence_results = parallel_map(&claim.evidence, jobs, |e| self.check_evidence(e));
 
-        // Overall verdict: all must confirm for Confirmed, any refuted = Refuted
-        let overall_verdict = if evidence_results
-            .iter()
-            .all(|r| r.verdict == Verdict::Confirmed)
-        {
-            Verdict::Confirmed
-        } else if evidence_results
-            .iter()
-            .any(|r| r.verdict == Verdict::Refuted)
-        {
-            Verdict::Refuted
-        } else if evidence_results
-            .iter()
-            .all(|r| r.verdict == Verdict::Unverifiable)
-        {
-            Verdict::Unverifiable
-        } else {
-            Verdict::Inconclusive
-        };
+        let (overall_verdict, confidence) = self.aggregation.aggregate(&evidence_results);
 
         VerificationReport {
             claim: claim.clone(),
             evidence_results,
             overall_verdict,
+            confidence,
             verified_at: Utc::now(),
+            skipped: None,
         }
     }
 }
 
-/// Extract a value from JSON using a simple path notation
-/// Supports paths like ".field", ".nested.field", "[0]", ".array[0].field"
-fn extract_json_path<'a>(json: &'a serde_json::Value, path: &str) -> Option<&'a serde_json::Value> {
-    let mut current = json;
+/// Group claim indices into levels such that every claim's dependencies
+/// are in earlier levels. Claims sharing an id are all depended upon.
+fn dependency_levels(claims: &[Claim]) -> Result<Vec<Vec<usize>>, VerificationError> {
+    let mut by_id: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (index, claim) in claims.iter().enumerate() {
+        by_id.entry(&claim.id).or_default().push(index);
+    }
+
+    let mut deps = Vec::with_capacity(claims.len());
+    for claim in claims {
+        let mut on = Vec::new();
+        for id in &claim.depends_on {
+            let targets = by_id.get(id.as_str()).ok_or_else(|| {
+                VerificationError::InvalidClaim(format!(
+                    "Claim '{}' depends on unknown claim '{}'",
+                    claim.id, id
+                ))
+            })?;
+            on.extend(targets);
+        }
+        deps.push(on);
+    }
 
-    for segment in path.split('.').filter(|s| !s.is_empty()) {
-        // Check for array index
-        if let Some(bracket_pos) = segment.find('[') {
-            let field_name = &segment[..bracket_pos];
-            if !field_name.is_empty() {
-                current = current.get(field_name)?;
+    let mut placed = vec![false; claims.len()];
+    let mut levels: Vec<Vec<usize>> = Vec::new();
+    while placed.iter().any(|p| !p) {
+        let ready: Vec<usize> = (0..claims.len())
+            .filter(|&i| !placed[i] && deps[i].iter().all(|&d| placed[d]))
+            .collect();
+        if ready.is_empty() {
+            return Err(VerificationError::InvalidClaim(format!(
+                "Dependency cycle: {}",
+                find_cycle(claims, &deps, &placed)
+            )));
+        }
+        for &i in &ready {
+            placed[i] = true;
+        }
+        levels.push(ready);
+    }
+    Ok(levels)
+}
+
+/// Describe one cycle among the unplaced claims as `a -> b -> a`. Every
+/// unplaced claim has an unplaced dependency, so following them must loop.
+fn find_cycle(claims: &[Claim], deps: &[Vec<usize>], placed: &[bool]) -> String {
+    let mut path: Vec<usize> = Vec::new();
+    let mut current = placed.iter().position(|p| !p).unwrap_or_default();
+    while !path.contains(&current) {
+        path.push(current);
+        match deps[current].iter().find(|&&d| !placed[d]) {
+            Some(&next) => current = next,
+            None => break,
+        }
+    }
+    let start = path.iter().position(|&i| i == current).unwrap_or_default();
+    path[start..]
+        .iter()
+        .chain(std::iter::once(&current))
+        .map(|&i| claims[i].id.as_str())
+        .collect::<Vec<_>>()
+        .join(" -> ")
+}
+
+/// Map `f` over `items` on up to `jobs` scoped threads, preserving input order
+fn parallel_map<T, R, F>(items: &[T], jobs: usize, f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    let workers = jobs.min(items.len());
+    if workers <= 1 {
+        return items.iter().map(f).collect();
+    }
+
+    let next = AtomicUsize::new(0);
+    let mut slots: Vec<Option<R>> = items.iter().map(|_| None).collect();
+
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..workers)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut done = Vec::new();
+                    loop {
+                        let index = next.fetch_add(1, Ordering::Relaxed);
+                        if index >= items.len() {
+                            break;
+                        }
+                        done.push((index, f(&items[index])));
+                    }
+                    done
+                })
+            })
+            .collect();
+
+        for handle in handles {
+            let done = handle
+                .join()
+                .unwrap_or_else(|panic| std::panic::resume_unwind(panic));
+            for (index, result) in done {
+                slots[index] = Some(result);
             }
+        }
+    });
 
-            // Extract index
-            let end_bracket = segment.find(']')?;
-            let index_str = &segment[bracket_pos + 1..end_bracket];
-            let index: usize = index_str.parse().ok()?;
-            current = current.get(index)?;
-        } else {
-            current = current.get(segment)?;
+    slots
+        .into_iter()
+        .map(|slot| slot.expect("every item is processed exactly once"))
+        .collect()
+}
+
+fn count_verdict(results: &[EvidenceResult], verdict: Verdict) -> usize {
+    results.iter().filter(|r| r.verdict == verdict).count()
+}
+
+/// Conjunction: all must confirm for Confirmed, any refuted = Refuted
+fn all_of(results: &[EvidenceResult]) -> Verdict {
+    if results.is_empty() {
+        Verdict::Unverifiable
+    } else if results.iter().all(|r| r.verdict == Verdict::Confirmed) {
+        Verdict::Confirmed
+    } else if results.iter().any(|r| r.verdict == Verdict::Refuted) {
+        Verdict::Refuted
+    } else if results.iter().all(|r| r.verdict == Verdict::Unverifiable) {
+        Verdict::Unverifiable
+    } else {
+        Verdict::Inconclusive
+    }
+}
+
+/// Disjunction: any confirmed = Confirmed, all must refute for Refuted
+fn any_of(results: &[EvidenceResult]) -> Verdict {
+    if results.is_empty() {
+        Verdict::Unverifiable
+    } else if results.iter().any(|r| r.verdict == Verdict::Confirmed) {
+        Verdict::Confirmed
+    } else if results.iter().all(|r| r.verdict == Verdict::Refuted) {
+        Verdict::Refuted
+    } else if results.iter().all(|r| r.verdict == Verdict::Unverifiable) {
+        Verdict::Unverifiable
+    } else {
+        Verdict::Inconclusive
+    }
+}
+
+/// Threshold: Confirmed once `n` confirm, Refuted once `n` can no longer be reached
+fn at_least(n: usize, results: &[EvidenceResult]) -> Verdict {
+    let confirmed = count_verdict(results, Verdict::Confirmed);
+    let refuted = count_verdict(results, Verdict::Refuted);
+
+    if confirmed >= n {
+        Verdict::Confirmed
+    } else if results.len() - refuted < n {
+        Verdict::Refuted
+    } else if results.iter().all(|r| r.verdict == Verdict::Unverifiable) {
+        Verdict::Unverifiable
+    } else {
+        Verdict::Inconclusive
+    }
+}
+
+/// Judge a finished `CommandSucceeds` run against its expectations
+fn check_command_run(
+    run: &exec::CommandRun,
+    timeout_secs: Option<u64>,
+    expected_exit_code: i32,
+    stdout_contains: Option<&str>,
+    stdout_matches: Option<&str>,
+) -> (Verdict, Option<String>) {
+    if run.timed_out {
+        let secs = timeout_secs.unwrap_or_default();
+        return (
+            Verdict::Inconclusive,
+            Some(VerificationError::Timeout(secs).to_string()),
+        );
+    }
+
+    match run.code {
+        Some(code) if code == expected_exit_code => {}
+        Some(code) => {
+            return (
+                Verdict::Refuted,
+                Some(format!(
+                    "Command exited with code {} (expected {})",
+                    code, expected_exit_code
+                )),
+            )
+        }
+        None => {
+            return (
+                Verdict::Refuted,
+                Some("Command was terminated by a signal".to_string()),
+            )
         }
     }
 
-    Some(current)
+    if let Some(needle) = stdout_contains {
+        if !run.stdout.contains(needle) {
+            return (
+                Verdict::Refuted,
+                Some(format!("Command output does not contain '{}'", needle)),
+            );
+        }
+    }
+
+    if let Some(pattern) = stdout_matches {
+        match Regex::new(pattern) {
+            Ok(re) if re.is_match(&run.stdout) => {}
+            Ok(_) => {
+                return (
+                    Verdict::Refuted,
+                    Some(format!("Command output does not match /{}/", pattern)),
+                )
+            }
+            Err(e) => {
+                return (
+                    Verdict::Unverifiable,
+                    Some(format!("Invalid regex pattern: {}", e)),
+                )
+            }
+        }
+    }
+
+    (Verdict::Confirmed, Some("Command succeeded".to_string()))
 }
 
 #[cfg(test)]
@@ -819,6 +2479,306 @@ mod tests {
         assert_eq!(claim.source, Some("test-agent".to_string()));
     }
 
+    #[test]
+    fn test_claim_ids_are_content_addressed() {
+        let build = |path: &str| {
+            Claim::new("Created a file")
+                .with_evidence(EvidenceSpec::FileExists {
+                    path: path.to_string(),
+                })
+                .with_source("agent")
+        };
+        let claim = build("a.txt");
+        assert_eq!(claim.id, build("a.txt").id);
+        assert_eq!(claim.id, claim.content_id());
+        assert_ne!(claim.id, build("b.txt").id);
+        assert_ne!(claim.id, Claim::new("Created a file").id);
+
+        // Key order, whitespace, omitted defaults and timestamps do not matter
+        let parsed: Claim = serde_json::from_str(
+            r#"{ "source": "agent", "timestamp": "2020-01-01T00:00:00Z",
+                 "evidence": [{ "spec": { "path": "a.txt" }, "type": "FileExists" }],
+                 "description": "Created a file" }"#,
+        )
+        .unwrap();
+        assert_eq!(parsed.id, claim.id);
+
+        // Explicit ids are kept, and builder calls leave them alone
+        let named = Claim::new("x").with_id("mine").with_source("agent");
+        assert_eq!(named.id, "mine");
+
+        let first = Claim::new("x").with_id_scheme(IdScheme::Uuid7);
+        let second = Claim::new("x").with_id_scheme(IdScheme::Uuid7);
+        assert_eq!(first.id.len(), 36);
+        assert_ne!(first.id, second.id);
+        assert_eq!("uuid7".parse::<IdScheme>(), Ok(IdScheme::Uuid7));
+        assert!("random".parse::<IdScheme>().is_err());
+    }
+
+    #[test]
+    fn test_verifier_is_send_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Verifier>();
+    }
+
+    #[test]
+    fn test_parallel_map_preserves_order() {
+        let items: Vec<usize> = (0..100).collect();
+        for jobs in [0, 1, 3, 8, 200] {
+            let doubled = parallel_map(&items, jobs, |i| i * 2);
+            assert_eq!(doubled, items.iter().map(|i| i * 2).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn test_verify_batch_matches_serial() {
+        let claims: Vec<Claim> = (0..20)
+            .map(|i| {
+                let path = if i % 3 == 0 { "/nonexistent" } else { "/" };
+                Claim::new(format!("claim {}", i)).with_evidence(EvidenceSpec::DirectoryExists {
+                    path: path.to_string(),
+                })
+            })
+            .collect();
+
+        let serial = Verifier::new().verify_batch(&claims).unwrap();
+        let parallel = Verifier::new().with_jobs(4).verify_batch(&claims).unwrap();
+
+        assert_eq!(serial.len(), parallel.len());
+        for (a, b) in serial.iter().zip(&parallel) {
+            assert_eq!(a.claim.id, b.claim.id);
+            assert_eq!(a.overall_verdict, b.overall_verdict);
+        }
+    }
+
+    #[test]
+    fn test_verify_batch_streaming_reports_each_claim_once() {
+        let claims: Vec<Claim> = (0..10)
+            .map(|i| {
+                Claim::new(format!("claim {}", i)).with_evidence(EvidenceSpec::DirectoryExists {
+                    path: "/".to_string(),
+                })
+            })
+            .collect();
+
+        let seen = std::sync::Mutex::new(Vec::new());
+        let reports = Verifier::new()
+            .with_jobs(3)
+            .verify_batch_streaming(&claims, |report| {
+                seen.lock().unwrap().push(report.claim.description.clone())
+            })
+            .unwrap();
+
+        let mut seen = seen.into_inner().unwrap();
+        seen.sort();
+        let mut expected: Vec<String> = reports
+            .iter()
+            .map(|r| r.claim.description.clone())
+            .collect();
+        expected.sort();
+        assert_eq!(seen, expected);
+        assert_eq!(reports[7].claim.description, "claim 7");
+    }
+
+    fn claim(id: &str, path: &str) -> Claim {
+        let mut claim = Claim::new(id).with_evidence(EvidenceSpec::DirectoryExists {
+            path: path.to_string(),
+        });
+        claim.id = id.to_string();
+        claim
+    }
+
+    #[test]
+    fn test_verify_batch_skips_dependents_of_refuted_claims() {
+        let claims = vec![
+            claim("tests", "/").depends_on("build"),
+            claim("build", "/nonexistent"),
+            claim("docs", "/").depends_on("tests"),
+            claim("lint", "/"),
+        ];
+
+        let reports = Verifier::new().with_jobs(2).verify_batch(&claims).unwrap();
+        let verdicts: Vec<Verdict> = reports.iter().map(|r| r.overall_verdict).collect();
+        assert_eq!(
+            verdicts,
+            [
+                Verdict::Inconclusive,
+                Verdict::Refuted,
+                Verdict::Inconclusive,
+                Verdict::Confirmed
+            ]
+        );
+        assert_eq!(
+            reports[0].skipped.as_deref(),
+            Some("Depends on claim 'build', which was refuted")
+        );
+        assert_eq!(
+            reports[2].skipped.as_deref(),
+            Some("Depends on claim 'tests', which was skipped")
+        );
+        assert!(reports[0].evidence_results.is_empty());
+    }
+
+    #[test]
+    fn test_dependency_levels_order_and_errors() {
+        let claims = vec![
+            claim("c", "/").depends_on("b"),
+            claim("b", "/").depends_on("a"),
+            claim("a", "/"),
+        ];
+        assert_eq!(dependency_levels(&claims).unwrap(), [[2], [1], [0]]);
+
+        let unknown = vec![claim("a", "/").depends_on("ghost")];
+        let err = dependency_levels(&unknown).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid claim structure: Claim 'a' depends on unknown claim 'ghost'"
+        );
+
+        let cycle = vec![
+            claim("x", "/").depends_on("a"),
+            claim("a", "/").depends_on("b"),
+            claim("b", "/").depends_on("a"),
+        ];
+        assert!(matches!(
+            Verifier::new().verify_batch(&cycle),
+            Err(VerificationError::InvalidClaim(reason)) if reason == "Dependency cycle: a -> b -> a"
+        ));
+    }
+
+    fn result(verdict: Verdict, weight: f64, required: bool) -> EvidenceResult {
+        EvidenceResult {
+            spec: EvidenceSpec::Weighted {
+                evidence: Box::new(EvidenceSpec::DirectoryExists {
+                    path: "/".to_string(),
+                }),
+                weight,
+                required,
+            },
+            verdict,
+            details: None,
+            children: Vec::new(),
+            artifacts: BTreeMap::new(),
+            region: None,
+        }
+    }
+
+    #[test]
+    fn test_aggregation_policies() {
+        use Verdict::*;
+        let results = [
+            result(Confirmed, 3.0, false),
+            result(Unverifiable, 1.0, false),
+            result(Refuted, 1.0, false),
+        ];
+
+        let (verdict, confidence) = AggregationPolicy::Strict.aggregate(&results);
+        assert_eq!(verdict, Refuted);
+        assert!((confidence - 0.6).abs() < 1e-9);
+        assert_eq!(AggregationPolicy::Majority.aggregate(&results).0, Confirmed);
+        assert_eq!(
+            AggregationPolicy::WeightedThreshold(0.9)
+                .aggregate(&results)
+                .0,
+            Refuted
+        );
+        // 0.6 confirmed, 0.8 possible: undecided
+        assert_eq!(
+            AggregationPolicy::WeightedThreshold(0.7)
+                .aggregate(&results)
+                .0,
+            Inconclusive
+        );
+
+        // Required evidence vetoes a majority either way
+        let vetoed = [result(Confirmed, 5.0, false), result(Refuted, 1.0, true)];
+        assert_eq!(AggregationPolicy::Majority.aggregate(&vetoed).0, Refuted);
+        let pending = [
+            result(Confirmed, 5.0, false),
+            result(Inconclusive, 1.0, true),
+        ];
+        assert_eq!(
+            AggregationPolicy::Majority.aggregate(&pending).0,
+            Inconclusive
+        );
+
+        assert_eq!(
+            AggregationPolicy::Majority.aggregate(&[]),
+            (Unverifiable, 0.0)
+        );
+
+        // Nothing Confirmed is never enough, even for a zero threshold
+        assert_eq!(
+            AggregationPolicy::WeightedThreshold(0.0)
+                .aggregate(&[result(Refuted, 1.0, false)])
+                .0,
+            Refuted
+        );
+    }
+
+    #[test]
+    fn test_aggregation_policy_from_str() {
+        assert_eq!("majority".parse(), Ok(AggregationPolicy::Majority));
+        assert_eq!(
+            "threshold=0.75".parse(),
+            Ok(AggregationPolicy::WeightedThreshold(0.75))
+        );
+        assert!("threshold=2".parse::<AggregationPolicy>().is_err());
+        assert!("threshold=0".parse::<AggregationPolicy>().is_err());
+        assert!("lenient".parse::<AggregationPolicy>().is_err());
+    }
+
+    #[test]
+    fn test_nested_weighted_is_unverifiable() {
+        let weighted = EvidenceSpec::Weighted {
+            evidence: Box::new(EvidenceSpec::DirectoryExists {
+                path: "/".to_string(),
+            }),
+            weight: 2.0,
+            required: true,
+        };
+        let nested = EvidenceSpec::Not {
+            evidence: Box::new(EvidenceSpec::AnyOf {
+                evidence: vec![weighted.clone()],
+            }),
+        };
+        let result = Verifier::new().check_evidence(&nested);
+        assert_eq!(result.verdict, Verdict::Unverifiable);
+        assert!(result
+            .details
+            .unwrap()
+            .contains("only allowed at the top level"));
+
+        assert_eq!(
+            Verifier::new().check_evidence(&weighted).verdict,
+            Verdict::Confirmed
+        );
+    }
+
+    #[test]
+    fn test_weighted_defaults_and_old_reports() {
+        let spec: EvidenceSpec = serde_json::from_value(serde_json::json!({
+            "type": "Weighted",
+            "spec": { "evidence": { "type": "DirectoryExists", "spec": { "path": "/" } } }
+        }))
+        .unwrap();
+        assert!(matches!(
+            spec,
+            EvidenceSpec::Weighted { weight, required: false, .. } if weight == 1.0
+        ));
+        assert_eq!(
+            Verifier::new().check_evidence(&spec).verdict,
+            Verdict::Confirmed
+        );
+
+        // Reports recorded before confidence existed still load
+        let report = Verifier::new().verify(&Claim::new("old").with_evidence(spec));
+        let mut json = serde_json::to_value(&report).unwrap();
+        json.as_object_mut().unwrap().remove("confidence");
+        let old: VerificationReport = serde_json::from_value(json).unwrap();
+        assert_eq!(old.confidence, 0.0);
+    }
+
     #[test]
     fn test_verdict_trustworthiness() {
         assert!(Verdict::Confirmed.is_trustworthy());
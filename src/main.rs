@@ -5,24 +5,92 @@
 //!   `dyadt check <claim.json>`     - Verify a claim from a JSON file
 //!   `dyadt verify <path>`          - Quick check if a file/directory exists
 //!   `dyadt report <claims.json>`   - Generate a verification report
+//!   `dyadt expand <claims.json>`   - Print a claims manifest fully resolved
+//!   `dyadt validate <claims.json>` - Check a claims file against the schema
 
+use chrono::{DateTime, Utc};
+use did_you_actually_do_that::baseline::{Baseline, Change};
+use did_you_actually_do_that::claim_extractor::{ClaimExtractor, ExtractionContext};
+use did_you_actually_do_that::history::{parse_since, HistoryStats, HistoryStore, Tally};
 use did_you_actually_do_that::hooks::{format_hook_output, parse_hook_input, HookHandler};
+use did_you_actually_do_that::manifest::ManifestLoader;
 use did_you_actually_do_that::mcp_server::McpServer;
-use did_you_actually_do_that::{Claim, EvidenceSpec, Verdict, VerificationReport, Verifier};
+use did_you_actually_do_that::policy::ExecutionPolicy;
+use did_you_actually_do_that::report::{self, ReportContext, Reporters};
+use did_you_actually_do_that::schema;
+use did_you_actually_do_that::snapshot::Snapshot;
+use did_you_actually_do_that::{
+    AggregationPolicy, Claim, EvidenceSpec, IdScheme, Verdict, Verifier,
+};
 use sha2::{Digest, Sha256};
 use std::env;
 use std::fs;
-use std::process::ExitCode;
+use std::io;
 #[cfg(feature = "watch")]
 use std::io::Write;
+use std::process::ExitCode;
 
-/// Output format for reports
-#[derive(Debug, Clone, Copy, PartialEq)]
-enum OutputFormat {
-    Human,
-    Json,
-    Sarif,
-    JUnit,
+/// Options for the `report` command
+#[derive(Debug, Clone)]
+struct ReportOptions {
+    /// Name of the reporter to write with (see `report::Reporters`)
+    format: String,
+    /// Worker threads for verification (0 = one per CPU)
+    jobs: usize,
+    /// Saved `report --json` output to compare against (`--baseline FILE`)
+    baseline: Option<String>,
+}
+
+impl Default for ReportOptions {
+    fn default() -> Self {
+        Self {
+            format: "human".to_string(),
+            jobs: 1,
+            baseline: None,
+        }
+    }
+}
+
+const REPORT_USAGE: &str =
+    "Usage: dyadt report [--format NAME] [--jobs N] [--baseline FILE] <claims.json>
+       --json, --sarif, --junit, --markdown, --html, --tap, --csv and --ndjson
+       are short for --format json and so on";
+
+/// Parse `report` arguments into options and the claims file path
+fn parse_report_args(args: &[String]) -> Result<(ReportOptions, String), String> {
+    let mut options = ReportOptions::default();
+    let mut path = None;
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--format" => {
+                let name = iter.next().ok_or("--format requires a format name")?;
+                options.format = name.clone();
+            }
+            "--json" | "--sarif" | "--junit" | "--markdown" | "--html" | "--tap" | "--csv"
+            | "--ndjson" => options.format = arg[2..].to_string(),
+            "--baseline" => {
+                let file = iter.next().ok_or("--baseline requires a report file")?;
+                options.baseline = Some(file.clone());
+            }
+            "--jobs" | "-j" => {
+                let value = iter.next().ok_or("--jobs requires a value")?;
+                options.jobs = value
+                    .parse()
+                    .map_err(|_| format!("Invalid --jobs value: {}", value))?;
+            }
+            flag if flag.starts_with("--") => return Err(format!("Unknown option: {}", flag)),
+            file => {
+                if path.replace(file.to_string()).is_some() {
+                    return Err("Only one claims file may be given".to_string());
+                }
+            }
+        }
+    }
+
+    let path = path.ok_or("Missing claims file")?;
+    Ok((options, path))
 }
 
 fn print_help() {
@@ -32,21 +100,61 @@ Did You Actually Do That? (dyadt) v0.1.0
 A verification framework for validating claimed actions against reality.
 
 USAGE:
-    dyadt <COMMAND> [ARGS]
+    dyadt [--no-exec] [--history DIR] [--aggregation POLICY] [--var KEY=VALUE]...
+          [--ids SCHEME] <COMMAND> [ARGS]
 
 COMMANDS:
     check <claim.json>      Verify a claim from a JSON file
     verify <path>           Quick check if a file or directory exists
     hash <file>             Compute SHA-256 hash of a file (for evidence specs)
     report <claims.json>    Verify multiple claims and generate a report
+    report --format NAME <file>
+                            Output report as human, json, sarif, junit, markdown,
+                            html, tap, csv or ndjson (also --json, --sarif, ...)
     report --json <file>    Output report as JSON
     report --sarif <file>   Output report as SARIF (for code scanning)
     report --junit <file>   Output report as JUnit XML (for CI)
+    report --markdown <file>
+                            Output report as Markdown (for merge request comments)
+    report --html <file>    Output report as a single-file HTML page
+    report --tap <file>     Output report as TAP version 14
+    report --csv <file>     Output one CSV row per top-level evidence check
+    report --ndjson <file>  Stream one JSON report per line as claims finish
+    report --jobs N <file>  Verify claims on N worker threads (0 = one per CPU)
+    report --baseline PREV <file>
+                            Compare with a saved `report --json` by claim id;
+                            exit 1 only if a Confirmed claim is now Refuted
+    validate <claims.json>  Check a claims file for unknown fields, missing
+                            fields and misspelt types (exit 1 on problems)
+    schema                  Print the JSON Schema for claim files
+    expand <claims.json>    Print a claims manifest with variables, templates
+                            and includes resolved
     watch <claims.json>     Watch and re-verify on file changes (requires 'watch' feature)
+    stats [--since 7d]      Confirmation rates per source, evidence type and session
+    snapshot <dir> -o FILE  Record paths, sizes, mtimes and hashes under a directory
+    diff <snapshot.json>    Show files created/modified/deleted since a snapshot
+    diff --claims <file>    Emit the changes as claims with hash evidence
+    diff --cross-check <transcript> <file>
+                            Compare files the transcript claims to have changed
+                            with what actually changed (exit 1 on phantom changes)
     mcp-server              Run as MCP server (for AI self-verification)
     hook <json>             Process hook event from Claude Code
     help                    Show this help message
 
+OPTIONS:
+    --no-exec               Never run external programs; command and git
+                            evidence is reported as Unverifiable
+    --history DIR           Record every verified claim in DIR/history.jsonl
+                            (defaults to $DYADT_HISTORY when set)
+    --aggregation POLICY    How evidence verdicts combine: strict (default,
+                            all must be confirmed), majority, or threshold=0.8
+                            (confirmed share of the evidence weight)
+    --var KEY=VALUE         Define ${{KEY}} for claim manifests (repeatable;
+                            overrides environment variables)
+    --ids SCHEME            Ids for claims without one: content (default, a
+                            hash of description, evidence and source, stable
+                            across runs) or uuid7 (unique per run)
+
 EXAMPLES:
     # Verify a specific claim
     dyadt check my-claim.json
@@ -60,10 +168,30 @@ EXAMPLES:
     # Generate CI-friendly report
     dyadt report --junit claims.json > results.xml
 
+    # Record the tree before an agent turn, then see what it really did
+    dyadt snapshot . -o before.json
+    dyadt diff --cross-check agent-reply.txt before.json
+
+    # How often were claims from the hook refuted this week?
+    dyadt --history ~/.dyadt stats --since 7d
+
+    # Reuse one manifest for another crate, and see what it expands to
+    dyadt --var crate=cli expand claims.json
+
+    # Check an untrusted claim file without running anything it names
+    dyadt --no-exec check untrusted-claim.json
+
 EVIDENCE TYPES:
-    FileExists, FileWithHash, FileContains, FileMatchesRegex, FileJsonPath,
-    DirectoryExists, CommandSucceeds, GitClean, GitCommitExists, GitBranchExists,
-    FileModifiedAfter, EnvVar, Custom
+    FileExists, FileWithHash, FileContains, FileMatchesRegex, FileLineRange,
+    FileUnifiedDiffApplied, FileJsonPath, FileStructuredPath, SymbolDefined, DirectoryExists, CommandSucceeds,
+    TestPasses, GitClean, GitCommitExists, GitBranchExists, FileModifiedAfter,
+    EnvVar, Custom
+    Git history: FileChangedInCommit, CommitTouchesPaths, CommitDiffMatches,
+    CommitMessageMatches, CommitIsAncestor
+    Absence: FileAbsent, DirectoryAbsent, GitBranchAbsent, EnvVarAbsent
+    Composites: AllOf, AnyOf, Not, AtLeast (nest any of the above)
+    Weighted (top-level evidence only): weight and required flag for
+    --aggregation
 
 EXIT CODES:
     0 - All claims verified (Confirmed)
@@ -74,40 +202,166 @@ EXIT CODES:
     );
 }
 
-fn verify_claim_file(path: &str) -> ExitCode {
-    let contents = match fs::read_to_string(path) {
-        Ok(c) => c,
-        Err(e) => {
-            eprintln!("Error reading {}: {}", path, e);
-            return ExitCode::from(3);
+/// Options accepted anywhere on the command line
+#[derive(Debug, Clone, Default)]
+struct GlobalOptions {
+    /// Which programs evidence may run (`--no-exec` denies all)
+    policy: ExecutionPolicy,
+    /// Where verified claims are recorded (`--history DIR` or `DYADT_HISTORY`)
+    history: Option<HistoryStore>,
+    /// How evidence verdicts combine (`--aggregation POLICY`)
+    aggregation: AggregationPolicy,
+    /// Variables for claim manifests (`--var key=value`)
+    vars: Vec<(String, String)>,
+    /// Ids for claims that do not name one (`--ids content|uuid7`)
+    ids: IdScheme,
+}
+
+impl GlobalOptions {
+    /// Remove global flags from `args`, returning the options they select
+    fn take(args: &mut Vec<String>) -> Result<Self, String> {
+        let mut options = Self::default();
+        let mut iter = std::mem::take(args).into_iter();
+
+        while let Some(arg) = iter.next() {
+            match arg.as_str() {
+                "--no-exec" => options.policy = ExecutionPolicy::deny_all(),
+                "--aggregation" => {
+                    let policy = iter.next().ok_or("--aggregation requires a policy")?;
+                    options.aggregation = policy.parse()?;
+                }
+                "--ids" => {
+                    let scheme = iter.next().ok_or("--ids requires content or uuid7")?;
+                    options.ids = scheme.parse()?;
+                }
+                "--var" => {
+                    let pair = iter.next().ok_or("--var requires key=value")?;
+                    let (key, value) = pair
+                        .split_once('=')
+                        .ok_or_else(|| format!("Invalid --var '{}', expected key=value", pair))?;
+                    options.vars.push((key.to_string(), value.to_string()));
+                }
+                "--history" => {
+                    let dir = iter.next().ok_or("--history requires a directory")?;
+                    let store = HistoryStore::open(&dir)
+                        .map_err(|e| format!("Cannot open history {}: {}", dir, e))?;
+                    options.history = Some(store);
+                }
+                _ => args.push(arg),
+            }
         }
-    };
 
-    let claim: Claim = match serde_json::from_str(&contents) {
-        Ok(c) => c,
+        if options.history.is_none() {
+            options.history = HistoryStore::from_env()
+                .map_err(|e| format!("Cannot open history from DYADT_HISTORY: {}", e))?;
+        }
+        Ok(options)
+    }
+
+    /// A verifier configured with these options
+    fn verifier(&self) -> Verifier {
+        let verifier = Verifier::new()
+            .with_policy(self.policy.clone())
+            .with_aggregation(self.aggregation);
+        match &self.history {
+            Some(store) => verifier.with_history(store.clone()),
+            None => verifier,
+        }
+    }
+
+    /// Load the claims in a claims file or manifest, expanding its
+    /// variables, templates and includes. Files that fail strict
+    /// validation are rejected with every problem listed.
+    fn load_claims(&self, path: &str) -> Result<Vec<Claim>, String> {
+        let loader = self.loader();
+        let diagnostics = schema::validate_file(path, &loader);
+        if !diagnostics.is_empty() {
+            let lines: Vec<String> = diagnostics
+                .iter()
+                .map(|d| format!("  {}:{}", path, d))
+                .collect();
+            return Err(format!(
+                "{} is not a valid claims file\n{}",
+                path,
+                lines.join("\n")
+            ));
+        }
+        loader.load_file(path)
+    }
+
+    fn loader(&self) -> ManifestLoader {
+        self.vars.iter().fold(
+            ManifestLoader::new().with_id_scheme(self.ids),
+            |loader, (key, value)| loader.with_var(key, value),
+        )
+    }
+}
+
+fn verify_claim_file(path: &str, globals: &GlobalOptions) -> ExitCode {
+    let claim = match globals.load_claims(path) {
+        Ok(mut claims) if claims.len() == 1 => claims.remove(0),
+        Ok(claims) => {
+            eprintln!(
+                "{} holds {} claims; use 'dyadt report' to verify several",
+                path,
+                claims.len()
+            );
+            return ExitCode::from(3);
+        }
         Err(e) => {
-            eprintln!("Error parsing claim JSON: {}", e);
+            eprintln!("Error loading claim: {}", e);
             return ExitCode::from(3);
         }
     };
 
-    let verifier = Verifier::new();
+    let verifier = globals.verifier();
     let report = verifier.verify(&claim);
-    print_report(&report);
+    print!("{}", report::format_report(&report));
 
     verdict_to_exit_code(report.overall_verdict)
 }
 
-fn quick_verify(path: &str) -> ExitCode {
+/// Check a claims file against the claim schema, listing every problem
+fn validate_claims(path: &str, globals: &GlobalOptions) -> ExitCode {
+    let diagnostics = schema::validate_file(path, &globals.loader());
+    if diagnostics.is_empty() {
+        println!("{}: ok", path);
+        return ExitCode::SUCCESS;
+    }
+    for diagnostic in &diagnostics {
+        println!("{}:{}", path, diagnostic);
+    }
+    println!("{} problem(s) found", diagnostics.len());
+    ExitCode::from(1)
+}
+
+/// Print a claims file with its variables, templates and includes resolved
+fn expand_claims(path: &str, globals: &GlobalOptions) -> ExitCode {
+    match globals.loader().expand_file(path) {
+        Ok(claims) => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&claims).unwrap_or_default()
+            );
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("Error loading claims: {}", e);
+            ExitCode::from(3)
+        }
+    }
+}
+
+fn quick_verify(path: &str, globals: &GlobalOptions) -> ExitCode {
     let claim = Claim::new(format!("Path exists: {}", path))
         .with_evidence(EvidenceSpec::FileExists {
             path: path.to_string(),
         })
         .with_source("dyadt-cli");
 
-    let verifier = Verifier::new();
+    let verifier = globals.verifier();
     let report = verifier.verify(&claim);
-    print_report(&report);
+    print!("{}", report::format_report(&report));
 
     verdict_to_exit_code(report.overall_verdict)
 }
@@ -133,426 +387,422 @@ fn compute_hash(path: &str) -> ExitCode {
     }
 }
 
-fn verify_multiple(path: &str) -> ExitCode {
-    verify_multiple_with_format(path, OutputFormat::Human)
+fn verdict_to_exit_code(verdict: Verdict) -> ExitCode {
+    match verdict {
+        Verdict::Confirmed => ExitCode::SUCCESS,
+        Verdict::Refuted => ExitCode::from(1),
+        Verdict::Inconclusive | Verdict::Unverifiable => ExitCode::from(2),
+    }
 }
 
-fn print_report(report: &VerificationReport) {
-    println!("{}", report.summary());
+/// Verify multiple claims with configurable output format
+fn verify_multiple_with_format(
+    path: &str,
+    options: &ReportOptions,
+    globals: &GlobalOptions,
+) -> ExitCode {
+    let reporters = Reporters::new();
+    let Some(reporter) = reporters.get(&options.format) else {
+        let names: Vec<&str> = reporters.all().map(|r| r.name()).collect();
+        eprintln!(
+            "Unknown format '{}' (expected one of: {})",
+            options.format,
+            names.join(", ")
+        );
+        return ExitCode::from(3);
+    };
 
-    if let Some(ref source) = report.claim.source {
-        println!("  Source: {}", source);
-    }
+    let claims = match globals.load_claims(path) {
+        Ok(claims) => claims,
+        Err(e) => {
+            eprintln!("Error loading claims: {}", e);
+            return ExitCode::from(3);
+        }
+    };
 
-    for result in &report.evidence_results {
-        let icon = match result.verdict {
-            Verdict::Confirmed => "  ✓",
-            Verdict::Refuted => "  ✗",
-            Verdict::Inconclusive => "  ?",
-            Verdict::Unverifiable => "  ⊘",
-        };
-
-        let evidence_desc = match &result.spec {
-            EvidenceSpec::FileExists { path } => format!("File exists: {}", path),
-            EvidenceSpec::FileWithHash { path, .. } => format!("File hash: {}", path),
-            EvidenceSpec::FileContains { path, substring } => {
-                format!("File contains '{}': {}", substring, path)
-            }
-            EvidenceSpec::FileMatchesRegex { path, pattern } => {
-                format!("File matches regex '{}': {}", pattern, path)
-            }
-            EvidenceSpec::FileJsonPath {
-                path, json_path, ..
-            } => {
-                format!("JSON path '{}': {}", json_path, path)
-            }
-            EvidenceSpec::DirectoryExists { path } => format!("Directory exists: {}", path),
-            EvidenceSpec::CommandSucceeds { command, .. } => {
-                format!("Command succeeds: {}", command)
-            }
-            EvidenceSpec::GitClean { repo_path } => {
-                format!("Git clean: {}", repo_path.as_deref().unwrap_or("."))
+    let mut context = ReportContext::new();
+    if let Some(path) = &options.baseline {
+        match Baseline::load(path) {
+            Ok(baseline) => context = context.with_baseline(baseline),
+            Err(e) => {
+                eprintln!("{}", e);
+                return ExitCode::from(3);
             }
-            EvidenceSpec::GitCommitExists { commit, .. } => {
-                format!("Git commit exists: {}", commit)
-            }
-            EvidenceSpec::GitBranchExists { branch, .. } => {
-                format!("Git branch exists: {}", branch)
-            }
-            EvidenceSpec::FileModifiedAfter { path, after } => {
-                format!("File modified after {}: {}", after, path)
-            }
-            EvidenceSpec::EnvVar { name, expected } => {
-                format!("Env {}={}", name, expected)
-            }
-            EvidenceSpec::Custom { name, .. } => format!("Custom check: {}", name),
-        };
+        }
+    }
 
-        println!("{} {}", icon, evidence_desc);
+    let verifier = globals.verifier().with_jobs(options.jobs);
+    let reports = match verifier.verify_batch_streaming(&claims, |report| {
+        if !reporter.streams() {
+            return;
+        }
+        if let Err(e) = reporter.on_report(report, &context, &mut io::stdout().lock()) {
+            eprintln!("Error writing report: {}", e);
+        }
+    }) {
+        Ok(reports) => reports,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return ExitCode::from(3);
+        }
+    };
 
-        if let Some(ref details) = result.details {
-            println!("      {}", details);
+    if !reporter.streams() {
+        if let Err(e) = reporter.write(&reports, &context, &mut io::stdout().lock()) {
+            eprintln!("Error writing report: {}", e);
+            return ExitCode::from(3);
         }
     }
-}
 
-fn verdict_to_exit_code(verdict: Verdict) -> ExitCode {
-    match verdict {
-        Verdict::Confirmed => ExitCode::SUCCESS,
-        Verdict::Refuted => ExitCode::from(1),
-        Verdict::Inconclusive | Verdict::Unverifiable => ExitCode::from(2),
+    match context.comparisons(&reports) {
+        // Against a baseline only regressions fail the run
+        Some(comparisons) if comparisons.iter().any(|c| c.change == Change::Regressed) => {
+            ExitCode::from(1)
+        }
+        Some(_) => ExitCode::SUCCESS,
+        None => verdict_to_exit_code(report::worst_verdict(&reports)),
     }
 }
 
-/// Verify multiple claims with configurable output format
-fn verify_multiple_with_format(path: &str, format: OutputFormat) -> ExitCode {
-    let contents = match fs::read_to_string(path) {
-        Ok(c) => c,
+/// Watch mode for continuous verification
+#[cfg(feature = "watch")]
+fn watch_claims(path: &str, globals: &GlobalOptions) -> ExitCode {
+    use did_you_actually_do_that::watch::watch_and_verify_with;
+    use did_you_actually_do_that::VerificationReport;
+
+    let claims = match globals.load_claims(path) {
+        Ok(claims) => claims,
         Err(e) => {
-            eprintln!("Error reading {}: {}", path, e);
+            eprintln!("Error loading claims: {}", e);
             return ExitCode::from(3);
         }
     };
 
-    let claims: Vec<Claim> = match serde_json::from_str(&contents) {
-        Ok(c) => c,
+    let on_report = |report: &VerificationReport| {
+        print!("{}", report::format_report(report));
+        let _ = std::io::stdout().flush();
+    };
+
+    let verifier = globals.verifier();
+    match watch_and_verify_with(verifier, claims, on_report, 500) {
+        Ok(()) => ExitCode::SUCCESS,
         Err(e) => {
-            eprintln!("Error parsing claims JSON: {}", e);
-            return ExitCode::from(3);
+            eprintln!("Watch error: {}", e);
+            ExitCode::from(3)
         }
-    };
+    }
+}
 
-    let verifier = Verifier::new();
-    let reports: Vec<VerificationReport> = claims.iter().map(|c| verifier.verify(c)).collect();
-
-    let worst_verdict = reports.iter().fold(Verdict::Confirmed, |acc, r| {
-        match (acc, r.overall_verdict) {
-            (_, Verdict::Refuted) | (Verdict::Refuted, _) => Verdict::Refuted,
-            (_, Verdict::Inconclusive) | (Verdict::Inconclusive, _) => Verdict::Inconclusive,
-            (_, Verdict::Unverifiable) | (Verdict::Unverifiable, _) => Verdict::Unverifiable,
-            (Verdict::Confirmed, Verdict::Confirmed) => Verdict::Confirmed,
-        }
-    });
-
-    match format {
-        OutputFormat::Human => {
-            println!("Verification Report");
-            println!("===================\n");
-            for report in &reports {
-                print_report(report);
-                println!();
+#[cfg(not(feature = "watch"))]
+fn watch_claims(_path: &str, _globals: &GlobalOptions) -> ExitCode {
+    eprintln!("Watch mode requires the 'watch' feature. Rebuild with:");
+    eprintln!("  cargo build --features watch");
+    ExitCode::from(3)
+}
+
+/// Options for the `stats` command
+#[derive(Debug, Clone, Default)]
+struct StatsOptions {
+    since: Option<DateTime<Utc>>,
+    json: bool,
+}
+
+const STATS_USAGE: &str = "Usage: dyadt [--history DIR] stats [--since 7d|RFC3339] [--json]";
+
+fn parse_stats_args(args: &[String]) -> Result<StatsOptions, String> {
+    let mut options = StatsOptions::default();
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--json" => options.json = true,
+            "--since" => {
+                let value = iter.next().ok_or("--since requires a value")?;
+                options.since = Some(parse_since(value, Utc::now())?);
             }
-            println!("-------------------");
-            println!("Overall: {:?}", worst_verdict);
+            other => return Err(format!("Unknown argument: {}", other)),
         }
-        OutputFormat::Json => {
-            output_json(&reports);
+    }
+
+    Ok(options)
+}
+
+/// Print confirmation rates from the verification history
+fn show_stats(globals: &GlobalOptions, options: &StatsOptions) -> ExitCode {
+    let Some(store) = &globals.history else {
+        eprintln!("No history configured: pass --history DIR or set DYADT_HISTORY");
+        return ExitCode::from(3);
+    };
+
+    let records = match store.records() {
+        Ok(records) => records,
+        Err(e) => {
+            eprintln!("Error reading {}: {}", store.path().display(), e);
+            return ExitCode::from(3);
         }
-        OutputFormat::Sarif => {
-            output_sarif(&reports);
+    };
+    let stats = HistoryStats::from_records(&records, options.since);
+
+    if options.json {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&stats).unwrap_or_else(|_| "{}".to_string())
+        );
+        return ExitCode::SUCCESS;
+    }
+
+    println!("History: {}", store.path().display());
+    match stats.since {
+        Some(since) => println!("Window:  since {}", since.to_rfc3339()),
+        None => println!("Window:  all time"),
+    }
+    println!("Claims:  {}", format_tally(&stats.claims));
+
+    for (title, groups) in [
+        ("By source", &stats.by_source),
+        ("By evidence type", &stats.by_evidence_type),
+        ("By session", &stats.by_session),
+    ] {
+        if groups.is_empty() {
+            continue;
         }
-        OutputFormat::JUnit => {
-            output_junit(&reports);
+        println!("\n{}:", title);
+        let width = groups.keys().map(|k| k.chars().count()).max().unwrap_or(0);
+        for (name, tally) in groups {
+            println!("  {:<width$}  {}", name, format_tally(tally), width = width);
         }
     }
 
-    verdict_to_exit_code(worst_verdict)
+    ExitCode::SUCCESS
 }
 
-/// Output reports as JSON
-fn output_json(reports: &[VerificationReport]) {
-    let output = serde_json::json!({
-        "$schema": "https://json-schema.org/draft/2020-12/schema",
-        "tool": {
-            "name": "did-you-actually-do-that",
-            "version": env!("CARGO_PKG_VERSION")
-        },
-        "reports": reports,
-        "summary": {
-            "total": reports.len(),
-            "confirmed": reports.iter().filter(|r| r.overall_verdict == Verdict::Confirmed).count(),
-            "refuted": reports.iter().filter(|r| r.overall_verdict == Verdict::Refuted).count(),
-            "inconclusive": reports.iter().filter(|r| r.overall_verdict == Verdict::Inconclusive).count(),
-            "unverifiable": reports.iter().filter(|r| r.overall_verdict == Verdict::Unverifiable).count()
-        }
-    });
-    println!("{}", serde_json::to_string_pretty(&output).unwrap());
+fn format_tally(tally: &Tally) -> String {
+    format!(
+        "{:>5} total  {:>5.1}% confirmed  {} refuted  {} other",
+        tally.total,
+        tally.confirmation_rate() * 100.0,
+        tally.refuted,
+        tally.inconclusive + tally.unverifiable
+    )
 }
 
-/// Output reports as SARIF (Static Analysis Results Interchange Format)
-/// Useful for GitHub code scanning integration
-fn output_sarif(reports: &[VerificationReport]) {
-    let results: Vec<serde_json::Value> = reports
-        .iter()
-        .flat_map(|report| {
-            report.evidence_results.iter().filter_map(|result| {
-                // Only include refuted or inconclusive results as findings
-                if result.verdict == Verdict::Confirmed {
-                    return None;
-                }
-
-                let level = match result.verdict {
-                    Verdict::Refuted => "error",
-                    Verdict::Inconclusive => "warning",
-                    Verdict::Unverifiable => "note",
-                    Verdict::Confirmed => unreachable!(),
-                };
-
-                let rule_id = match &result.spec {
-                    EvidenceSpec::FileExists { .. } => "file-exists",
-                    EvidenceSpec::FileWithHash { .. } => "file-hash",
-                    EvidenceSpec::FileContains { .. } => "file-contains",
-                    EvidenceSpec::FileMatchesRegex { .. } => "file-regex",
-                    EvidenceSpec::FileJsonPath { .. } => "file-json-path",
-                    EvidenceSpec::DirectoryExists { .. } => "directory-exists",
-                    EvidenceSpec::CommandSucceeds { .. } => "command-succeeds",
-                    EvidenceSpec::GitClean { .. } => "git-clean",
-                    EvidenceSpec::GitCommitExists { .. } => "git-commit-exists",
-                    EvidenceSpec::GitBranchExists { .. } => "git-branch-exists",
-                    EvidenceSpec::FileModifiedAfter { .. } => "file-modified-after",
-                    EvidenceSpec::EnvVar { .. } => "env-var",
-                    EvidenceSpec::Custom { .. } => "custom",
-                };
-
-                Some(serde_json::json!({
-                    "ruleId": rule_id,
-                    "level": level,
-                    "message": {
-                        "text": result.details.clone().unwrap_or_else(|| format!("{:?}", result.verdict))
-                    },
-                    "locations": [{
-                        "physicalLocation": {
-                            "artifactLocation": {
-                                "uri": get_evidence_path(&result.spec).unwrap_or_default()
-                            }
-                        }
-                    }],
-                    "properties": {
-                        "claim": report.claim.description.clone(),
-                        "claimId": report.claim.id.clone()
-                    }
-                }))
-            })
-        })
-        .collect();
-
-    let sarif = serde_json::json!({
-        "$schema": "https://raw.githubusercontent.com/oasis-tcs/sarif-spec/master/Schemata/sarif-schema-2.1.0.json",
-        "version": "2.1.0",
-        "runs": [{
-            "tool": {
-                "driver": {
-                    "name": "did-you-actually-do-that",
-                    "version": env!("CARGO_PKG_VERSION"),
-                    "informationUri": "https://gitlab.com/hyperpolymath/did-you-actually-do-that",
-                    "rules": [
-                        {"id": "file-exists", "shortDescription": {"text": "File existence check"}},
-                        {"id": "file-hash", "shortDescription": {"text": "File hash verification"}},
-                        {"id": "file-contains", "shortDescription": {"text": "File content check"}},
-                        {"id": "file-regex", "shortDescription": {"text": "File regex match"}},
-                        {"id": "file-json-path", "shortDescription": {"text": "JSON path verification"}},
-                        {"id": "directory-exists", "shortDescription": {"text": "Directory existence check"}},
-                        {"id": "command-succeeds", "shortDescription": {"text": "Command success verification"}},
-                        {"id": "git-clean", "shortDescription": {"text": "Git working directory check"}},
-                        {"id": "git-commit-exists", "shortDescription": {"text": "Git commit existence"}},
-                        {"id": "git-branch-exists", "shortDescription": {"text": "Git branch existence"}},
-                        {"id": "file-modified-after", "shortDescription": {"text": "File modification time check"}},
-                        {"id": "env-var", "shortDescription": {"text": "Environment variable check"}},
-                        {"id": "custom", "shortDescription": {"text": "Custom verification"}}
-                    ]
-                }
-            },
-            "results": results
-        }]
-    });
-
-    println!("{}", serde_json::to_string_pretty(&sarif).unwrap());
+/// Options for the `snapshot` command
+#[derive(Debug, Clone)]
+struct SnapshotOptions {
+    dir: String,
+    output: Option<String>,
+    ignore: Vec<String>,
 }
 
-/// Output reports as JUnit XML (for CI systems)
-fn output_junit(reports: &[VerificationReport]) {
-    let mut output = String::new();
-
-    let total_tests: usize = reports.iter().map(|r| r.evidence_results.len()).sum();
-    let failures: usize = reports
-        .iter()
-        .flat_map(|r| &r.evidence_results)
-        .filter(|e| e.verdict == Verdict::Refuted)
-        .count();
-    let errors: usize = reports
-        .iter()
-        .flat_map(|r| &r.evidence_results)
-        .filter(|e| e.verdict == Verdict::Unverifiable || e.verdict == Verdict::Inconclusive)
-        .count();
-
-    output.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
-    output.push_str(&format!(
-        "<testsuites tests=\"{}\" failures=\"{}\" errors=\"{}\">\n",
-        total_tests, failures, errors
-    ));
-
-    for report in reports {
-        let suite_tests = report.evidence_results.len();
-        let suite_failures = report
-            .evidence_results
-            .iter()
-            .filter(|e| e.verdict == Verdict::Refuted)
-            .count();
-        let suite_errors = report
-            .evidence_results
-            .iter()
-            .filter(|e| e.verdict == Verdict::Unverifiable || e.verdict == Verdict::Inconclusive)
-            .count();
-
-        output.push_str(&format!(
-            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\">\n",
-            xml_escape(&report.claim.description),
-            suite_tests,
-            suite_failures,
-            suite_errors
-        ));
-
-        for result in &report.evidence_results {
-            let test_name = format_evidence_name(&result.spec);
-            output.push_str(&format!(
-                "    <testcase name=\"{}\" classname=\"{}\">\n",
-                xml_escape(&test_name),
-                xml_escape(&report.claim.id)
-            ));
+const SNAPSHOT_USAGE: &str = "Usage: dyadt snapshot [--ignore NAME]... [-o FILE] <dir>";
 
-            match result.verdict {
-                Verdict::Confirmed => {}
-                Verdict::Refuted => {
-                    output.push_str(&format!(
-                        "      <failure message=\"{}\" type=\"Refuted\"/>\n",
-                        xml_escape(result.details.as_deref().unwrap_or("Evidence refuted"))
-                    ));
-                }
-                Verdict::Inconclusive | Verdict::Unverifiable => {
-                    output.push_str(&format!(
-                        "      <error message=\"{}\" type=\"{:?}\"/>\n",
-                        xml_escape(result.details.as_deref().unwrap_or("Verification error")),
-                        result.verdict
-                    ));
+fn parse_snapshot_args(args: &[String]) -> Result<SnapshotOptions, String> {
+    let mut dir = None;
+    let mut output = None;
+    let mut ignore = vec![".git".to_string()];
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--output" | "-o" => {
+                output = Some(iter.next().ok_or("--output requires a file")?.clone());
+            }
+            "--ignore" => ignore.push(iter.next().ok_or("--ignore requires a name")?.clone()),
+            flag if flag.starts_with("--") => return Err(format!("Unknown option: {}", flag)),
+            path => {
+                if dir.replace(path.to_string()).is_some() {
+                    return Err("Only one directory may be given".to_string());
                 }
             }
+        }
+    }
+
+    Ok(SnapshotOptions {
+        dir: dir.ok_or("Missing directory")?,
+        output,
+        ignore,
+    })
+}
 
-            output.push_str("    </testcase>\n");
+/// Record a manifest of every file under a directory
+fn take_snapshot(options: &SnapshotOptions) -> ExitCode {
+    let snapshot = match Snapshot::capture_ignoring(&options.dir, &options.ignore) {
+        Ok(snapshot) => snapshot,
+        Err(e) => {
+            eprintln!("Error capturing {}: {}", options.dir, e);
+            return ExitCode::from(3);
         }
+    };
 
-        output.push_str("  </testsuite>\n");
+    match &options.output {
+        Some(file) => {
+            if let Err(e) = snapshot.save(file) {
+                eprintln!("Error writing {}: {}", file, e);
+                return ExitCode::from(3);
+            }
+            eprintln!(
+                "Recorded {} files under {} in {}",
+                snapshot.files.len(),
+                snapshot.root.display(),
+                file
+            );
+        }
+        None => println!(
+            "{}",
+            serde_json::to_string_pretty(&snapshot).unwrap_or_else(|_| "{}".to_string())
+        ),
     }
-
-    output.push_str("</testsuites>\n");
-    print!("{}", output);
+    ExitCode::SUCCESS
 }
 
-/// Helper to escape XML special characters
-fn xml_escape(s: &str) -> String {
-    s.replace('&', "&amp;")
-        .replace('<', "&lt;")
-        .replace('>', "&gt;")
-        .replace('"', "&quot;")
-        .replace('\'', "&apos;")
+/// Output format for the `diff` command
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum DiffFormat {
+    Human,
+    Json,
+    Claims,
 }
 
-/// Helper to get path from evidence spec
-fn get_evidence_path(spec: &EvidenceSpec) -> Option<String> {
-    match spec {
-        EvidenceSpec::FileExists { path }
-        | EvidenceSpec::FileWithHash { path, .. }
-        | EvidenceSpec::FileContains { path, .. }
-        | EvidenceSpec::FileMatchesRegex { path, .. }
-        | EvidenceSpec::FileJsonPath { path, .. }
-        | EvidenceSpec::FileModifiedAfter { path, .. } => Some(path.clone()),
-        EvidenceSpec::DirectoryExists { path } => Some(path.clone()),
-        EvidenceSpec::GitClean { repo_path }
-        | EvidenceSpec::GitCommitExists { repo_path, .. }
-        | EvidenceSpec::GitBranchExists { repo_path, .. } => {
-            repo_path.clone().or_else(|| Some(".".to_string()))
-        }
-        EvidenceSpec::CommandSucceeds { command, .. } => Some(command.clone()),
-        EvidenceSpec::EnvVar { name, .. } => Some(format!("${}", name)),
-        EvidenceSpec::Custom { name, .. } => Some(name.clone()),
-    }
+/// Options for the `diff` command
+#[derive(Debug, Clone)]
+struct DiffOptions {
+    snapshot: String,
+    format: DiffFormat,
+    /// Agent transcript whose claims are compared with the real changes
+    cross_check: Option<String>,
 }
 
-/// Helper to format evidence name for JUnit
-fn format_evidence_name(spec: &EvidenceSpec) -> String {
-    match spec {
-        EvidenceSpec::FileExists { path } => format!("FileExists: {}", path),
-        EvidenceSpec::FileWithHash { path, .. } => format!("FileWithHash: {}", path),
-        EvidenceSpec::FileContains { path, substring } => {
-            format!("FileContains '{}': {}", substring, path)
-        }
-        EvidenceSpec::FileMatchesRegex { path, pattern } => {
-            format!("FileMatchesRegex '{}': {}", pattern, path)
-        }
-        EvidenceSpec::FileJsonPath {
-            path, json_path, ..
-        } => {
-            format!("FileJsonPath '{}': {}", json_path, path)
-        }
-        EvidenceSpec::DirectoryExists { path } => format!("DirectoryExists: {}", path),
-        EvidenceSpec::CommandSucceeds { command, .. } => format!("CommandSucceeds: {}", command),
-        EvidenceSpec::GitClean { repo_path } => {
-            format!("GitClean: {}", repo_path.as_deref().unwrap_or("."))
-        }
-        EvidenceSpec::GitCommitExists { commit, .. } => format!("GitCommitExists: {}", commit),
-        EvidenceSpec::GitBranchExists { branch, .. } => format!("GitBranchExists: {}", branch),
-        EvidenceSpec::FileModifiedAfter { path, after } => {
-            format!("FileModifiedAfter {}: {}", after, path)
-        }
-        EvidenceSpec::EnvVar { name, expected } => format!("EnvVar {}={}", name, expected),
-        EvidenceSpec::Custom { name, .. } => format!("Custom: {}", name),
+const DIFF_USAGE: &str =
+    "Usage: dyadt diff [--json|--claims] [--cross-check <transcript.txt>] <snapshot.json>";
+
+fn parse_diff_args(args: &[String]) -> Result<DiffOptions, String> {
+    let mut snapshot = None;
+    let mut format = DiffFormat::Human;
+    let mut cross_check = None;
+    let mut iter = args.iter();
+
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--json" => format = DiffFormat::Json,
+            "--claims" => format = DiffFormat::Claims,
+            "--cross-check" => {
+                cross_check = Some(iter.next().ok_or("--cross-check requires a file")?.clone());
+            }
+            flag if flag.starts_with("--") => return Err(format!("Unknown option: {}", flag)),
+            file => {
+                if snapshot.replace(file.to_string()).is_some() {
+                    return Err("Only one snapshot file may be given".to_string());
+                }
+            }
+        }
     }
-}
 
-/// Watch mode for continuous verification
-#[cfg(feature = "watch")]
-fn watch_claims(path: &str) -> ExitCode {
-    use did_you_actually_do_that::watch::watch_and_verify;
+    Ok(DiffOptions {
+        snapshot: snapshot.ok_or("Missing snapshot file")?,
+        format,
+        cross_check,
+    })
+}
 
-    let contents = match fs::read_to_string(path) {
-        Ok(c) => c,
+/// Show what changed since a snapshot, optionally checking an agent's account of it
+fn diff_snapshot(options: &DiffOptions) -> ExitCode {
+    let snapshot = match Snapshot::load(&options.snapshot) {
+        Ok(snapshot) => snapshot,
         Err(e) => {
-            eprintln!("Error reading {}: {}", path, e);
+            eprintln!("Error reading snapshot {}: {}", options.snapshot, e);
             return ExitCode::from(3);
         }
     };
-
-    let claims: Vec<Claim> = match serde_json::from_str(&contents) {
-        Ok(c) => c,
+    let diff = match snapshot.diff_now() {
+        Ok(diff) => diff,
         Err(e) => {
-            eprintln!("Error parsing claims JSON: {}", e);
+            eprintln!("Error capturing {}: {}", snapshot.root.display(), e);
             return ExitCode::from(3);
         }
     };
 
-    let on_report = |report: &VerificationReport| {
-        print_report(report);
-        let _ = std::io::stdout().flush();
+    let cross_check = match &options.cross_check {
+        Some(transcript) => {
+            let text = match fs::read_to_string(transcript) {
+                Ok(text) => text,
+                Err(e) => {
+                    eprintln!("Error reading {}: {}", transcript, e);
+                    return ExitCode::from(3);
+                }
+            };
+            let base = env::current_dir().unwrap_or_else(|_| snapshot.root.clone());
+            let context = ExtractionContext {
+                source: "dyadt-cross-check".to_string(),
+                working_directory: Some(base.to_string_lossy().into_owned()),
+                ..Default::default()
+            };
+            let claims = ClaimExtractor::new().extract_from_text(&text, &context);
+            Some(diff.cross_check(&claims, &snapshot.root, &base))
+        }
+        None => None,
     };
 
-    match watch_and_verify(claims, on_report, 500) {
-        Ok(()) => ExitCode::SUCCESS,
-        Err(e) => {
-            eprintln!("Watch error: {}", e);
-            ExitCode::from(3)
+    match options.format {
+        DiffFormat::Claims => println!(
+            "{}",
+            serde_json::to_string_pretty(&diff.to_claims()).unwrap_or_else(|_| "[]".to_string())
+        ),
+        DiffFormat::Json => {
+            let json = serde_json::json!({ "diff": diff, "cross_check": cross_check });
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&json).unwrap_or_else(|_| "{}".to_string())
+            );
+        }
+        DiffFormat::Human => {
+            println!(
+                "Changes under {} since {}:",
+                snapshot.root.display(),
+                snapshot.taken_at.to_rfc3339()
+            );
+            for (label, changes) in [
+                ("created", &diff.created),
+                ("modified", &diff.modified),
+                ("deleted", &diff.deleted),
+            ] {
+                for change in changes {
+                    println!("  {:<9}{}", label, change.path);
+                }
+            }
+            if diff.is_empty() {
+                println!("  (no changes)");
+            }
+
+            if let Some(check) = &cross_check {
+                println!("\nCross-check against claimed changes:");
+                for claimed in &check.confirmed {
+                    println!("  ✓ {} ({})", claimed.path, claimed.claim);
+                }
+                for claimed in &check.claimed_but_unchanged {
+                    println!(
+                        "  ✗ claimed but unchanged: {} ({})",
+                        claimed.path, claimed.claim
+                    );
+                }
+                for path in &check.unclaimed_changes {
+                    println!("  ? changed but not claimed: {}", path);
+                }
+            }
         }
     }
-}
 
-#[cfg(not(feature = "watch"))]
-fn watch_claims(_path: &str) -> ExitCode {
-    eprintln!("Watch mode requires the 'watch' feature. Rebuild with:");
-    eprintln!("  cargo build --features watch");
-    ExitCode::from(3)
+    match cross_check {
+        Some(check) if !check.is_consistent() => ExitCode::from(1),
+        _ => ExitCode::SUCCESS,
+    }
 }
 
 /// Run the MCP server
-fn run_mcp_server() -> ExitCode {
-    let server = McpServer::new();
+fn run_mcp_server(globals: &GlobalOptions) -> ExitCode {
+    // Each server process is one session in the history
+    let mut globals = globals.clone();
+    globals.history = globals.history.map(|store| store.for_mcp_session());
+    let server = McpServer::with_verifier(globals.verifier());
     match server.run() {
         Ok(()) => ExitCode::SUCCESS,
         Err(e) => {
@@ -563,7 +813,7 @@ fn run_mcp_server() -> ExitCode {
 }
 
 /// Process a hook event from Claude Code
-fn run_hook(json_input: &str) -> ExitCode {
+fn run_hook(json_input: &str, globals: &GlobalOptions) -> ExitCode {
     let input = match parse_hook_input(json_input) {
         Ok(i) => i,
         Err(e) => {
@@ -572,7 +822,7 @@ fn run_hook(json_input: &str) -> ExitCode {
         }
     };
 
-    let handler = HookHandler::new();
+    let handler = HookHandler::with_verifier(globals.verifier());
     let output = handler.handle(&input);
 
     // Output JSON result
@@ -586,7 +836,14 @@ fn run_hook(json_input: &str) -> ExitCode {
 }
 
 fn main() -> ExitCode {
-    let args: Vec<String> = env::args().collect();
+    let mut args: Vec<String> = env::args().collect();
+    let globals = match GlobalOptions::take(&mut args) {
+        Ok(globals) => globals,
+        Err(e) => {
+            eprintln!("{}", e);
+            return ExitCode::from(3);
+        }
+    };
 
     if args.len() < 2 {
         print_help();
@@ -599,7 +856,7 @@ fn main() -> ExitCode {
                 eprintln!("Usage: dyadt check <claim.json>");
                 ExitCode::from(3)
             } else {
-                verify_claim_file(&args[2])
+                verify_claim_file(&args[2], &globals)
             }
         }
         "verify" => {
@@ -607,61 +864,87 @@ fn main() -> ExitCode {
                 eprintln!("Usage: dyadt verify <path>");
                 ExitCode::from(3)
             } else {
-                quick_verify(&args[2])
+                quick_verify(&args[2], &globals)
             }
         }
-        "hash" => {
+        "schema" => {
+            println!(
+                "{}",
+                serde_json::to_string_pretty(&schema::claims_file_schema()).unwrap_or_default()
+            );
+            ExitCode::SUCCESS
+        }
+        "validate" => {
             if args.len() < 3 {
-                eprintln!("Usage: dyadt hash <file>");
+                eprintln!("Usage: dyadt validate <claims.json>");
                 ExitCode::from(3)
             } else {
-                compute_hash(&args[2])
+                validate_claims(&args[2], &globals)
             }
         }
-        "report" => {
+        "expand" => {
             if args.len() < 3 {
-                eprintln!("Usage: dyadt report [--json|--sarif|--junit] <claims.json>");
+                eprintln!("Usage: dyadt expand <claims.json>");
                 ExitCode::from(3)
-            } else if args[2] == "--json" {
-                if args.len() < 4 {
-                    eprintln!("Usage: dyadt report --json <claims.json>");
-                    ExitCode::from(3)
-                } else {
-                    verify_multiple_with_format(&args[3], OutputFormat::Json)
-                }
-            } else if args[2] == "--sarif" {
-                if args.len() < 4 {
-                    eprintln!("Usage: dyadt report --sarif <claims.json>");
-                    ExitCode::from(3)
-                } else {
-                    verify_multiple_with_format(&args[3], OutputFormat::Sarif)
-                }
-            } else if args[2] == "--junit" {
-                if args.len() < 4 {
-                    eprintln!("Usage: dyadt report --junit <claims.json>");
-                    ExitCode::from(3)
-                } else {
-                    verify_multiple_with_format(&args[3], OutputFormat::JUnit)
-                }
             } else {
-                verify_multiple(&args[2])
+                expand_claims(&args[2], &globals)
             }
         }
+        "hash" => {
+            if args.len() < 3 {
+                eprintln!("Usage: dyadt hash <file>");
+                ExitCode::from(3)
+            } else {
+                compute_hash(&args[2])
+            }
+        }
+        "snapshot" => match parse_snapshot_args(&args[2..]) {
+            Ok(options) => take_snapshot(&options),
+            Err(e) => {
+                eprintln!("{}", e);
+                eprintln!("{}", SNAPSHOT_USAGE);
+                ExitCode::from(3)
+            }
+        },
+        "diff" => match parse_diff_args(&args[2..]) {
+            Ok(options) => diff_snapshot(&options),
+            Err(e) => {
+                eprintln!("{}", e);
+                eprintln!("{}", DIFF_USAGE);
+                ExitCode::from(3)
+            }
+        },
+        "report" => match parse_report_args(&args[2..]) {
+            Ok((options, path)) => verify_multiple_with_format(&path, &options, &globals),
+            Err(e) => {
+                eprintln!("{}", e);
+                eprintln!("{}", REPORT_USAGE);
+                ExitCode::from(3)
+            }
+        },
         "watch" => {
             if args.len() < 3 {
                 eprintln!("Usage: dyadt watch <claims.json>");
                 ExitCode::from(3)
             } else {
-                watch_claims(&args[2])
+                watch_claims(&args[2], &globals)
             }
         }
-        "mcp-server" => run_mcp_server(),
+        "stats" => match parse_stats_args(&args[2..]) {
+            Ok(options) => show_stats(&globals, &options),
+            Err(e) => {
+                eprintln!("{}", e);
+                eprintln!("{}", STATS_USAGE);
+                ExitCode::from(3)
+            }
+        },
+        "mcp-server" => run_mcp_server(&globals),
         "hook" => {
             if args.len() < 3 {
                 eprintln!("Usage: dyadt hook <json>");
                 ExitCode::from(3)
             } else {
-                run_hook(&args[2])
+                run_hook(&args[2], &globals)
             }
         }
         "help" | "--help" | "-h" => {
@@ -0,0 +1,597 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Claim manifests: variables, evidence templates and includes
+//!
+//! A claims file may be a single claim, an array of claims, or a manifest:
+//!
+//! ```json
+//! {
+//!   "include": ["common/claims.json"],
+//!   "vars": { "crate": "core" },
+//!   "templates": {
+//!     "rust-module": [
+//!       { "type": "FileExists", "spec": { "path": "${dir}/${name}.rs" } },
+//!       { "type": "SymbolDefined", "spec": { "path": "${dir}/${name}.rs", "symbol": "${symbol}" } }
+//!     ]
+//!   },
+//!   "claims": [{
+//!     "description": "Added the parser",
+//!     "evidence": [
+//!       { "template": "rust-module", "with": { "dir": "${crate}/src", "name": "parser", "symbol": "parse" } }
+//!     ]
+//!   }]
+//! }
+//! ```
+//!
+//! Everything is expanded into plain [`Claim`]s before verification. Only
+//! manifests are expanded: a single claim or an array of claims is used as
+//! written, `${...}` and all.
+//!
+//! - `${NAME}` in any string is replaced by a variable, and `${NAME:-text}`
+//!   falls back to `text` when `NAME` is undefined. `$${` is a literal `${`.
+//!   Template parameters shadow `--var` values, which shadow the
+//!   environment, which shadows the manifest's `vars`. A string that is
+//!   exactly one `${NAME}` takes the variable's JSON value, so numeric
+//!   parameters stay numbers.
+//! - `{"template": NAME, "with": {...}}` stands for a template's evidence
+//!   (one spec or an array of specs, spliced in place), with `with` as its
+//!   parameters. References work inside composite evidence and templates.
+//! - `include` lists manifests to load first, relative to the including
+//!   file. Their claims come before this file's, and their variables and
+//!   templates are visible here unless this file redefines them.
+//!
+//! ```rust
+//! use did_you_actually_do_that::manifest::ManifestLoader;
+//! use serde_json::json;
+//!
+//! let manifest = json!({
+//!     "templates": { "exists": { "type": "FileExists", "spec": { "path": "${path}" } } },
+//!     "claims": [{
+//!         "description": "Wrote the config",
+//!         "evidence": [{ "template": "exists", "with": { "path": "${dir}/app.toml" } }]
+//!     }]
+//! });
+//! let loader = ManifestLoader::new().with_var("dir", "config");
+//! let claims = loader.expand(manifest, ".".as_ref()).unwrap();
+//! assert_eq!(claims[0]["evidence"][0]["spec"]["path"], "config/app.toml");
+//! ```
+
+use crate::{Claim, IdScheme};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// The object form of a claims file
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Manifest {
+    #[serde(default)]
+    include: Vec<String>,
+    #[serde(default)]
+    vars: Map<String, Value>,
+    #[serde(default)]
+    templates: Map<String, Value>,
+    #[serde(default)]
+    claims: Vec<Value>,
+}
+
+/// What a loaded manifest makes visible to files that include it
+#[derive(Debug, Default)]
+struct Scope {
+    vars: Map<String, Value>,
+    templates: Map<String, Value>,
+}
+
+/// Expands claim manifests into claims
+#[derive(Debug, Clone, Default)]
+pub struct ManifestLoader {
+    vars: HashMap<String, Value>,
+    ids: IdScheme,
+}
+
+impl ManifestLoader {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Define a variable, as `--var name=value` does
+    pub fn with_var(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.vars.insert(name.into(), Value::String(value.into()));
+        self
+    }
+
+    /// How claims without an `id` get one. With [`IdScheme::Uuid7`] the
+    /// expanded JSON carries the generated ids.
+    pub fn with_id_scheme(mut self, ids: IdScheme) -> Self {
+        self.ids = ids;
+        self
+    }
+
+    /// Load and expand a claims file
+    pub fn load_file(&self, path: impl AsRef<Path>) -> Result<Vec<Claim>, String> {
+        self.expand_file(path)?
+            .into_iter()
+            .map(|claim| serde_json::from_value(claim).map_err(|e| e.to_string()))
+            .collect()
+    }
+
+    /// Expand a claims file into the JSON of its claims
+    pub fn expand_file(&self, path: impl AsRef<Path>) -> Result<Vec<Value>, String> {
+        let mut stack = Vec::new();
+        let (_, claims) = self.load(path.as_ref(), &mut stack)?;
+        Ok(claims)
+    }
+
+    /// Expand an already-parsed claims file; includes resolve against `dir`
+    pub fn expand(&self, document: Value, dir: &Path) -> Result<Vec<Value>, String> {
+        let (_, claims) = self.expand_document(document, None, dir, &mut Vec::new())?;
+        Ok(claims)
+    }
+
+    fn load(&self, path: &Path, stack: &mut Vec<PathBuf>) -> Result<(Scope, Vec<Value>), String> {
+        let canonical = path
+            .canonicalize()
+            .map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+        if let Some(start) = stack.iter().position(|p| *p == canonical) {
+            let cycle: Vec<String> = stack[start..]
+                .iter()
+                .chain([&canonical])
+                .map(|p| p.display().to_string())
+                .collect();
+            return Err(format!("Include cycle: {}", cycle.join(" -> ")));
+        }
+
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| format!("Cannot read {}: {}", path.display(), e))?;
+        let document: Value = serde_json::from_str(&text)
+            .map_err(|e| format!("{}: invalid JSON: {}", path.display(), e))?;
+        let dir = path.parent().unwrap_or(Path::new("."));
+
+        stack.push(canonical);
+        let result = self.expand_document(document, Some(path), dir, stack);
+        stack.pop();
+        result
+    }
+
+    fn expand_document(
+        &self,
+        document: Value,
+        origin: Option<&Path>,
+        dir: &Path,
+        stack: &mut Vec<PathBuf>,
+    ) -> Result<(Scope, Vec<Value>), String> {
+        // Errors from included files already name their own file
+        let here = |e: String| match origin {
+            Some(path) => format!("{}: {}", path.display(), e),
+            None => e,
+        };
+        let interpolated = is_manifest(&document);
+        let manifest = match document {
+            Value::Array(claims) => Manifest {
+                claims,
+                ..Manifest::default()
+            },
+            Value::Object(_) if interpolated => serde_json::from_value(document)
+                .map_err(|e| here(format!("invalid manifest: {}", e)))?,
+            claim @ Value::Object(_) => Manifest {
+                claims: vec![claim],
+                ..Manifest::default()
+            },
+            _ => {
+                return Err(here(
+                    "expected a claim, an array of claims or a manifest".to_string(),
+                ))
+            }
+        };
+
+        let mut scope = Scope::default();
+        let mut claims = Vec::new();
+        for include in &manifest.include {
+            let (included, included_claims) = self.load(&dir.join(include), stack)?;
+            scope.vars.extend(included.vars);
+            scope.templates.extend(included.templates);
+            claims.extend(included_claims);
+        }
+        scope.vars.extend(manifest.vars);
+        scope.templates.extend(manifest.templates);
+
+        for (n, claim) in manifest.claims.into_iter().enumerate() {
+            // Plain claims are taken as written, so a literal `${...}` in
+            // them is never replaced
+            let claim = if interpolated {
+                self.expand_claim(claim, &scope)
+            } else {
+                self.assign_id(claim)
+            };
+            let claim = claim.map_err(|e| here(format!("claim {}: {}", n + 1, e)))?;
+            claims.push(claim);
+        }
+        Ok((scope, claims))
+    }
+
+    fn expand_claim(&self, claim: Value, scope: &Scope) -> Result<Value, String> {
+        let params = Map::new();
+        let mut claim = interpolate(claim, &|name| self.lookup(name, &params, scope))?;
+        if let Some(evidence) = claim.get_mut("evidence") {
+            let specs = std::mem::take(evidence);
+            *evidence = Value::Array(self.expand_evidence(specs, scope, &mut Vec::new())?);
+        }
+        self.assign_id(claim)
+    }
+
+    /// Check that `claim` is a valid claim and give it an id from the
+    /// loader's scheme if it has none
+    fn assign_id(&self, mut claim: Value) -> Result<Value, String> {
+        let parsed: Claim = serde_json::from_value(claim.clone()).map_err(|e| e.to_string())?;
+        if self.ids == IdScheme::Uuid7 && claim.get("id").is_none() {
+            claim["id"] = Value::String(self.ids.id_for(&parsed));
+        }
+        Ok(claim)
+    }
+
+    /// Expand one evidence value (a spec, a template reference or an array
+    /// of either) into the specs it stands for
+    fn expand_evidence(
+        &self,
+        evidence: Value,
+        scope: &Scope,
+        templates: &mut Vec<String>,
+    ) -> Result<Vec<Value>, String> {
+        match evidence {
+            Value::Array(items) => {
+                let mut specs = Vec::new();
+                for item in items {
+                    specs.extend(self.expand_evidence(item, scope, templates)?);
+                }
+                Ok(specs)
+            }
+            Value::Object(mut object) if object.contains_key("template") => {
+                let name = match object.remove("template") {
+                    Some(Value::String(name)) => name,
+                    _ => return Err("template name must be a string".to_string()),
+                };
+                let params = match object.remove("with") {
+                    Some(Value::Object(params)) => params,
+                    None => Map::new(),
+                    Some(_) => {
+                        return Err(format!("'with' of template '{}' must be an object", name))
+                    }
+                };
+                if let Some(key) = object.keys().next() {
+                    return Err(format!(
+                        "Unexpected key '{}' in use of template '{}'",
+                        key, name
+                    ));
+                }
+                if templates.contains(&name) {
+                    templates.push(name);
+                    return Err(format!("Template cycle: {}", templates.join(" -> ")));
+                }
+                let body = scope
+                    .templates
+                    .get(&name)
+                    .ok_or_else(|| format!("Unknown template '{}'", name))?
+                    .clone();
+                let body = interpolate(body, &|var| self.lookup(var, &params, scope))
+                    .map_err(|e| format!("template '{}': {}", name, e))?;
+
+                templates.push(name);
+                let specs = self.expand_evidence(body, scope, templates);
+                templates.pop();
+                specs
+            }
+            Value::Object(mut object) => {
+                // Composite evidence nests specs under `spec.evidence`
+                if let Some(nested) = object
+                    .get_mut("spec")
+                    .and_then(|spec| spec.get_mut("evidence"))
+                {
+                    let single = !nested.is_array();
+                    let mut specs =
+                        self.expand_evidence(std::mem::take(nested), scope, templates)?;
+                    *nested = if single {
+                        if specs.len() != 1 {
+                            return Err(format!(
+                                "{} takes one piece of evidence, but its template expands to {}",
+                                object["type"].as_str().unwrap_or("Evidence"),
+                                specs.len()
+                            ));
+                        }
+                        specs.remove(0)
+                    } else {
+                        Value::Array(specs)
+                    };
+                }
+                Ok(vec![Value::Object(object)])
+            }
+            other => Ok(vec![other]),
+        }
+    }
+
+    fn lookup(&self, name: &str, params: &Map<String, Value>, scope: &Scope) -> Option<Value> {
+        params
+            .get(name)
+            .or_else(|| self.vars.get(name))
+            .cloned()
+            .or_else(|| std::env::var(name).ok().map(Value::String))
+            .or_else(|| scope.vars.get(name).cloned())
+    }
+}
+
+/// Whether a claims file uses the manifest form rather than plain claims
+pub(crate) fn is_manifest(document: &Value) -> bool {
+    document.as_object().is_some_and(|object| {
+        ["include", "vars", "templates", "claims"]
+            .iter()
+            .any(|key| object.contains_key(*key))
+    })
+}
+
+/// Replace `${NAME}` references in every string of `value`
+fn interpolate(value: Value, lookup: &dyn Fn(&str) -> Option<Value>) -> Result<Value, String> {
+    match value {
+        Value::String(s) => interpolate_str(&s, lookup),
+        Value::Array(items) => items
+            .into_iter()
+            .map(|item| interpolate(item, lookup))
+            .collect::<Result<_, _>>()
+            .map(Value::Array),
+        Value::Object(object) => object
+            .into_iter()
+            .map(|(key, item)| Ok((key, interpolate(item, lookup)?)))
+            .collect::<Result<_, String>>()
+            .map(Value::Object),
+        other => Ok(other),
+    }
+}
+
+fn interpolate_str(s: &str, lookup: &dyn Fn(&str) -> Option<Value>) -> Result<Value, String> {
+    let resolve = |reference: &str| -> Result<Value, String> {
+        let (name, default) = match reference.split_once(":-") {
+            Some((name, default)) => (name, Some(default)),
+            None => (reference, None),
+        };
+        lookup(name.trim())
+            .or_else(|| default.map(|d| Value::String(d.to_string())))
+            .ok_or_else(|| format!("Undefined variable '{}'", name.trim()))
+    };
+
+    // A lone reference keeps the variable's JSON type
+    if let Some(reference) = s.strip_prefix("${").and_then(|r| r.strip_suffix('}')) {
+        if !reference.contains(['{', '}']) {
+            return resolve(reference);
+        }
+    }
+
+    let mut out = String::new();
+    let mut rest = s;
+    while let Some(pos) = rest.find('$') {
+        out.push_str(&rest[..pos]);
+        let tail = &rest[pos..];
+        if let Some(after) = tail.strip_prefix("$${") {
+            out.push_str("${");
+            rest = after;
+        } else if let Some(after) = tail.strip_prefix("${") {
+            let end = after
+                .find('}')
+                .ok_or_else(|| format!("Unterminated '${{' in \"{}\"", s))?;
+            match resolve(&after[..end])? {
+                Value::String(value) => out.push_str(&value),
+                value => out.push_str(&value.to_string()),
+            }
+            rest = &after[end + 1..];
+        } else {
+            out.push('$');
+            rest = &tail[1..];
+        }
+    }
+    out.push_str(rest);
+    Ok(Value::String(out))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn write(dir: &Path, name: &str, value: Value) -> PathBuf {
+        let path = dir.join(name);
+        std::fs::write(&path, value.to_string()).unwrap();
+        path
+    }
+
+    fn exists(path: &str) -> Value {
+        json!({ "type": "FileExists", "spec": { "path": path } })
+    }
+
+    #[test]
+    fn test_interpolation_precedence_and_defaults() {
+        std::env::set_var("DYADT_MANIFEST_TEST_ENV", "from-env");
+        let manifest = json!({
+            "vars": { "a": "manifest", "DYADT_MANIFEST_TEST_ENV": "manifest" },
+            "claims": [{
+                "description": "${a} ${DYADT_MANIFEST_TEST_ENV} ${missing:-fallback} $${a} $5",
+                "evidence": []
+            }]
+        });
+        let claims = ManifestLoader::new()
+            .expand(manifest.clone(), Path::new("."))
+            .unwrap();
+        assert_eq!(
+            claims[0]["description"],
+            "manifest from-env fallback ${a} $5"
+        );
+
+        let claims = ManifestLoader::new()
+            .with_var("a", "cli")
+            .with_var("DYADT_MANIFEST_TEST_ENV", "cli")
+            .expand(manifest, Path::new("."))
+            .unwrap();
+        assert_eq!(claims[0]["description"], "cli cli fallback ${a} $5");
+
+        let err = ManifestLoader::new()
+            .expand(
+                json!({ "claims": [{ "description": "${nope}", "evidence": [] }] }),
+                Path::new("."),
+            )
+            .unwrap_err();
+        assert_eq!(err, "claim 1: Undefined variable 'nope'");
+    }
+
+    #[test]
+    fn test_templates_splice_and_nest() {
+        let manifest = json!({
+            "templates": {
+                "exists": exists("${path}"),
+                "module": [
+                    { "template": "exists", "with": { "path": "src/${name}.rs" } },
+                    { "type": "CommandSucceeds", "spec": { "command": "true", "args": [], "timeout_secs": "${timeout}" } }
+                ]
+            },
+            "claims": [{
+                "description": "Added modules",
+                "evidence": [
+                    exists("Cargo.toml"),
+                    { "template": "module", "with": { "name": "parser", "timeout": 5 } },
+                    { "type": "Not", "spec": { "evidence": { "template": "exists", "with": { "path": "old.rs" } } } }
+                ]
+            }]
+        });
+        let claims = ManifestLoader::new()
+            .expand(manifest, Path::new("."))
+            .unwrap();
+        let evidence = claims[0]["evidence"].as_array().unwrap();
+        assert_eq!(evidence.len(), 4);
+        assert_eq!(evidence[1], exists("src/parser.rs"));
+        assert_eq!(evidence[2]["spec"]["timeout_secs"], 5);
+        assert_eq!(evidence[3]["spec"]["evidence"], exists("old.rs"));
+    }
+
+    #[test]
+    fn test_template_errors() {
+        let expand = |templates: Value, evidence: Value| {
+            let manifest = json!({
+                "templates": templates,
+                "claims": [{ "description": "x", "evidence": [evidence] }]
+            });
+            ManifestLoader::new()
+                .expand(manifest, Path::new("."))
+                .unwrap_err()
+        };
+        assert_eq!(
+            expand(json!({}), json!({ "template": "nope" })),
+            "claim 1: Unknown template 'nope'"
+        );
+        assert_eq!(
+            expand(
+                json!({ "a": { "template": "b" }, "b": [{ "template": "a" }] }),
+                json!({ "template": "a" })
+            ),
+            "claim 1: Template cycle: a -> b -> a"
+        );
+        assert!(
+            expand(json!({ "a": exists("${p}") }), json!({ "template": "a" }))
+                .contains("Undefined variable 'p'")
+        );
+    }
+
+    #[test]
+    fn test_includes_merge_and_detect_cycles() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::create_dir(dir.path().join("common")).unwrap();
+        write(
+            &dir.path().join("common"),
+            "base.json",
+            json!({
+                "vars": { "root": "base" },
+                "templates": { "exists": exists("${root}/${path}") },
+                "claims": [{ "description": "base claim", "evidence": [exists("${root}")] }]
+            }),
+        );
+        let main = write(
+            dir.path(),
+            "claims.json",
+            json!({
+                "include": ["common/base.json"],
+                "vars": { "root": "main" },
+                "claims": [{
+                    "description": "main claim",
+                    "evidence": [{ "template": "exists", "with": { "path": "lib.rs" } }]
+                }]
+            }),
+        );
+
+        let claims = ManifestLoader::new().load_file(&main).unwrap();
+        assert_eq!(claims.len(), 2);
+        assert_eq!(claims[0].description, "base claim");
+        match &claims[0].evidence[0] {
+            crate::EvidenceSpec::FileExists { path } => assert_eq!(path, "base"),
+            other => panic!("unexpected {:?}", other),
+        }
+        match &claims[1].evidence[0] {
+            crate::EvidenceSpec::FileExists { path } => assert_eq!(path, "main/lib.rs"),
+            other => panic!("unexpected {:?}", other),
+        }
+
+        write(dir.path(), "a.json", json!({ "include": ["b.json"] }));
+        write(dir.path(), "b.json", json!({ "include": ["a.json"] }));
+        let err = ManifestLoader::new()
+            .expand_file(dir.path().join("a.json"))
+            .unwrap_err();
+        assert!(err.starts_with("Include cycle: "), "{}", err);
+        assert!(err.ends_with("a.json"), "{}", err);
+    }
+
+    #[test]
+    fn test_uuid7_ids_only_fill_missing_ids() {
+        let manifest = json!([
+            { "id": "kept", "description": "a", "evidence": [] },
+            { "description": "b", "evidence": [] }
+        ]);
+        let loader = ManifestLoader::new().with_id_scheme(IdScheme::Uuid7);
+        let claims = loader.expand(manifest.clone(), Path::new(".")).unwrap();
+        assert_eq!(claims[0]["id"], "kept");
+        assert_eq!(claims[1]["id"].as_str().unwrap().len(), 36);
+
+        let claims = ManifestLoader::new()
+            .expand(manifest, Path::new("."))
+            .unwrap();
+        assert!(claims[1].get("id").is_none());
+    }
+
+    #[test]
+    fn test_plain_claim_files_still_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let single = write(
+            dir.path(),
+            "one.json",
+            json!({ "description": "one", "evidence": [exists("x")] }),
+        );
+        assert_eq!(ManifestLoader::new().load_file(&single).unwrap().len(), 1);
+
+        let invalid = write(
+            dir.path(),
+            "bad.json",
+            json!({ "claims": [{ "description": "no evidence" }] }),
+        );
+        let err = ManifestLoader::new().load_file(&invalid).unwrap_err();
+        assert!(err.contains("claim 1: missing field `evidence`"), "{}", err);
+    }
+
+    #[test]
+    fn test_plain_claims_are_not_interpolated() {
+        std::env::set_var("DYADT_MANIFEST_TEST_PLAIN", "secret");
+        let claim = json!({
+            "description": "Wrote ${DYADT_MANIFEST_TEST_PLAIN}",
+            "evidence": [{
+                "type": "FileContains",
+                "spec": { "path": "app.env", "substring": "DB=${DATABASE_URL}" }
+            }]
+        });
+        let loader = ManifestLoader::new().with_var("DATABASE_URL", "postgres://");
+        for document in [claim.clone(), json!([claim.clone()])] {
+            let claims = loader.expand(document, Path::new(".")).unwrap();
+            assert_eq!(claims, vec![claim.clone()]);
+        }
+    }
+}
@@ -31,9 +31,12 @@
 //! }
 //! ```
 
-use crate::{Claim, EvidenceSpec, Verdict, VerificationReport, Verifier};
+use crate::history::HistoryStore;
+use crate::report;
+use crate::schema;
+use crate::{Claim, EvidenceSpec, Verifier};
 use serde::{Deserialize, Serialize};
-use serde_json::{json, Value};
+use serde_json::{json, Map, Value};
 use sha2::{Digest, Sha256};
 use std::io::{self, BufRead, Write};
 
@@ -92,10 +95,28 @@ impl Default for McpServer {
 
 impl McpServer {
     /// Create a new MCP server
+    ///
+    /// If `DYADT_HISTORY` is set, every verified claim is recorded there under
+    /// a session named after the server's start time. If that history cannot
+    /// be opened, a warning goes to stderr and nothing is recorded.
     pub fn new() -> Self {
-        Self {
-            verifier: Verifier::new(),
-        }
+        let verifier = match HistoryStore::from_env() {
+            Ok(Some(store)) => Verifier::new().with_history(store.for_mcp_session()),
+            Ok(None) => Verifier::new(),
+            Err(e) => {
+                eprintln!(
+                    "Warning: cannot open history from DYADT_HISTORY, not recording: {}",
+                    e
+                );
+                Verifier::new()
+            }
+        };
+        Self { verifier }
+    }
+
+    /// Create an MCP server around a configured verifier (e.g. with custom checkers)
+    pub fn with_verifier(verifier: Verifier) -> Self {
+        Self { verifier }
     }
 
     /// Run the MCP server on stdio
@@ -186,46 +207,18 @@ impl McpServer {
 
     /// Handle tools/list request
     fn handle_tools_list(&self, id: Value) -> JsonRpcResponse {
+        let definitions = self.claim_definitions();
         let tools = vec![
             ToolDef {
                 name: "verify_claim".to_string(),
                 description:
                     "Verify a claim against evidence. Returns a detailed verification report."
                         .to_string(),
-                input_schema: json!({
-                    "type": "object",
-                    "properties": {
-                        "description": {
-                            "type": "string",
-                            "description": "Human-readable description of what was claimed"
-                        },
-                        "evidence": {
-                            "type": "array",
-                            "description": "Array of evidence specifications to check",
-                            "items": {
-                                "type": "object",
-                                "properties": {
-                                    "type": {
-                                        "type": "string",
-                                        "enum": ["FileExists", "FileWithHash", "FileContains", "FileMatchesRegex",
-                                                 "FileJsonPath", "DirectoryExists", "CommandSucceeds", "GitClean",
-                                                 "GitCommitExists", "GitBranchExists", "FileModifiedAfter", "EnvVar"]
-                                    },
-                                    "spec": {
-                                        "type": "object",
-                                        "description": "Evidence-specific parameters"
-                                    }
-                                },
-                                "required": ["type", "spec"]
-                            }
-                        },
-                        "source": {
-                            "type": "string",
-                            "description": "Optional source identifier (e.g., 'claude-code')"
-                        }
-                    },
-                    "required": ["description", "evidence"]
-                }),
+                input_schema: {
+                    let mut schema = definitions["Claim"].clone();
+                    schema["$defs"] = Value::Object(definitions.clone());
+                    schema
+                },
             },
             ToolDef {
                 name: "quick_verify".to_string(),
@@ -266,18 +259,11 @@ impl McpServer {
                         "claims": {
                             "type": "array",
                             "description": "Array of claims to verify",
-                            "items": {
-                                "type": "object",
-                                "properties": {
-                                    "description": {"type": "string"},
-                                    "evidence": {"type": "array"},
-                                    "source": {"type": "string"}
-                                },
-                                "required": ["description", "evidence"]
-                            }
+                            "items": { "$ref": "#/$defs/Claim" }
                         }
                     },
-                    "required": ["claims"]
+                    "required": ["claims"],
+                    "$defs": definitions
                 }),
             },
         ];
@@ -290,6 +276,45 @@ impl McpServer {
         }
     }
 
+    /// The generated claim schema definitions, with `Custom` evidence
+    /// narrowed to the checkers registered on this server's verifier
+    fn claim_definitions(&self) -> Map<String, Value> {
+        let mut definitions = schema::definitions().clone();
+        let checkers = self.verifier.custom_checkers();
+        if checkers.is_empty() {
+            return definitions;
+        }
+
+        let custom = checkers.into_iter().map(|checker| {
+            json!({
+                "type": "object",
+                "description": checker.description(),
+                "properties": {
+                    "type": { "const": "Custom" },
+                    "spec": {
+                        "type": "object",
+                        "properties": {
+                            "name": { "const": checker.name() },
+                            "params": checker.params_schema()
+                        },
+                        "required": ["name", "params"],
+                        "additionalProperties": false
+                    }
+                },
+                "required": ["type", "spec"],
+                "additionalProperties": false
+            })
+        });
+        if let Some(Value::Array(variants)) = definitions
+            .get_mut("EvidenceSpec")
+            .and_then(|spec| spec.get_mut("oneOf"))
+        {
+            variants.retain(|variant| variant["properties"]["type"]["const"] != "Custom");
+            variants.extend(custom);
+        }
+        definitions
+    }
+
     /// Handle tools/call request
     fn handle_tools_call(&self, id: Value, params: Option<&Value>) -> JsonRpcResponse {
         let params = match params {
@@ -349,40 +374,14 @@ impl McpServer {
 
     /// Verify a single claim
     fn tool_verify_claim(&self, args: &Value) -> Result<String, String> {
-        let description = args
-            .get("description")
-            .and_then(|v| v.as_str())
-            .ok_or("Missing 'description' field")?;
-
-        let evidence_array = args
-            .get("evidence")
-            .and_then(|v| v.as_array())
-            .ok_or("Missing 'evidence' array")?;
-
-        let source = args.get("source").and_then(|v| v.as_str());
-
-        // Parse evidence specifications
-        let mut evidence_specs = Vec::new();
-        for ev in evidence_array {
-            let spec: EvidenceSpec = serde_json::from_value(ev.clone())
-                .map_err(|e| format!("Invalid evidence: {}", e))?;
-            evidence_specs.push(spec);
-        }
-
-        // Build claim
-        let mut claim = Claim::new(description);
-        for spec in evidence_specs {
-            claim = claim.with_evidence(spec);
-        }
-        if let Some(s) = source {
-            claim = claim.with_source(s);
-        }
+        let claim: Claim =
+            serde_json::from_value(args.clone()).map_err(|e| format!("Invalid claim: {}", e))?;
 
         // Verify
         let report = self.verifier.verify(&claim);
 
         // Format result
-        Ok(format_report(&report))
+        Ok(report::format_report(&report))
     }
 
     /// Quick file/directory existence check
@@ -399,7 +398,7 @@ impl McpServer {
             .with_source("mcp-server");
 
         let report = self.verifier.verify(&claim);
-        Ok(format_report(&report))
+        Ok(report::format_report(&report))
     }
 
     /// Compute file hash
@@ -436,118 +435,42 @@ impl McpServer {
             .and_then(|v| v.as_array())
             .ok_or("Missing 'claims' array")?;
 
-        let mut reports = Vec::new();
-        let mut worst_verdict = Verdict::Confirmed;
-
+        let mut claims = Vec::new();
         for claim_val in claims_array {
             let claim: Claim = serde_json::from_value(claim_val.clone())
                 .map_err(|e| format!("Invalid claim: {}", e))?;
-
-            let report = self.verifier.verify(&claim);
-
-            worst_verdict = match (worst_verdict, report.overall_verdict) {
-                (_, Verdict::Refuted) | (Verdict::Refuted, _) => Verdict::Refuted,
-                (_, Verdict::Inconclusive) | (Verdict::Inconclusive, _) => Verdict::Inconclusive,
-                (_, Verdict::Unverifiable) | (Verdict::Unverifiable, _) => Verdict::Unverifiable,
-                (Verdict::Confirmed, Verdict::Confirmed) => Verdict::Confirmed,
-            };
-
-            reports.push(report);
+            claims.push(claim);
         }
 
-        let summary = json!({
-            "total": reports.len(),
-            "confirmed": reports.iter().filter(|r| r.overall_verdict == Verdict::Confirmed).count(),
-            "refuted": reports.iter().filter(|r| r.overall_verdict == Verdict::Refuted).count(),
-            "inconclusive": reports.iter().filter(|r| r.overall_verdict == Verdict::Inconclusive).count(),
-            "unverifiable": reports.iter().filter(|r| r.overall_verdict == Verdict::Unverifiable).count(),
-            "overall_verdict": format!("{:?}", worst_verdict),
-            "reports": reports.iter().map(|r| {
+        let reports = self
+            .verifier
+            .verify_batch(&claims)
+            .map_err(|e| e.to_string())?;
+
+        let mut summary = report::report_summary(&reports);
+        summary["overall_verdict"] = json!(format!("{:?}", report::worst_verdict(&reports)));
+        summary["reports"] = reports
+            .iter()
+            .map(|r| {
                 json!({
                     "claim": r.claim.description,
                     "verdict": format!("{:?}", r.overall_verdict),
-                    "evidence_count": r.evidence_results.len()
+                    "evidence_count": r.evidence_results.len(),
+                    "confidence": r.confidence,
+                    "skipped": r.skipped
                 })
-            }).collect::<Vec<_>>()
-        });
+            })
+            .collect();
 
         Ok(serde_json::to_string_pretty(&summary).unwrap())
     }
 }
 
-/// Format a verification report for human/AI consumption
-fn format_report(report: &VerificationReport) -> String {
-    let mut output = String::new();
-
-    let emoji = match report.overall_verdict {
-        Verdict::Confirmed => "✓",
-        Verdict::Refuted => "✗",
-        Verdict::Inconclusive => "?",
-        Verdict::Unverifiable => "⊘",
-    };
-
-    output.push_str(&format!(
-        "[{}] {} - {:?}\n",
-        emoji, report.claim.description, report.overall_verdict
-    ));
-
-    if let Some(ref source) = report.claim.source {
-        output.push_str(&format!("Source: {}\n", source));
-    }
-
-    for result in &report.evidence_results {
-        let icon = match result.verdict {
-            Verdict::Confirmed => "  ✓",
-            Verdict::Refuted => "  ✗",
-            Verdict::Inconclusive => "  ?",
-            Verdict::Unverifiable => "  ⊘",
-        };
-
-        let evidence_desc = format_evidence(&result.spec);
-        output.push_str(&format!("{} {}\n", icon, evidence_desc));
-
-        if let Some(ref details) = result.details {
-            output.push_str(&format!("      {}\n", details));
-        }
-    }
-
-    output
-}
-
-/// Format evidence spec for display
-fn format_evidence(spec: &EvidenceSpec) -> String {
-    match spec {
-        EvidenceSpec::FileExists { path } => format!("File exists: {}", path),
-        EvidenceSpec::FileWithHash { path, .. } => format!("File hash: {}", path),
-        EvidenceSpec::FileContains { path, substring } => {
-            format!("File contains '{}': {}", substring, path)
-        }
-        EvidenceSpec::FileMatchesRegex { path, pattern } => {
-            format!("File matches regex '{}': {}", pattern, path)
-        }
-        EvidenceSpec::FileJsonPath {
-            path, json_path, ..
-        } => {
-            format!("JSON path '{}': {}", json_path, path)
-        }
-        EvidenceSpec::DirectoryExists { path } => format!("Directory exists: {}", path),
-        EvidenceSpec::CommandSucceeds { command, .. } => format!("Command succeeds: {}", command),
-        EvidenceSpec::GitClean { repo_path } => {
-            format!("Git clean: {}", repo_path.as_deref().unwrap_or("."))
-        }
-        EvidenceSpec::GitCommitExists { commit, .. } => format!("Git commit exists: {}", commit),
-        EvidenceSpec::GitBranchExists { branch, .. } => format!("Git branch exists: {}", branch),
-        EvidenceSpec::FileModifiedAfter { path, after } => {
-            format!("File modified after {}: {}", after, path)
-        }
-        EvidenceSpec::EnvVar { name, expected } => format!("Env {}={}", name, expected),
-        EvidenceSpec::Custom { name, .. } => format!("Custom check: {}", name),
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::checker::BuiltinChecker;
+    use crate::Verdict;
 
     #[test]
     fn test_handle_initialize() {
@@ -577,6 +500,46 @@ mod tests {
         assert!(tool_names.contains(&"verify_batch"));
     }
 
+    #[test]
+    fn test_tools_list_schema_is_generated() {
+        let server = McpServer::new();
+        let result = server.handle_tools_list(json!(1)).result.unwrap();
+        let schema = &result["tools"][0]["inputSchema"];
+        assert_eq!(schema["required"], json!(["description", "evidence"]));
+
+        // Every evidence type, generic Custom included
+        let variants = schema["$defs"]["EvidenceSpec"]["oneOf"].as_array().unwrap();
+        assert_eq!(variants.len(), BuiltinChecker::all().len() + 1);
+        assert!(variants
+            .iter()
+            .any(|v| v["properties"]["type"]["const"] == "Custom"));
+        assert_eq!(
+            result["tools"][3]["inputSchema"]["properties"]["claims"]["items"]["$ref"],
+            "#/$defs/Claim"
+        );
+    }
+
+    #[test]
+    fn test_tools_list_advertises_custom_checkers() {
+        let mut verifier = Verifier::new();
+        verifier.register_checker("always_pass", |_| Ok(Verdict::Confirmed));
+        let server = McpServer::with_verifier(verifier);
+
+        let result = server.handle_tools_list(json!(1)).result.unwrap();
+        let variants = result["tools"][0]["inputSchema"]["$defs"]["EvidenceSpec"]["oneOf"]
+            .as_array()
+            .unwrap()
+            .clone();
+
+        assert_eq!(variants.len(), BuiltinChecker::all().len() + 1);
+        assert!(variants
+            .iter()
+            .any(|v| v["properties"]["type"]["const"] == "FileExists"));
+        assert!(variants
+            .iter()
+            .any(|v| v["properties"]["spec"]["properties"]["name"]["const"] == "always_pass"));
+    }
+
     #[test]
     fn test_quick_verify_nonexistent() {
         let server = McpServer::new();
@@ -608,4 +571,28 @@ mod tests {
         assert!(text.contains("Test claim"));
         assert!(text.contains("Refuted"));
     }
+
+    #[test]
+    fn test_verify_claim_accepts_full_claim() {
+        let server = McpServer::new();
+        let result = server.tool_verify_claim(&json!({
+            "id": "claim-42",
+            "description": "Test claim",
+            "timestamp": "2024-01-01T00:00:00Z",
+            "evidence": [
+                {
+                    "type": "FileExists",
+                    "spec": { "path": "/nonexistent/file.txt" }
+                }
+            ],
+            "depends_on": [],
+            "suppressions": []
+        }));
+
+        assert!(result.unwrap().contains("Refuted"));
+        assert!(server
+            .tool_verify_claim(&json!({ "evidence": [] }))
+            .unwrap_err()
+            .starts_with("Invalid claim"));
+    }
 }
@@ -0,0 +1,347 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Line-level edits: `FileLineRange` and `FileUnifiedDiffApplied`
+//!
+//! Both compare exact lines, ignoring only line-ending differences (`\r\n`
+//! and `\n` compare equal). When they do not match, the details carry a
+//! compact unified diff from the expected text to what the file contains.
+//!
+//! A unified diff is *applied* when the new side of every hunk (its context
+//! and `+` lines) appears in the file, which is exactly the condition for
+//! the diff to reverse-apply. Hunks are looked for at the line their header
+//! names first, then anywhere in the file, so edits above a hunk that shift
+//! it do not matter.
+//!
+//! ```rust
+//! use did_you_actually_do_that::patch::parse_unified;
+//!
+//! let diff = "--- a/src/main.rs\n+++ b/src/main.rs\n@@ -2 +2 @@\n-    let x = f().unwrap();\n+    let x = f()?;\n";
+//! let hunks = parse_unified(diff).unwrap();
+//! assert_eq!(hunks[0].new_start, 2);
+//! assert_eq!(hunks[0].after, ["    let x = f()?;"]);
+//! ```
+
+use crate::Verdict;
+use similar::TextDiff;
+
+/// Context lines shown around each change in a compact diff
+const DIFF_CONTEXT: usize = 2;
+
+/// One hunk of a unified diff
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hunk {
+    /// 1-based first line of the hunk in the new file
+    pub new_start: usize,
+    /// Context and removed lines, in order
+    pub before: Vec<String>,
+    /// Context and added lines, in order
+    pub after: Vec<String>,
+}
+
+/// Parse the hunks of a single-file unified diff. File headers (`---`,
+/// `+++`, `diff --git`, `index`) are skipped.
+pub fn parse_unified(diff: &str) -> Result<Vec<Hunk>, String> {
+    let mut hunks = Vec::new();
+    let mut lines = diff.lines().enumerate();
+
+    while let Some((number, line)) = lines.next() {
+        if !line.starts_with("@@") {
+            continue;
+        }
+        let (old_len, new_start, new_len) = parse_header(line)
+            .ok_or_else(|| format!("Invalid hunk header on line {}: {}", number + 1, line))?;
+        let mut hunk = Hunk {
+            new_start,
+            before: Vec::new(),
+            after: Vec::new(),
+        };
+
+        // The header's line counts say where the hunk ends, so removed lines
+        // that look like `---` headers are still read as content
+        while hunk.before.len() < old_len || hunk.after.len() < new_len {
+            let Some((_, line)) = lines.next() else {
+                return Err(format!("Hunk on line {} is truncated", number + 1));
+            };
+            let text = line.get(1..).unwrap_or_default().to_string();
+            match line.chars().next() {
+                Some(' ') => {
+                    hunk.before.push(text.clone());
+                    hunk.after.push(text);
+                }
+                Some('-') => hunk.before.push(text),
+                Some('+') => hunk.after.push(text),
+                Some('\\') => {}
+                // Some editors strip the space from empty context lines
+                None => {
+                    hunk.before.push(String::new());
+                    hunk.after.push(String::new());
+                }
+                Some(_) => {
+                    return Err(format!("Hunk on line {} is truncated", number + 1));
+                }
+            }
+        }
+        hunks.push(hunk);
+    }
+
+    if hunks.is_empty() {
+        return Err("No hunks found in diff".to_string());
+    }
+    Ok(hunks)
+}
+
+/// `@@ -a,b +c,d @@` -> `(b, c, d)`; omitted counts are 1
+fn parse_header(line: &str) -> Option<(usize, usize, usize)> {
+    let mut ranges = line.strip_prefix("@@ ")?.split_whitespace();
+    let old = ranges.next()?.strip_prefix('-')?;
+    let new = ranges.next()?.strip_prefix('+')?;
+    let range = |spec: &str| -> Option<(usize, usize)> {
+        match spec.split_once(',') {
+            Some((start, len)) => Some((start.parse().ok()?, len.parse().ok()?)),
+            None => Some((spec.parse().ok()?, 1)),
+        }
+    };
+    let (_, old_len) = range(old)?;
+    let (new_start, new_len) = range(new)?;
+    Some((old_len, new_start, new_len))
+}
+
+impl Hunk {
+    /// 0-based index where the new side of this hunk starts in `lines`,
+    /// preferring the header's position and then the nearest match.
+    ///
+    /// A hunk with no new side (a deletion without context) has nothing to
+    /// find; it counts as applied once its removed lines are gone.
+    pub fn locate(&self, lines: &[&str]) -> Option<usize> {
+        let expected = self.new_start.saturating_sub(1);
+        if self.after.is_empty() {
+            let gone = find_all(&self.before, lines).next().is_none();
+            return gone.then_some(expected.min(lines.len()));
+        }
+        find_all(&self.after, lines).min_by_key(|&i| i.abs_diff(expected))
+    }
+}
+
+/// Every index where `block` occurs in `lines`
+fn find_all<'a>(block: &'a [String], lines: &'a [&str]) -> impl Iterator<Item = usize> + 'a {
+    let last = (lines.len() + 1).saturating_sub(block.len().max(1));
+    (0..last).filter(move |&i| block.iter().zip(&lines[i..]).all(|(a, b)| a == b))
+}
+
+/// A compact unified diff from `expected` to `actual`
+pub fn compact_diff(expected: &str, actual: &str) -> String {
+    let terminate = |s: &str| {
+        if s.is_empty() || s.ends_with('\n') {
+            s.to_string()
+        } else {
+            format!("{}\n", s)
+        }
+    };
+    let (expected, actual) = (terminate(expected), terminate(actual));
+    TextDiff::from_lines(&expected, &actual)
+        .unified_diff()
+        .context_radius(DIFF_CONTEXT)
+        .header("expected", "actual")
+        .to_string()
+}
+
+/// The lines `expected` stands for; an empty `expected` is one empty line
+fn expected_lines(expected: &str) -> Vec<&str> {
+    if expected.is_empty() {
+        vec![""]
+    } else {
+        expected.lines().collect()
+    }
+}
+
+/// Last line of a `FileLineRange`: `end`, or as far as `expected` reaches
+pub(crate) fn line_range_end(start: usize, end: Option<usize>, expected: &str) -> usize {
+    end.unwrap_or_else(|| start.saturating_add(expected_lines(expected).len() - 1))
+}
+
+/// Verdict for `FileLineRange`: lines `start..=end` of `text` must equal
+/// `expected`. Without `end`, the range is as long as `expected`.
+pub(crate) fn check_line_range(
+    text: &str,
+    start: usize,
+    end: Option<usize>,
+    expected: &str,
+) -> (Verdict, Option<String>) {
+    let wanted = expected_lines(expected);
+    let end = line_range_end(start, end, expected);
+    if start == 0 || end < start {
+        return (
+            Verdict::Unverifiable,
+            Some(format!("Invalid line range {}-{}", start, end)),
+        );
+    }
+
+    let lines: Vec<&str> = text.lines().collect();
+    if lines.len() < end {
+        return (
+            Verdict::Refuted,
+            Some(format!(
+                "File has {} lines, range {}-{} is out of bounds",
+                lines.len(),
+                start,
+                end
+            )),
+        );
+    }
+
+    let actual = &lines[start - 1..end];
+    if actual == wanted.as_slice() {
+        (
+            Verdict::Confirmed,
+            Some(format!("Lines {}-{} match", start, end)),
+        )
+    } else {
+        (
+            Verdict::Refuted,
+            Some(format!(
+                "Lines {}-{} differ:\n{}",
+                start,
+                end,
+                compact_diff(&wanted.join("\n"), &actual.join("\n"))
+            )),
+        )
+    }
+}
+
+/// Verdict for `FileUnifiedDiffApplied`: every hunk's new side must be
+/// present in `text`
+pub(crate) fn check_diff_applied(text: &str, diff: &str) -> (Verdict, Option<String>) {
+    let hunks = match parse_unified(diff) {
+        Ok(hunks) => hunks,
+        Err(e) => return (Verdict::Unverifiable, Some(e)),
+    };
+    let lines: Vec<&str> = text.lines().collect();
+
+    let mut shifted = Vec::new();
+    for (n, hunk) in hunks.iter().enumerate() {
+        match hunk.locate(&lines) {
+            Some(at) if at + 1 != hunk.new_start && !hunk.after.is_empty() => {
+                shifted.push(format!("hunk {} at line {}", n + 1, at + 1));
+            }
+            Some(_) => {}
+            None => {
+                let from = hunk.new_start.saturating_sub(1).min(lines.len());
+                let to = (from + hunk.after.len()).min(lines.len());
+                return (
+                    Verdict::Refuted,
+                    Some(format!(
+                        "Hunk {} of {} is not applied (expected at line {}):\n{}",
+                        n + 1,
+                        hunks.len(),
+                        hunk.new_start,
+                        compact_diff(&hunk.after.join("\n"), &lines[from..to].join("\n"))
+                    )),
+                );
+            }
+        }
+    }
+
+    let mut details = format!("All {} hunk(s) applied", hunks.len());
+    if !shifted.is_empty() {
+        details.push_str(&format!(" ({})", shifted.join(", ")));
+    }
+    (Verdict::Confirmed, Some(details))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FILE: &str = "fn main() {\n    let x = f()?;\n    println!(\"{}\", x);\n}\n";
+
+    #[test]
+    fn test_parse_counts_define_hunk_extent() {
+        let diff = "@@ -1,3 +1,2 @@\n a\n--- not a header\n b\n@@ -9 +8,0 @@\n-gone\n";
+        let hunks = parse_unified(diff).unwrap();
+        assert_eq!(hunks.len(), 2);
+        assert_eq!(hunks[0].before, ["a", "-- not a header", "b"]);
+        assert_eq!(hunks[0].after, ["a", "b"]);
+        assert!(hunks[1].after.is_empty());
+        assert_eq!(hunks[1].locate(&["kept"]), Some(1));
+        assert_eq!(hunks[1].locate(&["gone"]), None);
+
+        assert!(parse_unified("no hunks here").is_err());
+        assert!(parse_unified("@@ -1,2 +1,2 @@\n a\n").is_err());
+        assert!(parse_unified("@@ bogus @@\n").is_err());
+    }
+
+    #[test]
+    fn test_line_range() {
+        assert_eq!(
+            check_line_range(FILE, 2, None, "    let x = f()?;").0,
+            Verdict::Confirmed
+        );
+        assert_eq!(
+            check_line_range(
+                FILE,
+                2,
+                Some(3),
+                "    let x = f()?;\n    println!(\"{}\", x);\n"
+            )
+            .0,
+            Verdict::Confirmed
+        );
+
+        let (verdict, details) = check_line_range(FILE, 2, None, "    let x = f().unwrap();");
+        assert_eq!(verdict, Verdict::Refuted);
+        let details = details.unwrap();
+        assert!(
+            details.contains("-    let x = f().unwrap();"),
+            "{}",
+            details
+        );
+        assert!(details.contains("+    let x = f()?;"), "{}", details);
+
+        assert_eq!(check_line_range(FILE, 9, None, "x").0, Verdict::Refuted);
+        assert_eq!(
+            check_line_range(FILE, 0, None, "x").0,
+            Verdict::Unverifiable
+        );
+    }
+
+    #[test]
+    fn test_line_range_edge_cases() {
+        // An empty `expected` is an empty line
+        let text = "a\n\nb\n";
+        assert_eq!(check_line_range(text, 2, None, "").0, Verdict::Confirmed);
+        assert_eq!(check_line_range(text, 1, None, "").0, Verdict::Refuted);
+
+        // A huge start is out of bounds, not an overflow
+        assert_eq!(line_range_end(usize::MAX, None, "a\nb"), usize::MAX);
+        assert_eq!(
+            check_line_range(text, usize::MAX, None, "a\nb").0,
+            Verdict::Refuted
+        );
+    }
+
+    #[test]
+    fn test_diff_applied() {
+        let diff = "--- a/main.rs\n+++ b/main.rs\n@@ -1,3 +1,3 @@\n fn main() {\n-    let x = f().unwrap();\n+    let x = f()?;\n     println!(\"{}\", x);\n";
+        assert_eq!(check_diff_applied(FILE, diff).0, Verdict::Confirmed);
+
+        // Shifted by lines added above the hunk
+        let shifted = format!("use std::io;\n\n{}", FILE);
+        let (verdict, details) = check_diff_applied(&shifted, diff);
+        assert_eq!(verdict, Verdict::Confirmed);
+        assert!(details.unwrap().contains("hunk 1 at line 3"));
+
+        // Not applied: the file still has the old line
+        let old = FILE.replace("f()?", "f().unwrap()");
+        let (verdict, details) = check_diff_applied(&old, diff);
+        assert_eq!(verdict, Verdict::Refuted);
+        assert!(details.unwrap().contains("+    let x = f().unwrap();"));
+    }
+
+    #[test]
+    fn test_crlf_lines_compare_equal() {
+        let crlf = FILE.replace('\n', "\r\n");
+        assert_eq!(
+            check_line_range(&crlf, 2, None, "    let x = f()?;").0,
+            Verdict::Confirmed
+        );
+    }
+}
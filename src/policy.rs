@@ -0,0 +1,311 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Execution policy for evidence that runs external programs
+//!
+//! Claims are frequently written by the agent being audited, so a claim file
+//! can name any program in `CommandSucceeds` evidence (and `TestPasses` runs
+//! cargo, pytest or go). An [`ExecutionPolicy`]
+//! decides which programs a [`Verifier`](crate::Verifier) may start, and can
+//! optionally confine them with resource limits and namespaces on Linux.
+//! Evidence the policy blocks is reported as Unverifiable rather than run.
+//!
+//! ```rust
+//! use did_you_actually_do_that::policy::ExecutionPolicy;
+//! use did_you_actually_do_that::Verifier;
+//!
+//! // Only `cargo` and `git` may run, and never `/usr/bin/rm`
+//! let policy = ExecutionPolicy::allowlist(["cargo", "git"]).deny("rm");
+//! assert!(policy.check("cargo").is_ok());
+//! assert!(policy.check("/usr/bin/cargo").is_err());
+//!
+//! let verifier = Verifier::new().with_policy(policy);
+//! ```
+//!
+//! ## Matching programs
+//!
+//! Allowlist entries are matched exactly: a bare name such as `cargo` only
+//! permits the bare command `cargo` (resolved through `PATH`), while an entry
+//! containing a path only permits that exact path. This stops a claim from
+//! slipping `/tmp/x/cargo` past an allowlist of `cargo`. Denylist entries are
+//! matched against the program's file name as well, so denying `rm` also
+//! blocks `/bin/rm`.
+//!
+//! Allowing a shell or interpreter (`sh`, `python`, ...) effectively allows
+//! everything, since the arguments can run arbitrary programs.
+
+use std::path::Path;
+use std::process::Command;
+
+/// Which programs may be started
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ProgramRule {
+    AllowAll,
+    DenyAll,
+    Allowlist(Vec<String>),
+}
+
+/// Resource limits and namespaces applied to every spawned program (Linux only)
+///
+/// Limits are applied with `setrlimit` in the child just before `exec`.
+/// Setting `max_file_size_bytes` to `Some(0)` caps written file size at 0
+/// bytes, so the program cannot put data in any file. It is not read-only
+/// access: deleting, renaming, truncating, `mkdir` and `chmod` still
+/// succeed. It also breaks `TestPasses`, since pytest can no longer write
+/// its JUnit report (and cargo its build output).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Isolation {
+    /// CPU time limit in seconds (`RLIMIT_CPU`)
+    pub max_cpu_secs: Option<u64>,
+    /// Address space limit in bytes (`RLIMIT_AS`)
+    pub max_memory_bytes: Option<u64>,
+    /// Largest size the program may grow a file to, in bytes (`RLIMIT_FSIZE`)
+    pub max_file_size_bytes: Option<u64>,
+    /// Maximum number of processes for the user (`RLIMIT_NPROC`)
+    pub max_processes: Option<u64>,
+    /// Run in fresh user and network namespaces, leaving only loopback.
+    /// If the namespaces cannot be created the program is not run.
+    pub no_network: bool,
+}
+
+impl Isolation {
+    /// Written file size capped at 0 bytes, no network, and a generous CPU
+    /// limit. Files can still be deleted, renamed or truncated; see
+    /// [`Isolation`].
+    pub fn no_file_growth() -> Self {
+        Self {
+            max_cpu_secs: Some(300),
+            max_file_size_bytes: Some(0),
+            no_network: true,
+            ..Self::default()
+        }
+    }
+}
+
+/// Policy controlling which programs evidence checks may execute
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExecutionPolicy {
+    rule: ProgramRule,
+    denied: Vec<String>,
+    isolation: Option<Isolation>,
+}
+
+impl Default for ExecutionPolicy {
+    fn default() -> Self {
+        Self::allow_all()
+    }
+}
+
+impl ExecutionPolicy {
+    /// Run any program (the default, matching previous behavior)
+    pub fn allow_all() -> Self {
+        Self {
+            rule: ProgramRule::AllowAll,
+            denied: Vec::new(),
+            isolation: None,
+        }
+    }
+
+    /// Never run any program
+    pub fn deny_all() -> Self {
+        Self {
+            rule: ProgramRule::DenyAll,
+            ..Self::allow_all()
+        }
+    }
+
+    /// Only run the listed programs
+    pub fn allowlist<I, S>(programs: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            rule: ProgramRule::Allowlist(programs.into_iter().map(Into::into).collect()),
+            ..Self::allow_all()
+        }
+    }
+
+    /// Never run `program`, even if otherwise allowed
+    pub fn deny(mut self, program: impl Into<String>) -> Self {
+        self.denied.push(program.into());
+        self
+    }
+
+    /// Confine every program that is allowed to run
+    pub fn with_isolation(mut self, isolation: Isolation) -> Self {
+        self.isolation = Some(isolation);
+        self
+    }
+
+    /// Isolation applied to spawned programs, if any
+    pub fn isolation(&self) -> Option<&Isolation> {
+        self.isolation.as_ref()
+    }
+
+    /// Whether no program may run at all
+    pub fn denies_all(&self) -> bool {
+        self.rule == ProgramRule::DenyAll
+    }
+
+    /// Check whether `program` may run, returning the reason if not
+    pub fn check(&self, program: &str) -> Result<(), String> {
+        let file_name = Path::new(program)
+            .file_name()
+            .and_then(|n| n.to_str())
+            .unwrap_or(program);
+
+        if self
+            .denied
+            .iter()
+            .any(|d| d == program || d.as_str() == file_name)
+        {
+            return Err(format!(
+                "Execution blocked by policy: '{}' is denied",
+                program
+            ));
+        }
+
+        match &self.rule {
+            ProgramRule::AllowAll => Ok(()),
+            ProgramRule::DenyAll => Err(format!(
+                "Execution blocked by policy: running '{}' is disabled",
+                program
+            )),
+            ProgramRule::Allowlist(allowed) => {
+                if allowed.iter().any(|a| a == program) {
+                    Ok(())
+                } else {
+                    Err(format!(
+                        "Execution blocked by policy: '{}' is not in the allowlist",
+                        program
+                    ))
+                }
+            }
+        }
+    }
+
+    /// Build a [`Command`] for `program` if the policy allows it, with
+    /// isolation applied
+    pub(crate) fn command(&self, program: &str) -> Result<Command, String> {
+        self.check(program)?;
+        let mut command = Command::new(program);
+        if let Some(isolation) = self.isolation {
+            apply_isolation(&mut command, isolation)?;
+        }
+        Ok(command)
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn apply_isolation(command: &mut Command, isolation: Isolation) -> Result<(), String> {
+    use std::os::unix::process::CommandExt;
+
+    // SAFETY: the hook runs in the forked child before exec and only makes
+    // async-signal-safe system calls on copied data; it does not allocate.
+    unsafe {
+        command.pre_exec(move || isolate_child(&isolation));
+    }
+    Ok(())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn apply_isolation(_command: &mut Command, _isolation: Isolation) -> Result<(), String> {
+    Err("Execution blocked by policy: process isolation is only supported on Linux".to_string())
+}
+
+#[cfg(target_os = "linux")]
+fn isolate_child(isolation: &Isolation) -> std::io::Result<()> {
+    let limits = [
+        (libc::RLIMIT_CPU, isolation.max_cpu_secs),
+        (libc::RLIMIT_AS, isolation.max_memory_bytes),
+        (libc::RLIMIT_FSIZE, isolation.max_file_size_bytes),
+        (libc::RLIMIT_NPROC, isolation.max_processes),
+    ];
+
+    for (resource, limit) in limits {
+        if let Some(limit) = limit {
+            let rlim = libc::rlimit {
+                rlim_cur: limit as libc::rlim_t,
+                rlim_max: limit as libc::rlim_t,
+            };
+            // SAFETY: plain system call on a valid, initialized struct
+            if unsafe { libc::setrlimit(resource, &rlim) } != 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+        }
+    }
+
+    if isolation.max_file_size_bytes.is_some() {
+        // Let oversized writes fail with EFBIG instead of killing the
+        // program, so tools like git can degrade gracefully.
+        // SAFETY: setting a signal disposition to SIG_IGN is always valid
+        unsafe {
+            libc::signal(libc::SIGXFSZ, libc::SIG_IGN);
+        }
+    }
+
+    if isolation.no_network {
+        // SAFETY: plain system call; the forked child is single-threaded
+        if unsafe { libc::unshare(libc::CLONE_NEWUSER | libc::CLONE_NEWNET) } != 0 {
+            return Err(std::io::Error::last_os_error());
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allow_all_by_default() {
+        let policy = ExecutionPolicy::default();
+        assert!(policy.check("anything").is_ok());
+        assert!(!policy.denies_all());
+    }
+
+    #[test]
+    fn test_deny_all_blocks_everything() {
+        let policy = ExecutionPolicy::deny_all();
+        assert!(policy.check("true").is_err());
+        assert!(policy.denies_all());
+    }
+
+    #[test]
+    fn test_allowlist_matches_exactly() {
+        let policy = ExecutionPolicy::allowlist(["cargo", "/usr/bin/git"]);
+        assert!(policy.check("cargo").is_ok());
+        assert!(policy.check("/usr/bin/git").is_ok());
+        assert!(policy.check("/tmp/evil/cargo").is_err());
+        assert!(policy.check("git").is_err());
+    }
+
+    #[test]
+    fn test_denylist_matches_file_name() {
+        let policy = ExecutionPolicy::allow_all().deny("rm");
+        assert!(policy.check("rm").is_err());
+        assert!(policy.check("/bin/rm").is_err());
+        assert!(policy.check("rmdir").is_ok());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_file_size_limit_blocks_writes() {
+        let dir = tempfile::tempdir().unwrap();
+        let target = dir.path().join("out.txt");
+
+        let policy = ExecutionPolicy::allow_all().with_isolation(Isolation {
+            max_file_size_bytes: Some(0),
+            ..Isolation::default()
+        });
+        let status = policy
+            .command("sh")
+            .unwrap()
+            .args(["-c", &format!("echo data > {}", target.display())])
+            .status()
+            .unwrap();
+
+        assert!(!status.success());
+        assert_eq!(std::fs::metadata(&target).map(|m| m.len()).unwrap_or(0), 0);
+    }
+}
@@ -0,0 +1,381 @@
+// SPDX-License-Identifier: MPL-2.0
+//! JSON queries shared by `FileJsonPath` and `HttpJsonPath`
+//!
+//! Paths are [RFC 9535](https://www.rfc-editor.org/rfc/rfc9535) JSONPath
+//! expressions, so wildcards, slices, negative indices, quoted keys and
+//! filters all work:
+//!
+//! ```rust
+//! use did_you_actually_do_that::query::{JsonOp, JsonQuery};
+//! use serde_json::json;
+//!
+//! let doc = json!({"deps": [{"name": "serde", "version": "1.0.200"}], "a.b": 1});
+//!
+//! let query = JsonQuery::parse("$.deps[?@.name == 'serde'].version").unwrap();
+//! assert!(query.check(&doc, JsonOp::Matches, &json!("^1\\.")).is_ok());
+//!
+//! // Keys containing dots need bracket notation
+//! let query = JsonQuery::parse("$['a.b']").unwrap();
+//! assert!(query.check(&doc, JsonOp::Equals, &json!(1)).is_ok());
+//! ```
+//!
+//! Paths that do not start with `$` use the older dotted form (`.a.b[0]`),
+//! where every segment is a plain key and `[n]` an array index.
+//!
+//! When a path selects several values, every one of them must pass the
+//! comparison. Use a filter with [`JsonOp::Exists`] to ask whether *any*
+//! value qualifies.
+
+use crate::Verdict;
+use regex::Regex;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use serde_json_path::JsonPath;
+use std::fmt;
+
+/// How selected values are compared against the expected value
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+#[schemars(transform = add_op_aliases)]
+pub enum JsonOp {
+    /// Equal to `expected`
+    #[default]
+    Equals,
+    /// The path selects at least one value; `expected` is ignored
+    Exists,
+    /// A string containing the `expected` string, an array containing an
+    /// element equal to `expected`, or an object with the `expected` key
+    Contains,
+    /// A number greater than the `expected` number
+    #[serde(alias = ">")]
+    Gt,
+    /// A number less than the `expected` number
+    #[serde(alias = "<")]
+    Lt,
+    /// A string matching the `expected` regex
+    #[serde(alias = "regex")]
+    Matches,
+    /// A value of the `expected` type: `null`, `boolean`, `number`,
+    /// `integer`, `string`, `array` or `object`
+    #[serde(alias = "type-is")]
+    TypeIs,
+}
+
+/// Other spellings [`JsonOp`] accepts, as `(alias, name)`; these must match
+/// its `#[serde(alias)]`es
+pub(crate) const JSON_OP_ALIASES: &[(&str, &str)] = &[
+    (">", "gt"),
+    ("<", "lt"),
+    ("regex", "matches"),
+    ("type-is", "type_is"),
+];
+
+/// Let the generated schema accept the aliases, which schemars leaves out
+fn add_op_aliases(schema: &mut schemars::Schema) {
+    if let Some(Value::Array(variants)) = schema.get_mut("oneOf") {
+        for (alias, name) in JSON_OP_ALIASES {
+            variants.push(serde_json::json!({
+                "type": "string",
+                "const": alias,
+                "description": format!("Same as `{}`", name),
+            }));
+        }
+    }
+}
+
+/// Why [`JsonQuery::check`] did not pass
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CheckFailure {
+    /// The document does not satisfy the comparison
+    Mismatch(String),
+    /// The comparison itself is malformed (bad regex, unknown type name)
+    Invalid(String),
+}
+
+impl fmt::Display for CheckFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CheckFailure::Mismatch(reason) | CheckFailure::Invalid(reason) => f.write_str(reason),
+        }
+    }
+}
+
+/// A parsed JSON query
+#[derive(Debug, Clone)]
+pub struct JsonQuery {
+    expression: String,
+    path: JsonPath,
+}
+
+impl JsonQuery {
+    /// Parse an RFC 9535 expression, or a legacy dotted path
+    pub fn parse(expression: &str) -> Result<Self, String> {
+        let trimmed = expression.trim();
+        let normalized = if trimmed.starts_with('$') {
+            trimmed.to_string()
+        } else {
+            legacy_to_rfc9535(trimmed)?
+        };
+        let path = JsonPath::parse(&normalized)
+            .map_err(|e| format!("Invalid JSON path '{}': {}", expression, e))?;
+        Ok(Self {
+            expression: expression.to_string(),
+            path,
+        })
+    }
+
+    /// Values selected from `document`, in document order
+    pub fn select<'a>(&self, document: &'a Value) -> Vec<&'a Value> {
+        self.path.query(document).all()
+    }
+
+    /// Check the selected values, returning why the check failed if it did
+    pub fn check(
+        &self,
+        document: &Value,
+        op: JsonOp,
+        expected: &Value,
+    ) -> Result<(), CheckFailure> {
+        let invalid = |reason: &str| CheckFailure::Invalid(reason.to_string());
+        let selected = self.select(document);
+        if selected.is_empty() {
+            return Err(CheckFailure::Mismatch(format!(
+                "JSON path not found: {}",
+                self.expression
+            )));
+        }
+        if op == JsonOp::Exists {
+            return Ok(());
+        }
+
+        let pattern =
+            match op {
+                JsonOp::Matches => {
+                    let pattern = expected
+                        .as_str()
+                        .ok_or_else(|| invalid("Regex comparison needs a string pattern"))?;
+                    Some(Regex::new(pattern).map_err(|e| {
+                        CheckFailure::Invalid(format!("Invalid regex pattern: {}", e))
+                    })?)
+                }
+                _ => None,
+            };
+
+        for actual in selected {
+            let passed = match op {
+                JsonOp::Equals => actual == expected,
+                JsonOp::Exists => true,
+                JsonOp::Contains => match (actual, expected) {
+                    (Value::String(s), Value::String(needle)) => s.contains(needle.as_str()),
+                    (Value::Array(items), _) => items.contains(expected),
+                    (Value::Object(map), Value::String(key)) => map.contains_key(key),
+                    _ => false,
+                },
+                JsonOp::Gt | JsonOp::Lt => {
+                    let (Some(a), Some(b)) = (actual.as_f64(), expected.as_f64()) else {
+                        return Err(CheckFailure::Mismatch(format!(
+                            "Cannot compare {} with {}: both must be numbers",
+                            actual, expected
+                        )));
+                    };
+                    if op == JsonOp::Gt {
+                        a > b
+                    } else {
+                        a < b
+                    }
+                }
+                JsonOp::Matches => actual
+                    .as_str()
+                    .is_some_and(|s| pattern.as_ref().is_some_and(|re| re.is_match(s))),
+                JsonOp::TypeIs => {
+                    let wanted = expected
+                        .as_str()
+                        .ok_or_else(|| invalid("Type comparison needs a type name"))?;
+                    type_matches(actual, wanted).map_err(CheckFailure::Invalid)?
+                }
+            };
+
+            if !passed {
+                return Err(CheckFailure::Mismatch(match op {
+                    JsonOp::Equals => {
+                        format!("JSON path mismatch: expected {}, got {}", expected, actual)
+                    }
+                    _ => format!(
+                        "JSON path {}: {} does not satisfy {} {}",
+                        self.expression,
+                        actual,
+                        op.symbol(),
+                        expected
+                    ),
+                }));
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Verdict for a JSON query against a parsed document
+pub(crate) fn check_document(
+    document: &Value,
+    expression: &str,
+    op: JsonOp,
+    expected: &Value,
+) -> (Verdict, Option<String>) {
+    let query = match JsonQuery::parse(expression) {
+        Ok(query) => query,
+        Err(e) => return (Verdict::Unverifiable, Some(e)),
+    };
+    match query.check(document, op, expected) {
+        Ok(()) => (Verdict::Confirmed, Some("JSON path matches".to_string())),
+        Err(CheckFailure::Mismatch(reason)) => (Verdict::Refuted, Some(reason)),
+        Err(CheckFailure::Invalid(reason)) => (Verdict::Unverifiable, Some(reason)),
+    }
+}
+
+impl JsonOp {
+    fn symbol(self) -> &'static str {
+        match self {
+            JsonOp::Equals => "==",
+            JsonOp::Exists => "exists",
+            JsonOp::Contains => "contains",
+            JsonOp::Gt => ">",
+            JsonOp::Lt => "<",
+            JsonOp::Matches => "matches",
+            JsonOp::TypeIs => "type is",
+        }
+    }
+}
+
+fn type_matches(value: &Value, wanted: &str) -> Result<bool, String> {
+    Ok(match wanted {
+        "null" => value.is_null(),
+        "boolean" | "bool" => value.is_boolean(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "string" => value.is_string(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        other => return Err(format!("Unknown JSON type '{}'", other)),
+    })
+}
+
+/// Rewrite `.field.nested[0]` as `$['field']['nested'][0]`
+fn legacy_to_rfc9535(path: &str) -> Result<String, String> {
+    let mut out = String::from("$");
+
+    for segment in path.split('.').filter(|s| !s.is_empty()) {
+        let (name, mut rest) = match segment.find('[') {
+            Some(pos) => segment.split_at(pos),
+            None => (segment, ""),
+        };
+        if !name.is_empty() {
+            let escaped = name.replace('\\', "\\\\").replace('\'', "\\'");
+            out.push_str(&format!("['{}']", escaped));
+        }
+        while let Some(inner) = rest.strip_prefix('[') {
+            let end = inner
+                .find(']')
+                .ok_or_else(|| format!("Invalid JSON path '{}': unclosed '['", path))?;
+            let index: i64 = inner[..end]
+                .trim()
+                .parse()
+                .map_err(|_| format!("Invalid JSON path '{}': bad index", path))?;
+            out.push_str(&format!("[{}]", index));
+            rest = &inner[end + 1..];
+        }
+        if !rest.is_empty() {
+            return Err(format!("Invalid JSON path '{}'", path));
+        }
+    }
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn select(doc: &Value, path: &str) -> Vec<Value> {
+        JsonQuery::parse(path)
+            .unwrap()
+            .select(doc)
+            .into_iter()
+            .cloned()
+            .collect()
+    }
+
+    #[test]
+    fn test_legacy_paths_still_work() {
+        let doc = json!({"name": "test", "outer": {"items": ["a", "b", "c"]}, "foo-bar": 1});
+        assert_eq!(select(&doc, ".name"), vec![json!("test")]);
+        assert_eq!(select(&doc, ".outer.items[2]"), vec![json!("c")]);
+        assert_eq!(select(&doc, "outer.items[0]"), vec![json!("a")]);
+        assert_eq!(select(&doc, ".foo-bar"), vec![json!(1)]);
+        assert!(select(&doc, ".missing").is_empty());
+    }
+
+    #[test]
+    fn test_rfc9535_features() {
+        let doc = json!({
+            "a.b": true,
+            "items": [{"n": 1}, {"n": 5}, {"n": 9}]
+        });
+        assert_eq!(select(&doc, "$['a.b']"), vec![json!(true)]);
+        assert_eq!(select(&doc, "$.items[-1].n"), vec![json!(9)]);
+        assert_eq!(select(&doc, "$.items[*].n").len(), 3);
+        assert_eq!(
+            select(&doc, "$.items[?@.n > 3].n"),
+            vec![json!(5), json!(9)]
+        );
+    }
+
+    #[test]
+    fn test_comparisons() {
+        let doc = json!({"v": "1.2.3", "tags": ["x", "y"], "n": 4, "items": [2, 3]});
+        let check = |path: &str, op, expected: Value| {
+            JsonQuery::parse(path).unwrap().check(&doc, op, &expected)
+        };
+
+        assert!(check("$.v", JsonOp::Contains, json!(".2.")).is_ok());
+        assert!(check("$.tags", JsonOp::Contains, json!("y")).is_ok());
+        assert!(check("$", JsonOp::Contains, json!("tags")).is_ok());
+        assert!(check("$.n", JsonOp::Gt, json!(3)).is_ok());
+        assert!(check("$.n", JsonOp::Lt, json!(3)).is_err());
+        assert!(check("$.v", JsonOp::Matches, json!(r"^\d+\.\d+")).is_ok());
+        assert!(check("$.n", JsonOp::TypeIs, json!("integer")).is_ok());
+        assert!(check("$.tags", JsonOp::TypeIs, json!("object")).is_err());
+        assert!(check("$.missing", JsonOp::Exists, Value::Null).is_err());
+        // Every selected value must pass
+        assert!(check("$.items[*]", JsonOp::Gt, json!(1)).is_ok());
+        assert!(check("$.items[*]", JsonOp::Gt, json!(2)).is_err());
+        assert!(check("$.v", JsonOp::Gt, json!(1)).is_err());
+        assert!(matches!(
+            check("$.v", JsonOp::TypeIs, json!("date")),
+            Err(CheckFailure::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn test_op_aliases_deserialize() {
+        let op: JsonOp = serde_json::from_value(json!(">")).unwrap();
+        assert_eq!(op, JsonOp::Gt);
+        let op: JsonOp = serde_json::from_value(json!("regex")).unwrap();
+        assert_eq!(op, JsonOp::Matches);
+        let op: JsonOp = serde_json::from_value(json!("type-is")).unwrap();
+        assert_eq!(op, JsonOp::TypeIs);
+
+        for (alias, name) in JSON_OP_ALIASES {
+            let op: JsonOp = serde_json::from_value(json!(alias)).unwrap();
+            assert_eq!(json!(op), json!(name));
+        }
+    }
+
+    #[test]
+    fn test_invalid_paths_are_errors() {
+        assert!(JsonQuery::parse("$[").is_err());
+        assert!(JsonQuery::parse(".items[x]").is_err());
+    }
+}
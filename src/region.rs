@@ -0,0 +1,136 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Where in a file a piece of evidence was found, or should have been
+//!
+//! File evidence records a [`Region`] on its
+//! [`EvidenceResult`](crate::EvidenceResult) so reports can point at lines:
+//! the match for `FileContains` and `FileMatchesRegex` (or, when nothing
+//! matched, the closest near-match), the checked range for `FileLineRange`,
+//! and the definition for `SymbolDefined`.
+
+use serde::{Deserialize, Serialize};
+
+/// How alike a line must be to the wanted text (0.0-1.0) to count as a
+/// near-match
+const NEAR_MATCH: f64 = 0.6;
+
+/// A span of a text file
+///
+/// Lines and columns are 1-based. Columns count characters and
+/// `end_column` is exclusive, as in SARIF regions with
+/// `unicodeCodePoints` columns.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Region {
+    pub start_line: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_column: Option<usize>,
+    pub end_line: usize,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_column: Option<usize>,
+    /// Whether this is the closest near-match rather than an actual match
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub approximate: bool,
+}
+
+impl Region {
+    /// Whole lines `start..=end`
+    pub fn lines(start: usize, end: usize) -> Self {
+        Self {
+            start_line: start,
+            start_column: None,
+            end_line: end.max(start),
+            end_column: None,
+            approximate: false,
+        }
+    }
+
+    /// The span of `text` between byte offsets `start..end`
+    pub fn from_offsets(text: &str, start: usize, end: usize) -> Self {
+        let (start_line, start_column) = position(text, start);
+        let (end_line, end_column) = position(text, end.max(start));
+        Self {
+            start_line,
+            start_column: Some(start_column),
+            end_line,
+            end_column: Some(end_column),
+            approximate: false,
+        }
+    }
+
+    /// The line of `text` most like the first non-blank line of `wanted`,
+    /// if any is alike enough to be a plausible near-miss
+    pub fn closest_line(text: &str, wanted: &str) -> Option<Self> {
+        let wanted = wanted.lines().map(str::trim).find(|l| !l.is_empty())?;
+        let (index, line, score) = text
+            .lines()
+            .enumerate()
+            .map(|(i, line)| (i, line, strsim::normalized_levenshtein(line.trim(), wanted)))
+            .max_by(|a, b| a.2.total_cmp(&b.2))?;
+        if score < NEAR_MATCH {
+            return None;
+        }
+
+        let indent = line.chars().take_while(|c| c.is_whitespace()).count();
+        Some(Self {
+            start_line: index + 1,
+            start_column: Some(indent + 1),
+            end_line: index + 1,
+            end_column: Some(line.trim_end().chars().count() + 1),
+            approximate: true,
+        })
+    }
+
+    /// [`Region::closest_line`] for a regex, compared by its literal text
+    pub fn closest_to_pattern(text: &str, pattern: &str) -> Option<Self> {
+        let literal: String = pattern
+            .chars()
+            .filter(|c| !r"\^$.|?*+()[]{}".contains(*c))
+            .collect();
+        Self::closest_line(text, &literal)
+    }
+}
+
+/// 1-based line and character column of a byte offset
+fn position(text: &str, offset: usize) -> (usize, usize) {
+    let mut offset = offset.min(text.len());
+    while !text.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    let before = &text[..offset];
+    let line_start = before.rfind('\n').map_or(0, |n| n + 1);
+    (
+        before.matches('\n').count() + 1,
+        before[line_start..].chars().count() + 1,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_offsets() {
+        let text = "fn main() {\n    println!(\"héllo\");\n}\n";
+        let at = text.find("héllo").unwrap();
+        let region = Region::from_offsets(text, at, at + "héllo".len());
+        assert_eq!((region.start_line, region.start_column), (2, Some(15)));
+        // Columns count characters, not bytes
+        assert_eq!((region.end_line, region.end_column), (2, Some(20)));
+        assert!(!region.approximate);
+    }
+
+    #[test]
+    fn test_closest_line() {
+        let text = "use std::io;\n\n    let total = count + 1;\nreturn total;\n";
+        let region = Region::closest_line(text, "let total = count + 2;").unwrap();
+        assert_eq!(region.start_line, 3);
+        assert_eq!(region.start_column, Some(5));
+        assert_eq!(region.end_column, Some(27));
+        assert!(region.approximate);
+
+        assert_eq!(Region::closest_line(text, "something else entirely"), None);
+        assert_eq!(Region::closest_line(text, "\n  \n"), None);
+
+        let region = Region::closest_to_pattern(text, r"^return\s+totals;$").unwrap();
+        assert_eq!(region.start_line, 4);
+    }
+}
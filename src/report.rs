@@ -0,0 +1,1905 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Rendering verification reports
+//!
+//! Every output format of `dyadt report` is a [`Reporter`], which writes a
+//! batch of [`VerificationReport`]s to any [`io::Write`]. The built-in
+//! formats are registered in [`Reporters::new`]; embedders can register
+//! their own alongside them, or use a built-in one directly to produce
+//! SARIF, JUnit and the rest without the CLI.
+//!
+//! ## Writing a reporter
+//!
+//! ```rust
+//! use did_you_actually_do_that::report::{ReportContext, Reporter, Reporters};
+//! use did_you_actually_do_that::{Claim, EvidenceSpec, VerificationReport, Verifier};
+//! use std::io::{self, Write};
+//!
+//! /// One `id verdict` line per claim
+//! struct Brief;
+//!
+//! impl Reporter for Brief {
+//!     fn name(&self) -> &str {
+//!         "brief"
+//!     }
+//!
+//!     fn write(
+//!         &self,
+//!         reports: &[VerificationReport],
+//!         _context: &ReportContext,
+//!         out: &mut dyn Write,
+//!     ) -> io::Result<()> {
+//!         for report in reports {
+//!             writeln!(out, "{} {:?}", report.claim.id, report.overall_verdict)?;
+//!         }
+//!         Ok(())
+//!     }
+//! }
+//!
+//! let mut reporters = Reporters::new();
+//! reporters.register(Brief);
+//!
+//! let claim = Claim::new("Root exists").with_evidence(EvidenceSpec::DirectoryExists {
+//!     path: "/".to_string(),
+//! });
+//! let reports = Verifier::new().verify_batch(&[claim]).unwrap();
+//! let context = ReportContext::new();
+//!
+//! let mut brief = Vec::new();
+//! reporters["brief"].write(&reports, &context, &mut brief).unwrap();
+//! assert!(String::from_utf8(brief).unwrap().ends_with(" Confirmed\n"));
+//!
+//! let mut junit = Vec::new();
+//! reporters["junit"].write(&reports, &context, &mut junit).unwrap();
+//! assert!(String::from_utf8(junit).unwrap().contains("<testsuites"));
+//! ```
+
+use crate::baseline::{Baseline, Change, Comparison};
+use crate::checker::{BuiltinChecker, EvidenceChecker};
+use crate::{EvidenceResult, EvidenceSpec, Verdict, VerificationReport};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::io::{self, Write};
+
+/// An output format for verification reports
+///
+/// Reporters must be `Send + Sync` because [`Reporter::on_report`] may be
+/// called from several verification worker threads at once.
+pub trait Reporter: Send + Sync {
+    /// Name used to select this reporter, as in `dyadt report --format NAME`
+    fn name(&self) -> &str;
+
+    /// Short human-readable description of the format
+    fn description(&self) -> &str {
+        ""
+    }
+
+    /// Whether this format can be written a claim at a time with
+    /// [`Reporter::on_report`]. Callers that stream use `on_report` and skip
+    /// [`Reporter::write`]; everyone else calls only `write`.
+    fn streams(&self) -> bool {
+        false
+    }
+
+    /// Write one report as soon as its claim is verified
+    ///
+    /// Streaming formats write here; the default writes nothing. Reports
+    /// arrive in the order they finish, not claim order.
+    fn on_report(
+        &self,
+        _report: &VerificationReport,
+        _context: &ReportContext,
+        _out: &mut dyn Write,
+    ) -> io::Result<()> {
+        Ok(())
+    }
+
+    /// Write the whole run once every claim is verified, in claim order
+    fn write(
+        &self,
+        reports: &[VerificationReport],
+        context: &ReportContext,
+        out: &mut dyn Write,
+    ) -> io::Result<()>;
+}
+
+/// What a reporter may show besides the reports themselves
+#[derive(Debug, Clone, Default)]
+pub struct ReportContext {
+    baseline: Option<Baseline>,
+}
+
+impl ReportContext {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Compare reports against an earlier run's (see `dyadt report --baseline`)
+    pub fn with_baseline(mut self, baseline: Baseline) -> Self {
+        self.baseline = Some(baseline);
+        self
+    }
+
+    pub fn baseline(&self) -> Option<&Baseline> {
+        self.baseline.as_ref()
+    }
+
+    /// Each report compared with the baseline, if there is one
+    pub fn comparisons(&self, reports: &[VerificationReport]) -> Option<Vec<Comparison>> {
+        self.baseline.as_ref().map(|b| b.compare(reports))
+    }
+}
+
+/// Reporters by name
+pub struct Reporters {
+    /// In registration order, so listings put the built-in formats first
+    reporters: Vec<Box<dyn Reporter>>,
+}
+
+impl Default for Reporters {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Reporters {
+    /// The built-in reporters
+    pub fn new() -> Self {
+        let mut reporters = Self {
+            reporters: Vec::new(),
+        };
+        reporters.register(HumanReporter);
+        reporters.register(JsonReporter);
+        reporters.register(SarifReporter);
+        reporters.register(JUnitReporter);
+        reporters.register(MarkdownReporter);
+        reporters.register(HtmlReporter);
+        reporters.register(TapReporter);
+        reporters.register(CsvReporter);
+        reporters.register(NdjsonReporter);
+        reporters
+    }
+
+    /// Register a reporter under its own name, replacing any reporter
+    /// already registered under that name
+    pub fn register(&mut self, reporter: impl Reporter + 'static) {
+        let reporter: Box<dyn Reporter> = Box::new(reporter);
+        match self
+            .reporters
+            .iter_mut()
+            .find(|r| r.name() == reporter.name())
+        {
+            Some(existing) => *existing = reporter,
+            None => self.reporters.push(reporter),
+        }
+    }
+
+    /// Look up a reporter by name
+    pub fn get(&self, name: &str) -> Option<&dyn Reporter> {
+        self.reporters
+            .iter()
+            .find(|r| r.name() == name)
+            .map(|r| r.as_ref())
+    }
+
+    /// All registered reporters, in registration order
+    pub fn all(&self) -> impl Iterator<Item = &dyn Reporter> {
+        self.reporters.iter().map(|r| r.as_ref())
+    }
+}
+
+impl std::ops::Index<&str> for Reporters {
+    type Output = dyn Reporter;
+
+    /// Panics if no reporter is registered under `name`
+    fn index(&self, name: &str) -> &Self::Output {
+        self.reporters
+            .iter()
+            .find(|r| r.name() == name)
+            .map(|r| r.as_ref())
+            .unwrap_or_else(|| panic!("no reporter named '{}'", name))
+    }
+}
+
+/// Plain text for a terminal, as printed by `dyadt report`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HumanReporter;
+
+impl Reporter for HumanReporter {
+    fn name(&self) -> &str {
+        "human"
+    }
+
+    fn description(&self) -> &str {
+        "Plain text for a terminal"
+    }
+
+    fn write(
+        &self,
+        reports: &[VerificationReport],
+        context: &ReportContext,
+        out: &mut dyn Write,
+    ) -> io::Result<()> {
+        writeln!(out, "Verification Report")?;
+        writeln!(out, "===================\n")?;
+        for report in reports {
+            writeln!(out, "{}", format_report(report))?;
+        }
+        writeln!(out, "-------------------")?;
+        writeln!(out, "Overall: {:?}", worst_verdict(reports))?;
+        if let Some(comparisons) = context.comparisons(reports) {
+            write!(out, "{}", baseline_text(&comparisons))?;
+        }
+        Ok(())
+    }
+}
+
+/// One pretty-printed JSON document with every report and a summary
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonReporter;
+
+impl Reporter for JsonReporter {
+    fn name(&self) -> &str {
+        "json"
+    }
+
+    fn description(&self) -> &str {
+        "One JSON document with every report and a summary"
+    }
+
+    fn write(
+        &self,
+        reports: &[VerificationReport],
+        context: &ReportContext,
+        out: &mut dyn Write,
+    ) -> io::Result<()> {
+        let comparisons = context.comparisons(reports);
+        let document = json_document(reports, comparisons.as_deref());
+        serde_json::to_writer_pretty(&mut *out, &document)?;
+        writeln!(out)
+    }
+}
+
+/// SARIF 2.1.0, for code scanning
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SarifReporter;
+
+impl Reporter for SarifReporter {
+    fn name(&self) -> &str {
+        "sarif"
+    }
+
+    fn description(&self) -> &str {
+        "SARIF 2.1.0, for code scanning"
+    }
+
+    fn write(
+        &self,
+        reports: &[VerificationReport],
+        _context: &ReportContext,
+        out: &mut dyn Write,
+    ) -> io::Result<()> {
+        serde_json::to_writer_pretty(&mut *out, &sarif(reports))?;
+        writeln!(out)
+    }
+}
+
+/// JUnit XML, one test suite per claim and one test case per evidence check
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JUnitReporter;
+
+impl Reporter for JUnitReporter {
+    fn name(&self) -> &str {
+        "junit"
+    }
+
+    fn description(&self) -> &str {
+        "JUnit XML, for CI test result views"
+    }
+
+    fn write(
+        &self,
+        reports: &[VerificationReport],
+        _context: &ReportContext,
+        out: &mut dyn Write,
+    ) -> io::Result<()> {
+        out.write_all(junit(reports).as_bytes())
+    }
+}
+
+/// GitHub-flavoured Markdown, one collapsible table per claim
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MarkdownReporter;
+
+impl Reporter for MarkdownReporter {
+    fn name(&self) -> &str {
+        "markdown"
+    }
+
+    fn description(&self) -> &str {
+        "Markdown, for merge request comments"
+    }
+
+    fn write(
+        &self,
+        reports: &[VerificationReport],
+        context: &ReportContext,
+        out: &mut dyn Write,
+    ) -> io::Result<()> {
+        let comparisons = context.comparisons(reports);
+        out.write_all(markdown(reports, comparisons.as_deref()).as_bytes())
+    }
+}
+
+/// A self-contained HTML page, filterable by verdict and source
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HtmlReporter;
+
+impl Reporter for HtmlReporter {
+    fn name(&self) -> &str {
+        "html"
+    }
+
+    fn description(&self) -> &str {
+        "A single-file HTML page"
+    }
+
+    fn write(
+        &self,
+        reports: &[VerificationReport],
+        context: &ReportContext,
+        out: &mut dyn Write,
+    ) -> io::Result<()> {
+        let comparisons = context.comparisons(reports);
+        out.write_all(html(reports, comparisons.as_deref()).as_bytes())
+    }
+}
+
+/// TAP version 14, one test point per claim
+#[derive(Debug, Clone, Copy, Default)]
+pub struct TapReporter;
+
+impl Reporter for TapReporter {
+    fn name(&self) -> &str {
+        "tap"
+    }
+
+    fn description(&self) -> &str {
+        "TAP version 14, one test point per claim"
+    }
+
+    fn write(
+        &self,
+        reports: &[VerificationReport],
+        _context: &ReportContext,
+        out: &mut dyn Write,
+    ) -> io::Result<()> {
+        out.write_all(tap(reports).as_bytes())
+    }
+}
+
+/// CSV, one row per top-level evidence check
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CsvReporter;
+
+impl Reporter for CsvReporter {
+    fn name(&self) -> &str {
+        "csv"
+    }
+
+    fn description(&self) -> &str {
+        "CSV, one row per top-level evidence check"
+    }
+
+    fn write(
+        &self,
+        reports: &[VerificationReport],
+        context: &ReportContext,
+        out: &mut dyn Write,
+    ) -> io::Result<()> {
+        let comparisons = context.comparisons(reports);
+        out.write_all(csv(reports, comparisons.as_deref()).as_bytes())
+    }
+}
+
+/// One JSON report per line, written as each claim finishes
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NdjsonReporter;
+
+impl Reporter for NdjsonReporter {
+    fn name(&self) -> &str {
+        "ndjson"
+    }
+
+    fn description(&self) -> &str {
+        "One JSON report per line, streamed as claims finish"
+    }
+
+    fn streams(&self) -> bool {
+        true
+    }
+
+    fn on_report(
+        &self,
+        report: &VerificationReport,
+        context: &ReportContext,
+        out: &mut dyn Write,
+    ) -> io::Result<()> {
+        // A single write per line, so lines from concurrent workers never interleave
+        let mut line = ndjson_line(report, context.baseline()).to_string();
+        line.push('\n');
+        out.write_all(line.as_bytes())?;
+        out.flush()
+    }
+
+    fn write(
+        &self,
+        reports: &[VerificationReport],
+        context: &ReportContext,
+        out: &mut dyn Write,
+    ) -> io::Result<()> {
+        for report in reports {
+            self.on_report(report, context, out)?;
+        }
+        Ok(())
+    }
+}
+
+/// Plain-text rendering of one report, as printed by `dyadt check`
+pub fn format_report(report: &VerificationReport) -> String {
+    let mut output = format!("{}\n", report.summary());
+
+    if let Some(ref source) = report.claim.source {
+        output.push_str(&format!("  Source: {}\n", source));
+    }
+    if let Some(ref reason) = report.skipped {
+        output.push_str(&format!("  Skipped: {}\n", reason));
+    }
+    if !report.evidence_results.is_empty() {
+        output.push_str(&format!(
+            "  Confidence: {:.0}%\n",
+            report.confidence * 100.0
+        ));
+    }
+
+    for result in &report.evidence_results {
+        push_evidence_result(&mut output, result, 1);
+    }
+    output
+}
+
+/// Append one evidence result, indenting nested results under composites
+fn push_evidence_result(output: &mut String, result: &EvidenceResult, depth: usize) {
+    let indent = "  ".repeat(depth);
+
+    output.push_str(&format!(
+        "{}{} {}\n",
+        indent,
+        verdict_icon(result.verdict),
+        describe_evidence(&result.spec)
+    ));
+
+    if let Some(ref details) = result.details {
+        output.push_str(&format!("{}    {}\n", indent, details));
+    }
+
+    for (name, value) in &result.artifacts {
+        match value {
+            Value::String(text) => {
+                output.push_str(&format!("{}    {}:\n", indent, name));
+                for line in text.lines() {
+                    output.push_str(&format!("{}      {}\n", indent, line));
+                }
+            }
+            other => output.push_str(&format!("{}    {}: {}\n", indent, name, other)),
+        }
+    }
+
+    for child in &result.children {
+        push_evidence_result(output, child, depth + 1);
+    }
+}
+
+/// Icon for a verdict in text, Markdown and HTML reports
+pub fn verdict_icon(verdict: Verdict) -> &'static str {
+    match verdict {
+        Verdict::Confirmed => "✓",
+        Verdict::Refuted => "✗",
+        Verdict::Inconclusive => "?",
+        Verdict::Unverifiable => "⊘",
+    }
+}
+
+/// Human-readable one-line description of an evidence check
+pub fn describe_evidence(spec: &EvidenceSpec) -> String {
+    match spec {
+        EvidenceSpec::FileExists { path } => format!("File exists: {}", path),
+        EvidenceSpec::FileWithHash { path, .. } => format!("File hash: {}", path),
+        EvidenceSpec::FileContains { path, substring } => {
+            format!("File contains '{}': {}", substring, path)
+        }
+        EvidenceSpec::FileMatchesRegex { path, pattern } => {
+            format!("File matches regex '{}': {}", pattern, path)
+        }
+        EvidenceSpec::FileLineRange {
+            path, start, end, ..
+        } => match end {
+            Some(end) => format!("Lines {}-{}: {}", start, end, path),
+            None => format!("Lines from {}: {}", start, path),
+        },
+        EvidenceSpec::FileUnifiedDiffApplied { path, .. } => format!("Diff applied: {}", path),
+        EvidenceSpec::FileJsonPath {
+            path, json_path, ..
+        } => {
+            format!("JSON path '{}': {}", json_path, path)
+        }
+        EvidenceSpec::FileStructuredPath { path, query, .. } => {
+            format!("Config path '{}': {}", query, path)
+        }
+        EvidenceSpec::SymbolDefined { path, symbol, .. } => {
+            format!("Symbol {} defined: {}", symbol, path)
+        }
+        EvidenceSpec::DirectoryExists { path } => format!("Directory exists: {}", path),
+        EvidenceSpec::CommandSucceeds { command, .. } => {
+            format!("Command succeeds: {}", command)
+        }
+        EvidenceSpec::TestPasses { name, runner, .. } => {
+            format!("Test passes ({}): {}", runner.program(), name)
+        }
+        EvidenceSpec::GitClean { repo_path } => {
+            format!("Git clean: {}", repo_path.as_deref().unwrap_or("."))
+        }
+        EvidenceSpec::GitCommitExists { commit, .. } => {
+            format!("Git commit exists: {}", commit)
+        }
+        EvidenceSpec::GitBranchExists { branch, .. } => {
+            format!("Git branch exists: {}", branch)
+        }
+        EvidenceSpec::FileModifiedAfter { path, after } => {
+            format!("File modified after {}: {}", after, path)
+        }
+        EvidenceSpec::EnvVar { name, expected } => {
+            format!("Env {}={}", name, expected)
+        }
+        EvidenceSpec::FileChangedInCommit { commit, path, .. } => {
+            format!("{} changes {}", commit, path)
+        }
+        EvidenceSpec::CommitTouchesPaths { commit, paths, .. } => {
+            format!("{} touches {}", commit, paths.join(", "))
+        }
+        EvidenceSpec::CommitDiffMatches {
+            commit, pattern, ..
+        } => format!("{} diff matches /{}/", commit, pattern),
+        EvidenceSpec::CommitMessageMatches {
+            commit, pattern, ..
+        } => format!("{} message matches /{}/", commit, pattern),
+        EvidenceSpec::CommitIsAncestor { commit, .. } => format!("HEAD descends from {}", commit),
+        EvidenceSpec::FileAbsent { path } => format!("File absent: {}", path),
+        EvidenceSpec::DirectoryAbsent { path } => format!("Directory absent: {}", path),
+        EvidenceSpec::GitBranchAbsent { branch, .. } => {
+            format!("Git branch absent: {}", branch)
+        }
+        EvidenceSpec::EnvVarAbsent { name } => format!("Env {} unset", name),
+        EvidenceSpec::AllOf { evidence } => format!("All of {} checks", evidence.len()),
+        EvidenceSpec::AnyOf { evidence } => format!("Any of {} checks", evidence.len()),
+        EvidenceSpec::Not { .. } => "Must not hold:".to_string(),
+        EvidenceSpec::Weighted {
+            weight, required, ..
+        } => {
+            if *required {
+                format!("Required (weight {}):", weight)
+            } else {
+                format!("Weight {}:", weight)
+            }
+        }
+        EvidenceSpec::AtLeast { n, evidence } => {
+            format!("At least {} of {} checks", n, evidence.len())
+        }
+        EvidenceSpec::Custom { name, .. } => format!("Custom check: {}", name),
+    }
+}
+
+/// The worst verdict across all reports (Confirmed when there are none)
+pub fn worst_verdict(reports: &[VerificationReport]) -> Verdict {
+    reports.iter().fold(Verdict::Confirmed, |acc, r| {
+        match (acc, r.overall_verdict) {
+            (_, Verdict::Refuted) | (Verdict::Refuted, _) => Verdict::Refuted,
+            (_, Verdict::Inconclusive) | (Verdict::Inconclusive, _) => Verdict::Inconclusive,
+            (_, Verdict::Unverifiable) | (Verdict::Unverifiable, _) => Verdict::Unverifiable,
+            (Verdict::Confirmed, Verdict::Confirmed) => Verdict::Confirmed,
+        }
+    })
+}
+
+/// Count reports by verdict
+pub fn report_summary(reports: &[VerificationReport]) -> Value {
+    let count = |verdict| {
+        reports
+            .iter()
+            .filter(|r| r.overall_verdict == verdict)
+            .count()
+    };
+    json!({
+        "total": reports.len(),
+        "confirmed": count(Verdict::Confirmed),
+        "refuted": count(Verdict::Refuted),
+        "inconclusive": count(Verdict::Inconclusive),
+        "unverifiable": count(Verdict::Unverifiable)
+    })
+}
+
+/// Count comparisons by kind of change
+pub fn baseline_summary(comparisons: &[Comparison]) -> Value {
+    let count = |change| comparisons.iter().filter(|c| c.change == change).count();
+    json!({
+        "new": count(Change::New),
+        "fixed": count(Change::Fixed),
+        "regressed": count(Change::Regressed),
+        "unchanged": count(Change::Unchanged)
+    })
+}
+
+/// The claims that moved relative to the baseline, as plain text
+fn baseline_text(comparisons: &[Comparison]) -> String {
+    let mut output = format!("Baseline: {}\n", baseline_line(comparisons));
+    for change in [Change::Regressed, Change::Fixed, Change::New] {
+        for comparison in comparisons.iter().filter(|c| c.change == change) {
+            let before = comparison
+                .before
+                .map_or("-".to_string(), |v| format!("{:?}", v));
+            output.push_str(&format!(
+                "  {:<9} {} [{}] ({} -> {:?})\n",
+                change, comparison.description, comparison.claim_id, before, comparison.after
+            ));
+        }
+    }
+    output
+}
+
+/// The `dyadt report --json` document
+fn json_document(reports: &[VerificationReport], comparisons: Option<&[Comparison]>) -> Value {
+    let mut output = json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "tool": {
+            "name": "did-you-actually-do-that",
+            "version": env!("CARGO_PKG_VERSION")
+        },
+        "reports": reports,
+        "summary": report_summary(reports)
+    });
+    if let Some(comparisons) = comparisons {
+        output["baseline"] = json!({
+            "summary": baseline_summary(comparisons),
+            "claims": comparisons
+        });
+    }
+    output
+}
+
+/// One-line verdict counts shared by the Markdown and HTML reports
+fn summary_line(reports: &[VerificationReport]) -> String {
+    let summary = report_summary(reports);
+    format!(
+        "{} claims: {} confirmed, {} refuted, {} inconclusive, {} unverifiable",
+        summary["total"],
+        summary["confirmed"],
+        summary["refuted"],
+        summary["inconclusive"],
+        summary["unverifiable"]
+    )
+}
+
+/// One-line change counts shared by the Markdown and HTML reports
+fn baseline_line(comparisons: &[Comparison]) -> String {
+    let summary = baseline_summary(comparisons);
+    format!(
+        "{} regressed, {} fixed, {} new, {} unchanged",
+        summary["regressed"], summary["fixed"], summary["new"], summary["unchanged"]
+    )
+}
+
+/// Render reports as GitHub-flavoured Markdown, one collapsible table per
+/// claim, for pasting into a merge request comment
+fn markdown(reports: &[VerificationReport], comparisons: Option<&[Comparison]>) -> String {
+    let mut output = String::new();
+
+    output.push_str("## Verification Report\n\n");
+    output.push_str(&format!(
+        "**Overall: {:?}** ({})\n\n",
+        worst_verdict(reports),
+        summary_line(reports)
+    ));
+    if let Some(comparisons) = comparisons {
+        output.push_str(&format!("**Baseline:** {}\n\n", baseline_line(comparisons)));
+    }
+
+    for (i, report) in reports.iter().enumerate() {
+        let change = comparisons.and_then(|c| c.get(i)).map(|c| c.change);
+        // Expand the claims a reviewer needs to look at
+        let open = report.overall_verdict == Verdict::Refuted || change == Some(Change::Regressed);
+
+        output.push_str(if open {
+            "<details open>\n"
+        } else {
+            "<details>\n"
+        });
+        output.push_str(&format!(
+            "<summary>{} <b>{}</b> &mdash; {:?}",
+            verdict_icon(report.overall_verdict),
+            xml_escape(&report.claim.description),
+            report.overall_verdict
+        ));
+        if !report.evidence_results.is_empty() {
+            output.push_str(&format!(" ({:.0}%)", report.confidence * 100.0));
+        }
+        if let Some(change) = change.filter(|c| *c != Change::Unchanged) {
+            output.push_str(&format!(" &middot; {}", change));
+        }
+        output.push_str("</summary>\n\n");
+
+        output.push_str(&format!("Id: {}", markdown_escape(&report.claim.id)));
+        if let Some(ref source) = report.claim.source {
+            output.push_str(&format!(" &middot; Source: {}", markdown_escape(source)));
+        }
+        output.push_str("\n\n");
+        if let Some(ref reason) = report.skipped {
+            output.push_str(&format!("Skipped: {}\n\n", markdown_escape(reason)));
+        }
+
+        if report.evidence_results.is_empty() {
+            output.push_str("No evidence given.\n\n");
+        } else {
+            output.push_str("| | Evidence | Details |\n|---|---|---|\n");
+            for result in &report.evidence_results {
+                push_markdown_rows(&mut output, result, 0);
+            }
+            output.push('\n');
+        }
+        output.push_str("</details>\n\n");
+    }
+
+    output
+}
+
+/// Append a table row for a result and each of its nested results
+fn push_markdown_rows(output: &mut String, result: &EvidenceResult, depth: usize) {
+    let nesting = if depth == 0 {
+        String::new()
+    } else {
+        format!("{}↳ ", "&nbsp;&nbsp;".repeat(depth - 1))
+    };
+    output.push_str(&format!(
+        "| {} | {}{} | {} |\n",
+        verdict_icon(result.verdict),
+        nesting,
+        markdown_escape(&describe_evidence(&result.spec)),
+        markdown_escape(result.details.as_deref().unwrap_or(""))
+    ));
+    for child in &result.children {
+        push_markdown_rows(output, child, depth + 1);
+    }
+}
+
+/// Escape text for a single Markdown table cell
+fn markdown_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '\n' => escaped.push_str("<br>"),
+            '\r' => {}
+            '\\' | '`' | '*' | '_' | '[' | ']' | '|' | '#' | '~' => {
+                escaped.push('\\');
+                escaped.push(c);
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+const HTML_STYLE: &str = r#"body { font-family: system-ui, sans-serif; margin: 2rem auto; max-width: 60rem; padding: 0 1rem; color: #1f2328; }
+.filters { display: flex; gap: 1rem; margin: 1rem 0; }
+details.claim { border: 1px solid #d0d7de; border-radius: 6px; margin: 0.5rem 0; padding: 0.5rem 0.75rem; }
+details.claim > summary { cursor: pointer; }
+.badge { border-radius: 1em; color: #fff; font-size: 0.8em; padding: 0.1em 0.6em; }
+.Confirmed { background: #1a7f37; } .Refuted { background: #cf222e; }
+.Inconclusive { background: #9a6700; } .Unverifiable { background: #6e7781; }
+.change { font-size: 0.8em; font-weight: bold; text-transform: uppercase; }
+.change.regressed { color: #cf222e; } .change.fixed { color: #1a7f37; } .change.new { color: #0969da; }
+.meta { color: #59636e; font-size: 0.9em; }
+ul.evidence { list-style: none; padding-left: 1.25rem; }
+.result-details { color: #59636e; margin-left: 1.5em; }
+pre { background: #f6f8fa; overflow-x: auto; padding: 0.5rem; }"#;
+
+const HTML_SCRIPT: &str = r#"const verdict = document.getElementById("verdict");
+const source = document.getElementById("source");
+function applyFilters() {
+  let shown = 0;
+  for (const claim of document.querySelectorAll("details.claim")) {
+    claim.hidden = (verdict.value && claim.dataset.verdict !== verdict.value)
+      || (source.value && claim.dataset.source !== source.value);
+    if (!claim.hidden) shown++;
+  }
+  document.getElementById("empty").hidden = shown > 0;
+}
+verdict.addEventListener("change", applyFilters);
+source.addEventListener("change", applyFilters);"#;
+
+/// Source label for claims that do not name one, in the HTML source filter
+const NO_SOURCE: &str = "(none)";
+
+/// Render reports as a self-contained HTML page, filterable by verdict and
+/// source, with each claim's evidence expandable
+fn html(reports: &[VerificationReport], comparisons: Option<&[Comparison]>) -> String {
+    let mut output = String::new();
+
+    output.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    output.push_str("<title>Verification Report</title>\n");
+    output.push_str(&format!(
+        "<style>\n{}\n</style>\n</head>\n<body>\n",
+        HTML_STYLE
+    ));
+    output.push_str("<h1>Verification Report</h1>\n");
+
+    let overall = worst_verdict(reports);
+    output.push_str(&format!(
+        "<p><span class=\"badge {:?}\">{:?}</span> {}</p>\n",
+        overall,
+        overall,
+        xml_escape(&summary_line(reports))
+    ));
+    if let Some(comparisons) = comparisons {
+        output.push_str(&format!(
+            "<p>Baseline: {}</p>\n",
+            xml_escape(&baseline_line(comparisons))
+        ));
+    }
+
+    output.push_str("<div class=\"filters\">\n");
+    output.push_str("<label>Verdict <select id=\"verdict\"><option value=\"\">All</option>");
+    for verdict in [
+        Verdict::Confirmed,
+        Verdict::Refuted,
+        Verdict::Inconclusive,
+        Verdict::Unverifiable,
+    ] {
+        output.push_str(&format!("<option>{:?}</option>", verdict));
+    }
+    output.push_str("</select></label>\n");
+    let sources: std::collections::BTreeSet<&str> = reports
+        .iter()
+        .map(|r| r.claim.source.as_deref().unwrap_or(NO_SOURCE))
+        .collect();
+    output.push_str("<label>Source <select id=\"source\"><option value=\"\">All</option>");
+    for source in sources {
+        output.push_str(&format!("<option>{}</option>", xml_escape(source)));
+    }
+    output.push_str("</select></label>\n</div>\n");
+
+    for (i, report) in reports.iter().enumerate() {
+        let change = comparisons.and_then(|c| c.get(i)).map(|c| c.change);
+        let source = report.claim.source.as_deref().unwrap_or(NO_SOURCE);
+
+        output.push_str(&format!(
+            "<details class=\"claim\" data-verdict=\"{:?}\" data-source=\"{}\">\n",
+            report.overall_verdict,
+            xml_escape(source)
+        ));
+        output.push_str(&format!(
+            "<summary><span class=\"badge {:?}\">{:?}</span> {}",
+            report.overall_verdict,
+            report.overall_verdict,
+            xml_escape(&report.claim.description)
+        ));
+        if !report.evidence_results.is_empty() {
+            output.push_str(&format!(
+                " <span class=\"meta\">{:.0}%</span>",
+                report.confidence * 100.0
+            ));
+        }
+        if let Some(change) = change.filter(|c| *c != Change::Unchanged) {
+            output.push_str(&format!(" <span class=\"change {0}\">{0}</span>", change));
+        }
+        output.push_str("</summary>\n");
+
+        output.push_str(&format!(
+            "<p class=\"meta\">Id: {} &middot; Source: {} &middot; Verified: {}</p>\n",
+            xml_escape(&report.claim.id),
+            xml_escape(source),
+            report.verified_at.to_rfc3339()
+        ));
+        if let Some(ref reason) = report.skipped {
+            output.push_str(&format!("<p>Skipped: {}</p>\n", xml_escape(reason)));
+        }
+        if report.evidence_results.is_empty() {
+            output.push_str("<p>No evidence given.</p>\n");
+        } else {
+            push_html_results(&mut output, &report.evidence_results);
+        }
+        output.push_str("</details>\n");
+    }
+
+    output.push_str("<p id=\"empty\" hidden>No claims match the filters.</p>\n");
+    output.push_str(&format!(
+        "<script>\n{}\n</script>\n</body>\n</html>\n",
+        HTML_SCRIPT
+    ));
+    output
+}
+
+/// Append a list of evidence results, nesting composite children
+fn push_html_results(output: &mut String, results: &[EvidenceResult]) {
+    output.push_str("<ul class=\"evidence\">\n");
+    for result in results {
+        output.push_str(&format!(
+            "<li>{} {}",
+            verdict_icon(result.verdict),
+            xml_escape(&describe_evidence(&result.spec))
+        ));
+        if let Some(ref details) = result.details {
+            output.push_str(&format!(
+                "<div class=\"result-details\">{}</div>",
+                xml_escape(details)
+            ));
+        }
+        for (name, value) in &result.artifacts {
+            let text = match value {
+                Value::String(text) => text.clone(),
+                other => other.to_string(),
+            };
+            output.push_str(&format!(
+                "<details><summary>{}</summary><pre>{}</pre></details>",
+                xml_escape(name),
+                xml_escape(&text)
+            ));
+        }
+        if !result.children.is_empty() {
+            output.push('\n');
+            push_html_results(output, &result.children);
+        }
+        output.push_str("</li>\n");
+    }
+    output.push_str("</ul>\n");
+}
+
+/// One report as a JSON object, with its baseline change if any
+fn ndjson_line(report: &VerificationReport, baseline: Option<&Baseline>) -> Value {
+    let mut line = serde_json::to_value(report).unwrap();
+    if let Some(baseline) = baseline {
+        let comparison = &baseline.compare(std::slice::from_ref(report))[0];
+        line["baseline"] = json!({
+            "change": comparison.change,
+            "before": comparison.before
+        });
+    }
+    line
+}
+
+/// Render reports as TAP (Test Anything Protocol) version 14, one test
+/// point per claim
+///
+/// Refuted and Inconclusive claims are `not ok`. Unverifiable claims and
+/// claims skipped for a failed dependency are `ok # SKIP`, so only claims
+/// that could have been checked fail the run.
+fn tap(reports: &[VerificationReport]) -> String {
+    let mut output = String::new();
+    output.push_str("TAP version 14\n");
+    output.push_str(&format!("1..{}\n", reports.len()));
+
+    for (i, report) in reports.iter().enumerate() {
+        let description = tap_escape(&report.claim.description);
+        let skip = match (&report.skipped, report.overall_verdict) {
+            (Some(reason), _) => Some(reason.clone()),
+            (None, Verdict::Unverifiable) => Some("Unverifiable".to_string()),
+            _ => None,
+        };
+
+        if let Some(reason) = skip {
+            output.push_str(&format!(
+                "ok {} - {} # SKIP {}\n",
+                i + 1,
+                description,
+                tap_escape(&reason)
+            ));
+            continue;
+        }
+
+        let ok = report.overall_verdict == Verdict::Confirmed;
+        output.push_str(&format!(
+            "{} {} - {}\n",
+            if ok { "ok" } else { "not ok" },
+            i + 1,
+            description
+        ));
+        if ok {
+            continue;
+        }
+
+        // YAML diagnostics for failures
+        let evidence: Vec<Value> = report
+            .evidence_results
+            .iter()
+            .filter(|r| r.verdict != Verdict::Confirmed)
+            .map(|r| {
+                json!({
+                    "check": evidence_name(&r.spec),
+                    "verdict": r.verdict,
+                    "details": flatten_details(r)
+                })
+            })
+            .collect();
+        let diagnostics = json!({
+            "claim_id": report.claim.id,
+            "verdict": report.overall_verdict,
+            "confidence": report.confidence,
+            "evidence": evidence
+        });
+        let yaml = serde_yaml::to_string(&diagnostics).unwrap_or_default();
+        output.push_str("  ---\n");
+        for line in yaml.lines() {
+            output.push_str(&format!("  {}\n", line));
+        }
+        output.push_str("  ...\n");
+    }
+
+    output
+}
+
+/// Keep a TAP description on one line and stop `#` starting a directive
+fn tap_escape(s: &str) -> String {
+    s.replace('\\', "\\\\")
+        .replace('#', "\\#")
+        .replace(['\r', '\n'], " ")
+}
+
+/// Render reports as CSV (RFC 4180), one row per top-level evidence check
+///
+/// Claims without evidence get a single row with the evidence columns
+/// empty. Nested results of composite evidence are folded into `details`.
+fn csv(reports: &[VerificationReport], comparisons: Option<&[Comparison]>) -> String {
+    let mut output = String::new();
+    let mut header = vec![
+        "claim_id",
+        "claim",
+        "source",
+        "claim_verdict",
+        "confidence",
+        "verified_at",
+        "evidence",
+        "evidence_verdict",
+        "details",
+    ];
+    if comparisons.is_some() {
+        header.push("change");
+    }
+    output.push_str(&header.join(","));
+    output.push_str("\r\n");
+
+    for (i, report) in reports.iter().enumerate() {
+        let claim_fields = [
+            report.claim.id.clone(),
+            report.claim.description.clone(),
+            report.claim.source.clone().unwrap_or_default(),
+            format!("{:?}", report.overall_verdict),
+            format!("{:.3}", report.confidence),
+            report.verified_at.to_rfc3339(),
+        ];
+        let change = comparisons
+            .and_then(|c| c.get(i))
+            .map(|c| c.change.to_string());
+
+        let evidence_fields: Vec<[String; 3]> = if report.evidence_results.is_empty() {
+            vec![[
+                String::new(),
+                String::new(),
+                report.skipped.clone().unwrap_or_default(),
+            ]]
+        } else {
+            report
+                .evidence_results
+                .iter()
+                .map(|r| {
+                    [
+                        evidence_name(&r.spec),
+                        format!("{:?}", r.verdict),
+                        flatten_details(r),
+                    ]
+                })
+                .collect()
+        };
+
+        for evidence in evidence_fields {
+            let row: Vec<String> = claim_fields
+                .iter()
+                .chain(&evidence)
+                .chain(&change)
+                .map(|field| csv_field(field))
+                .collect();
+            output.push_str(&row.join(","));
+            output.push_str("\r\n");
+        }
+    }
+
+    output
+}
+
+/// Quote a CSV field if it contains a delimiter, quote or line break.
+/// Claim text comes from the agent being audited, so a field a spreadsheet
+/// would run as a formula gets a leading `'` to keep it text.
+fn csv_field(field: &str) -> String {
+    let field = if field.starts_with(['=', '+', '-', '@', '\t', '\r']) {
+        format!("'{}", field)
+    } else {
+        field.to_string()
+    };
+    if field.contains([',', '"', '\r', '\n']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field
+    }
+}
+
+/// Render reports as SARIF (Static Analysis Results Interchange Format)
+/// Useful for GitHub code scanning integration
+fn sarif(reports: &[VerificationReport]) -> Value {
+    let results: Vec<Value> = reports
+        .iter()
+        .flat_map(|report| {
+            report.evidence_results.iter().filter_map(|result| {
+                // Only include refuted or inconclusive results as findings
+                if result.verdict == Verdict::Confirmed {
+                    return None;
+                }
+
+                let level = match result.verdict {
+                    Verdict::Refuted => "error",
+                    Verdict::Inconclusive => "warning",
+                    Verdict::Unverifiable => "note",
+                    Verdict::Confirmed => unreachable!(),
+                };
+
+                let rule_id = sarif_rule_id(&result.spec);
+
+                let mut finding = json!({
+                    "ruleId": rule_id,
+                    "level": level,
+                    "message": {
+                        "text": flatten_details(result)
+                    },
+                    "locations": sarif_location(result).into_iter().collect::<Vec<_>>(),
+                    "partialFingerprints": {
+                        "claimEvidence/v1": sarif_fingerprint(&report.claim.id, &result.spec)
+                    },
+                    "properties": {
+                        "claim": report.claim.description.clone(),
+                        "claimId": report.claim.id.clone()
+                    }
+                });
+
+                let suppressions: Vec<Value> = report
+                    .claim
+                    .suppressions
+                    .iter()
+                    .filter(|s| s.covers(rule_id))
+                    .map(|s| {
+                        json!({
+                            "kind": "external",
+                            "status": "accepted",
+                            "justification": s.justification
+                        })
+                    })
+                    .collect();
+                if !suppressions.is_empty() {
+                    finding["suppressions"] = json!(suppressions);
+                }
+                if let Some(index) = SARIF_RULES.iter().position(|(id, _, _)| *id == rule_id) {
+                    finding["ruleIndex"] = json!(index);
+                }
+
+                Some(finding)
+            })
+        })
+        .collect();
+
+    let rules: Vec<Value> = SARIF_RULES
+        .iter()
+        .map(|(id, type_name, short)| {
+            let full = BuiltinChecker::get(type_name)
+                .map(|c| c.description())
+                .unwrap_or("Evidence checked by a registered custom checker");
+            json!({
+                "id": id,
+                "name": type_name,
+                "shortDescription": {"text": short},
+                "fullDescription": {"text": full},
+                "helpUri": SARIF_HELP_URI
+            })
+        })
+        .collect();
+
+    json!({
+        "$schema": "https://raw.githubusercontent.com/oasis-tcs/sarif-spec/master/Schemata/sarif-schema-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "did-you-actually-do-that",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": "https://gitlab.com/hyperpolymath/did-you-actually-do-that",
+                    "rules": rules
+                }
+            },
+            "columnKind": "unicodeCodePoints",
+            "results": results
+        }]
+    })
+}
+
+/// Where the evidence types are documented
+const SARIF_HELP_URI: &str =
+    "https://gitlab.com/hyperpolymath/did-you-actually-do-that#evidence-types";
+
+/// The SARIF rule a failure of `spec` is reported under
+fn sarif_rule_id(spec: &EvidenceSpec) -> &'static str {
+    match spec {
+        EvidenceSpec::FileExists { .. } => "file-exists",
+        EvidenceSpec::FileWithHash { .. } => "file-hash",
+        EvidenceSpec::FileContains { .. } => "file-contains",
+        EvidenceSpec::FileMatchesRegex { .. } => "file-regex",
+        EvidenceSpec::FileLineRange { .. } => "file-line-range",
+        EvidenceSpec::FileUnifiedDiffApplied { .. } => "file-diff-applied",
+        EvidenceSpec::FileJsonPath { .. } => "file-json-path",
+        EvidenceSpec::FileStructuredPath { .. } => "file-structured-path",
+        EvidenceSpec::SymbolDefined { .. } => "symbol-defined",
+        EvidenceSpec::DirectoryExists { .. } => "directory-exists",
+        EvidenceSpec::CommandSucceeds { .. } => "command-succeeds",
+        EvidenceSpec::TestPasses { .. } => "test-passes",
+        EvidenceSpec::GitClean { .. } => "git-clean",
+        EvidenceSpec::GitCommitExists { .. } => "git-commit-exists",
+        EvidenceSpec::GitBranchExists { .. } => "git-branch-exists",
+        EvidenceSpec::FileModifiedAfter { .. } => "file-modified-after",
+        EvidenceSpec::EnvVar { .. } => "env-var",
+        EvidenceSpec::FileChangedInCommit { .. } => "file-changed-in-commit",
+        EvidenceSpec::CommitTouchesPaths { .. } => "commit-touches-paths",
+        EvidenceSpec::CommitDiffMatches { .. } => "commit-diff-matches",
+        EvidenceSpec::CommitMessageMatches { .. } => "commit-message-matches",
+        EvidenceSpec::CommitIsAncestor { .. } => "commit-is-ancestor",
+        EvidenceSpec::FileAbsent { .. } => "file-absent",
+        EvidenceSpec::DirectoryAbsent { .. } => "directory-absent",
+        EvidenceSpec::GitBranchAbsent { .. } => "git-branch-absent",
+        EvidenceSpec::EnvVarAbsent { .. } => "env-var-absent",
+        EvidenceSpec::AllOf { .. } => "all-of",
+        EvidenceSpec::AnyOf { .. } => "any-of",
+        EvidenceSpec::Not { .. } => "not",
+        EvidenceSpec::Weighted { .. } => "weighted",
+        EvidenceSpec::AtLeast { .. } => "at-least",
+        EvidenceSpec::Custom { .. } => "custom",
+    }
+}
+
+/// SARIF rule id, evidence type name and short description of each rule
+const SARIF_RULES: &[(&str, &str, &str)] = &[
+    ("file-exists", "FileExists", "File existence check"),
+    ("file-hash", "FileWithHash", "File hash verification"),
+    ("file-contains", "FileContains", "File content check"),
+    ("file-regex", "FileMatchesRegex", "File regex match"),
+    (
+        "file-line-range",
+        "FileLineRange",
+        "Exact line range content",
+    ),
+    (
+        "file-diff-applied",
+        "FileUnifiedDiffApplied",
+        "Unified diff applied to file",
+    ),
+    ("file-json-path", "FileJsonPath", "JSON path verification"),
+    (
+        "file-structured-path",
+        "FileStructuredPath",
+        "Config file path verification",
+    ),
+    (
+        "symbol-defined",
+        "SymbolDefined",
+        "Source symbol definition",
+    ),
+    (
+        "directory-exists",
+        "DirectoryExists",
+        "Directory existence check",
+    ),
+    (
+        "command-succeeds",
+        "CommandSucceeds",
+        "Command success verification",
+    ),
+    (
+        "test-passes",
+        "TestPasses",
+        "Named test outcome verification",
+    ),
+    ("git-clean", "GitClean", "Git working directory check"),
+    (
+        "git-commit-exists",
+        "GitCommitExists",
+        "Git commit existence",
+    ),
+    (
+        "git-branch-exists",
+        "GitBranchExists",
+        "Git branch existence",
+    ),
+    (
+        "file-modified-after",
+        "FileModifiedAfter",
+        "File modification time check",
+    ),
+    ("env-var", "EnvVar", "Environment variable check"),
+    (
+        "file-changed-in-commit",
+        "FileChangedInCommit",
+        "Commit changes a path",
+    ),
+    (
+        "commit-touches-paths",
+        "CommitTouchesPaths",
+        "Commit changes every listed path",
+    ),
+    (
+        "commit-diff-matches",
+        "CommitDiffMatches",
+        "Commit diff line match",
+    ),
+    (
+        "commit-message-matches",
+        "CommitMessageMatches",
+        "Commit message match",
+    ),
+    (
+        "commit-is-ancestor",
+        "CommitIsAncestor",
+        "HEAD descends from commit",
+    ),
+    ("file-absent", "FileAbsent", "File absence check"),
+    (
+        "directory-absent",
+        "DirectoryAbsent",
+        "Directory absence check",
+    ),
+    ("git-branch-absent", "GitBranchAbsent", "Git branch absence"),
+    (
+        "env-var-absent",
+        "EnvVarAbsent",
+        "Environment variable unset check",
+    ),
+    ("all-of", "AllOf", "All nested checks must hold"),
+    ("any-of", "AnyOf", "At least one nested check must hold"),
+    ("not", "Not", "Nested check must not hold"),
+    ("weighted", "Weighted", "Weighted nested check"),
+    ("at-least", "AtLeast", "At least N nested checks must hold"),
+    ("custom", "Custom", "Custom verification"),
+];
+
+/// A SARIF location for a result: the file, directory or repository it
+/// checked (with the region it matched or nearly matched), or a logical
+/// location for commands, environment variables and custom checkers.
+/// Composite evidence is located at its first failing child.
+fn sarif_location(result: &EvidenceResult) -> Option<Value> {
+    if !result.children.is_empty() {
+        return result
+            .children
+            .iter()
+            .filter(|c| c.verdict != Verdict::Confirmed)
+            .chain(&result.children)
+            .find_map(sarif_location);
+    }
+
+    let logical = |name: &str, kind: &str| {
+        json!({
+            "logicalLocations": [{"name": name, "kind": kind}]
+        })
+    };
+    match &result.spec {
+        EvidenceSpec::CommandSucceeds { command, .. } => Some(logical(command, "resource")),
+        EvidenceSpec::EnvVar { name, .. } | EvidenceSpec::EnvVarAbsent { name } => {
+            Some(logical(name, "variable"))
+        }
+        EvidenceSpec::Custom { name, .. } => Some(logical(name, "resource")),
+        spec => {
+            let mut physical = json!({
+                "artifactLocation": {"uri": artifact_uri(&evidence_path(spec)?)}
+            });
+            if let Some(region) = &result.region {
+                let mut sarif_region = json!({
+                    "startLine": region.start_line,
+                    "endLine": region.end_line
+                });
+                if let (Some(start), Some(end)) = (region.start_column, region.end_column) {
+                    sarif_region["startColumn"] = json!(start);
+                    sarif_region["endColumn"] = json!(end);
+                }
+                physical["region"] = sarif_region;
+            }
+            Some(json!({ "physicalLocation": physical }))
+        }
+    }
+}
+
+/// A SARIF artifact URI for a path: relative paths stay relative (to the
+/// directory the claims were checked from), absolute ones become `file:` URIs
+fn artifact_uri(path: &str) -> String {
+    let path = path.replace('\\', "/");
+    if path.starts_with('/') {
+        format!("file://{}", percent_encode(&path))
+    } else if path.as_bytes().get(1) == Some(&b':') {
+        // Keep the drive letter's colon
+        format!("file:///{}{}", &path[..2], percent_encode(&path[2..]))
+    } else {
+        percent_encode(&path)
+    }
+}
+
+/// Percent-encode every byte of a path but unreserved characters and `/`
+fn percent_encode(path: &str) -> String {
+    let mut encoded = String::with_capacity(path.len());
+    for byte in path.bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~/".contains(&byte) {
+            encoded.push(byte as char);
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+    encoded
+}
+
+/// A fingerprint for a claim's evidence that stays the same across runs
+/// (and across changes to the message), so code scanning can match up
+/// findings
+fn sarif_fingerprint(claim_id: &str, spec: &EvidenceSpec) -> String {
+    let mut canonical = String::new();
+    crate::write_canonical(&json!(spec), &mut canonical);
+    let digest = Sha256::new()
+        .chain_update(claim_id.as_bytes())
+        .chain_update([0])
+        .chain_update(canonical.as_bytes())
+        .finalize();
+    hex::encode(&digest[..16])
+}
+
+/// Render reports as JUnit XML (for CI systems)
+fn junit(reports: &[VerificationReport]) -> String {
+    let mut output = String::new();
+
+    let total_tests: usize = reports.iter().map(|r| r.evidence_results.len()).sum();
+    let failures: usize = reports
+        .iter()
+        .flat_map(|r| &r.evidence_results)
+        .filter(|e| e.verdict == Verdict::Refuted)
+        .count();
+    let errors: usize = reports
+        .iter()
+        .flat_map(|r| &r.evidence_results)
+        .filter(|e| e.verdict == Verdict::Unverifiable || e.verdict == Verdict::Inconclusive)
+        .count();
+
+    output.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    output.push_str(&format!(
+        "<testsuites tests=\"{}\" failures=\"{}\" errors=\"{}\">\n",
+        total_tests, failures, errors
+    ));
+
+    for report in reports {
+        let suite_tests = report.evidence_results.len();
+        let suite_failures = report
+            .evidence_results
+            .iter()
+            .filter(|e| e.verdict == Verdict::Refuted)
+            .count();
+        let suite_errors = report
+            .evidence_results
+            .iter()
+            .filter(|e| e.verdict == Verdict::Unverifiable || e.verdict == Verdict::Inconclusive)
+            .count();
+
+        output.push_str(&format!(
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"{}\">\n",
+            xml_escape(&report.claim.description),
+            suite_tests,
+            suite_failures,
+            suite_errors
+        ));
+
+        for result in &report.evidence_results {
+            let test_name = evidence_name(&result.spec);
+            output.push_str(&format!(
+                "    <testcase name=\"{}\" classname=\"{}\">\n",
+                xml_escape(&test_name),
+                xml_escape(&report.claim.id)
+            ));
+
+            match result.verdict {
+                Verdict::Confirmed => {}
+                Verdict::Refuted => {
+                    output.push_str(&format!(
+                        "      <failure message=\"{}\" type=\"Refuted\"/>\n",
+                        xml_escape(&flatten_details(result))
+                    ));
+                }
+                Verdict::Inconclusive | Verdict::Unverifiable => {
+                    output.push_str(&format!(
+                        "      <error message=\"{}\" type=\"{:?}\"/>\n",
+                        xml_escape(&flatten_details(result)),
+                        result.verdict
+                    ));
+                }
+            }
+
+            output.push_str("    </testcase>\n");
+        }
+
+        output.push_str("  </testsuite>\n");
+    }
+
+    output.push_str("</testsuites>\n");
+    output
+}
+
+/// Escape XML (and HTML) special characters
+pub fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+/// The file, directory, repository or command an evidence check is about
+pub fn evidence_path(spec: &EvidenceSpec) -> Option<String> {
+    match spec {
+        EvidenceSpec::FileExists { path }
+        | EvidenceSpec::FileWithHash { path, .. }
+        | EvidenceSpec::FileContains { path, .. }
+        | EvidenceSpec::FileMatchesRegex { path, .. }
+        | EvidenceSpec::FileLineRange { path, .. }
+        | EvidenceSpec::FileUnifiedDiffApplied { path, .. }
+        | EvidenceSpec::FileJsonPath { path, .. }
+        | EvidenceSpec::FileStructuredPath { path, .. }
+        | EvidenceSpec::SymbolDefined { path, .. }
+        | EvidenceSpec::FileModifiedAfter { path, .. }
+        | EvidenceSpec::FileAbsent { path } => Some(path.clone()),
+        EvidenceSpec::DirectoryExists { path } | EvidenceSpec::DirectoryAbsent { path } => {
+            Some(path.clone())
+        }
+        EvidenceSpec::GitClean { repo_path }
+        | EvidenceSpec::GitCommitExists { repo_path, .. }
+        | EvidenceSpec::GitBranchExists { repo_path, .. }
+        | EvidenceSpec::GitBranchAbsent { repo_path, .. }
+        | EvidenceSpec::FileChangedInCommit { repo_path, .. }
+        | EvidenceSpec::CommitTouchesPaths { repo_path, .. }
+        | EvidenceSpec::CommitDiffMatches { repo_path, .. }
+        | EvidenceSpec::CommitMessageMatches { repo_path, .. }
+        | EvidenceSpec::CommitIsAncestor { repo_path, .. } => {
+            repo_path.clone().or_else(|| Some(".".to_string()))
+        }
+        EvidenceSpec::CommandSucceeds { command, .. } => Some(command.clone()),
+        EvidenceSpec::TestPasses { cwd, .. } => cwd.clone(),
+        EvidenceSpec::EnvVar { name, .. } | EvidenceSpec::EnvVarAbsent { name } => {
+            Some(format!("${}", name))
+        }
+        EvidenceSpec::AllOf { evidence }
+        | EvidenceSpec::AnyOf { evidence }
+        | EvidenceSpec::AtLeast { evidence, .. } => evidence.iter().find_map(evidence_path),
+        EvidenceSpec::Not { evidence } | EvidenceSpec::Weighted { evidence, .. } => {
+            evidence_path(evidence)
+        }
+        EvidenceSpec::Custom { name, .. } => Some(name.clone()),
+    }
+}
+
+/// Flatten a result and its nested results into a single message
+pub fn flatten_details(result: &EvidenceResult) -> String {
+    let own = result
+        .details
+        .clone()
+        .unwrap_or_else(|| format!("{:?}", result.verdict));
+
+    if result.children.is_empty() {
+        return own;
+    }
+
+    let nested: Vec<String> = result
+        .children
+        .iter()
+        .map(|child| {
+            format!(
+                "{} => {:?}: {}",
+                evidence_name(&child.spec),
+                child.verdict,
+                flatten_details(child)
+            )
+        })
+        .collect();
+
+    format!("{} [{}]", own, nested.join("; "))
+}
+
+/// Compact name of an evidence check, naming its type
+pub fn evidence_name(spec: &EvidenceSpec) -> String {
+    match spec {
+        EvidenceSpec::FileExists { path } => format!("FileExists: {}", path),
+        EvidenceSpec::FileWithHash { path, .. } => format!("FileWithHash: {}", path),
+        EvidenceSpec::FileContains { path, substring } => {
+            format!("FileContains '{}': {}", substring, path)
+        }
+        EvidenceSpec::FileMatchesRegex { path, pattern } => {
+            format!("FileMatchesRegex '{}': {}", pattern, path)
+        }
+        EvidenceSpec::FileLineRange { path, start, .. } => {
+            format!("FileLineRange {}: {}", start, path)
+        }
+        EvidenceSpec::FileUnifiedDiffApplied { path, .. } => {
+            format!("FileUnifiedDiffApplied: {}", path)
+        }
+        EvidenceSpec::FileJsonPath {
+            path, json_path, ..
+        } => {
+            format!("FileJsonPath '{}': {}", json_path, path)
+        }
+        EvidenceSpec::FileStructuredPath { path, query, .. } => {
+            format!("FileStructuredPath '{}': {}", query, path)
+        }
+        EvidenceSpec::SymbolDefined { path, symbol, .. } => {
+            format!("SymbolDefined {}: {}", symbol, path)
+        }
+        EvidenceSpec::DirectoryExists { path } => format!("DirectoryExists: {}", path),
+        EvidenceSpec::CommandSucceeds { command, .. } => format!("CommandSucceeds: {}", command),
+        EvidenceSpec::TestPasses { name, .. } => format!("TestPasses: {}", name),
+        EvidenceSpec::GitClean { repo_path } => {
+            format!("GitClean: {}", repo_path.as_deref().unwrap_or("."))
+        }
+        EvidenceSpec::GitCommitExists { commit, .. } => format!("GitCommitExists: {}", commit),
+        EvidenceSpec::GitBranchExists { branch, .. } => format!("GitBranchExists: {}", branch),
+        EvidenceSpec::FileModifiedAfter { path, after } => {
+            format!("FileModifiedAfter {}: {}", after, path)
+        }
+        EvidenceSpec::EnvVar { name, expected } => format!("EnvVar {}={}", name, expected),
+        EvidenceSpec::FileChangedInCommit { commit, path, .. } => {
+            format!("FileChangedInCommit: {} in {}", path, commit)
+        }
+        EvidenceSpec::CommitTouchesPaths { commit, paths, .. } => {
+            format!("CommitTouchesPaths: {} in {}", paths.join(", "), commit)
+        }
+        EvidenceSpec::CommitDiffMatches {
+            commit, pattern, ..
+        } => format!("CommitDiffMatches: /{}/ in {}", pattern, commit),
+        EvidenceSpec::CommitMessageMatches {
+            commit, pattern, ..
+        } => format!("CommitMessageMatches: /{}/ in {}", pattern, commit),
+        EvidenceSpec::CommitIsAncestor { commit, .. } => format!("CommitIsAncestor: {}", commit),
+        EvidenceSpec::FileAbsent { path } => format!("FileAbsent: {}", path),
+        EvidenceSpec::DirectoryAbsent { path } => format!("DirectoryAbsent: {}", path),
+        EvidenceSpec::GitBranchAbsent { branch, .. } => format!("GitBranchAbsent: {}", branch),
+        EvidenceSpec::EnvVarAbsent { name } => format!("EnvVarAbsent: {}", name),
+        EvidenceSpec::AllOf { evidence } => format!("AllOf ({} checks)", evidence.len()),
+        EvidenceSpec::AnyOf { evidence } => format!("AnyOf ({} checks)", evidence.len()),
+        EvidenceSpec::Not { evidence } => format!("Not: {}", evidence_name(evidence)),
+        EvidenceSpec::Weighted { evidence, .. } => evidence_name(evidence),
+        EvidenceSpec::AtLeast { n, evidence } => {
+            format!("AtLeast {} of {}", n, evidence.len())
+        }
+        EvidenceSpec::Custom { name, .. } => format!("Custom: {}", name),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Claim, Verifier};
+
+    fn reports() -> Vec<VerificationReport> {
+        let claims = [
+            Claim::new("Root exists").with_evidence(EvidenceSpec::DirectoryExists {
+                path: "/".to_string(),
+            }),
+            Claim::new("Nothing <here>").with_evidence(EvidenceSpec::FileExists {
+                path: "/nonexistent/file".to_string(),
+            }),
+        ];
+        Verifier::new().verify_batch(&claims).unwrap()
+    }
+
+    fn render(name: &str, reports: &[VerificationReport]) -> String {
+        let mut out = Vec::new();
+        Reporters::new()[name]
+            .write(reports, &ReportContext::new(), &mut out)
+            .unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn test_builtin_reporters_render() {
+        let reports = reports();
+
+        let human = render("human", &reports);
+        assert!(human.contains("[✓] Root exists - Confirmed"));
+        assert!(human.ends_with("Overall: Refuted\n"));
+
+        let json: Value = serde_json::from_str(&render("json", &reports)).unwrap();
+        assert_eq!(json["summary"]["refuted"], 1);
+
+        let sarif: Value = serde_json::from_str(&render("sarif", &reports)).unwrap();
+        assert_eq!(sarif["runs"][0]["results"].as_array().unwrap().len(), 1);
+
+        let junit = render("junit", &reports);
+        assert!(junit.contains("name=\"Nothing &lt;here&gt;\""));
+
+        let ndjson = render("ndjson", &reports);
+        let lines: Vec<Value> = ndjson
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[1]["claim"]["description"], "Nothing <here>");
+    }
+
+    #[test]
+    fn test_ndjson_streams_one_line_per_report() {
+        let reports = reports();
+        let mut out = Vec::new();
+        for report in &reports {
+            NdjsonReporter
+                .on_report(report, &ReportContext::new(), &mut out)
+                .unwrap();
+        }
+        let text = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines.len(), 2);
+        let first: Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(first["overall_verdict"], "Confirmed");
+    }
+
+    struct Named(&'static str, &'static str);
+
+    impl Reporter for Named {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn write(
+            &self,
+            _reports: &[VerificationReport],
+            _context: &ReportContext,
+            out: &mut dyn Write,
+        ) -> io::Result<()> {
+            out.write_all(self.1.as_bytes())
+        }
+    }
+
+    #[test]
+    fn test_register_adds_and_replaces() {
+        let mut reporters = Reporters::new();
+        let builtins = reporters.all().count();
+
+        reporters.register(Named("brief", "one"));
+        reporters.register(Named("json", "replaced"));
+        assert_eq!(reporters.all().count(), builtins + 1);
+        assert!(reporters.get("missing").is_none());
+
+        let mut out = Vec::new();
+        reporters["json"]
+            .write(&[], &ReportContext::new(), &mut out)
+            .unwrap();
+        assert_eq!(out, b"replaced");
+        // Replacing keeps the original position
+        assert_eq!(reporters.all().nth(1).unwrap().name(), "json");
+    }
+
+    #[test]
+    fn test_sarif_locations_fingerprints_and_suppressions() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = dir.path().join("my app.toml");
+        std::fs::write(
+            &config,
+            "[package]\n\n  name = \"renamed-package\"\nversion = \"1.0.0\"\n",
+        )
+        .unwrap();
+        let claims = [
+            Claim::new("Package renamed").with_evidence(EvidenceSpec::FileMatchesRegex {
+                path: config.display().to_string(),
+                pattern: r#"^name = "renamed-pakcage"$"#.to_string(),
+            }),
+            Claim::new("Token configured")
+                .with_evidence(EvidenceSpec::EnvVar {
+                    name: "DYADT_SARIF_TEST_UNSET".to_string(),
+                    expected: "1".to_string(),
+                })
+                .with_suppression(["env-var"], "Only set in release builds"),
+        ];
+        let reports = Verifier::new().verify_batch(&claims).unwrap();
+        let sarif: Value = serde_json::from_str(&render("sarif", &reports)).unwrap();
+        let run = &sarif["runs"][0];
+        let results = run["results"].as_array().unwrap();
+
+        let regex = &results[0];
+        assert_eq!(regex["ruleId"], "file-regex");
+        assert_eq!(
+            run["tool"]["driver"]["rules"][regex["ruleIndex"].as_u64().unwrap() as usize]["id"],
+            "file-regex"
+        );
+        let location = &regex["locations"][0]["physicalLocation"];
+        let uri = location["artifactLocation"]["uri"].as_str().unwrap();
+        assert!(uri.ends_with("/my%20app.toml"), "{}", uri);
+        // The near-match, since nothing matched
+        assert_eq!(location["region"]["startLine"], 3);
+        assert_eq!(location["region"]["startColumn"], 3);
+        assert_eq!(location["region"]["endColumn"], 27);
+        assert!(regex.get("suppressions").is_none());
+
+        let env = &results[1];
+        assert_eq!(
+            env["locations"][0]["logicalLocations"][0]["name"],
+            "DYADT_SARIF_TEST_UNSET"
+        );
+        assert_eq!(
+            env["suppressions"][0]["justification"],
+            "Only set in release builds"
+        );
+
+        // Fingerprints depend on the claim and evidence, not the run
+        let again: Value = serde_json::from_str(&render("sarif", &reports[..1])).unwrap();
+        assert_eq!(
+            again["runs"][0]["results"][0]["partialFingerprints"],
+            regex["partialFingerprints"]
+        );
+        assert_ne!(regex["partialFingerprints"], env["partialFingerprints"]);
+
+        let rules = run["tool"]["driver"]["rules"].as_array().unwrap();
+        assert!(rules.iter().all(|r| r["helpUri"].is_string()));
+        assert_eq!(
+            rules[0]["fullDescription"]["text"],
+            "A file should exist at the given path"
+        );
+    }
+
+    #[test]
+    fn test_escaping() {
+        assert_eq!(
+            xml_escape("<a href=\"x\">&</a>"),
+            "&lt;a href=&quot;x&quot;&gt;&amp;&lt;/a&gt;"
+        );
+        assert_eq!(csv_field("plain"), "plain");
+        assert_eq!(csv_field("a,\"b\""), "\"a,\"\"b\"\"\"");
+        assert_eq!(csv_field("=HYPERLINK(\"x\")"), "\"'=HYPERLINK(\"\"x\"\")\"");
+        assert_eq!(csv_field("@SUM(A1)"), "'@SUM(A1)");
+        assert_eq!(csv_field("a-b"), "a-b");
+        assert_eq!(markdown_escape("a|b*\nc"), "a\\|b\\*<br>c");
+        assert_eq!(tap_escape("fix #1\nnow"), "fix \\#1 now");
+        assert_eq!(artifact_uri("src\\lib.rs"), "src/lib.rs");
+        assert_eq!(artifact_uri("/tmp/x"), "file:///tmp/x");
+        assert_eq!(artifact_uri("docs/a b#1.md"), "docs/a%20b%231.md");
+        assert_eq!(
+            artifact_uri("C:\\My Files\\x.rs"),
+            "file:///C:/My%20Files/x.rs"
+        );
+    }
+
+    #[test]
+    fn test_sarif_rules_cover_every_evidence_type() {
+        for checker in BuiltinChecker::all() {
+            assert!(
+                SARIF_RULES
+                    .iter()
+                    .any(|(_, name, _)| *name == checker.name()),
+                "no SARIF rule for {}",
+                checker.name()
+            );
+        }
+        assert!(SARIF_RULES.iter().any(|(id, _, _)| *id == "custom"));
+
+        let ids: std::collections::HashSet<_> = SARIF_RULES.iter().map(|(id, _, _)| id).collect();
+        assert_eq!(ids.len(), SARIF_RULES.len());
+    }
+}
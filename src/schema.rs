@@ -0,0 +1,789 @@
+// SPDX-License-Identifier: MPL-2.0
+//! JSON Schema for claim files, and strict validation against it
+//!
+//! The schema is generated from [`Claim`] and [`EvidenceSpec`](crate::EvidenceSpec),
+//! so it cannot drift from what the verifier accepts. It is stricter than
+//! deserialization in one way: unknown fields are errors, so a misspelt
+//! optional field (`timout_secs`) is reported instead of silently ignored.
+//!
+//! Validation reports every problem at once, each with a JSON pointer, the
+//! line and column when the source text is available, and a suggestion for
+//! misspelt field names, evidence types and enum values:
+//!
+//! ```rust
+//! use did_you_actually_do_that::schema::validate_str;
+//!
+//! let text = r#"{
+//!     "description": "Wrote the config",
+//!     "evidence": [{ "type": "FileContains", "spec": { "path": "a.toml", "subtring": "x" } }]
+//! }"#;
+//! let diagnostics = validate_str(text);
+//! // The misspelt field, and the required `substring` it leaves missing
+//! assert_eq!(diagnostics.len(), 2);
+//! let typo = &diagnostics[1];
+//! assert_eq!((typo.line, typo.column), (Some(3), Some(72)));
+//! assert_eq!(typo.suggestion.as_deref(), Some("substring"));
+//! ```
+
+use crate::manifest::{self, ManifestLoader};
+use crate::Claim;
+use schemars::generate::SchemaSettings;
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::Path;
+use std::sync::OnceLock;
+
+/// One problem found in a claims file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    /// JSON pointer to the offending value, e.g. `/evidence/0/spec`
+    pub pointer: String,
+    /// 1-based position in the source text, when validating text
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub message: String,
+    /// The closest valid name, for misspellings
+    pub suggestion: Option<String>,
+}
+
+impl Diagnostic {
+    fn new(pointer: &str, message: impl Into<String>) -> Self {
+        Self {
+            pointer: pointer.to_string(),
+            line: None,
+            column: None,
+            message: message.into(),
+            suggestion: None,
+        }
+    }
+
+    fn suggest(mut self, suggestion: Option<&str>) -> Self {
+        self.suggestion = suggestion.map(str::to_string);
+        self
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let (Some(line), Some(column)) = (self.line, self.column) {
+            write!(f, "{}:{}: ", line, column)?;
+        }
+        if !self.pointer.is_empty() {
+            write!(f, "{}: ", self.pointer)?;
+        }
+        f.write_str(&self.message)?;
+        if let Some(suggestion) = &self.suggestion {
+            write!(f, "; did you mean `{}`?", suggestion)?;
+        }
+        Ok(())
+    }
+}
+
+/// Every definition the claim schemas refer to, `Claim` included
+pub fn definitions() -> &'static Map<String, Value> {
+    static DEFINITIONS: OnceLock<Map<String, Value>> = OnceLock::new();
+    DEFINITIONS.get_or_init(|| {
+        let generator = SchemaSettings::draft2020_12().into_generator();
+        let mut root = generator.into_root_schema_for::<Claim>().to_value();
+        let mut definitions = match root.as_object_mut().and_then(|r| r.remove("$defs")) {
+            Some(Value::Object(definitions)) => definitions,
+            _ => Map::new(),
+        };
+        if let Some(root) = root.as_object_mut() {
+            root.remove("$schema");
+            root.remove("title");
+            // Generated per claim, so the schema would change on every run
+            for field in ["id", "timestamp"] {
+                if let Some(Value::Object(property)) = root
+                    .get_mut("properties")
+                    .and_then(|properties| properties.get_mut(field))
+                {
+                    property.remove("default");
+                }
+            }
+        }
+        definitions.insert("Claim".to_string(), root);
+        definitions.values_mut().for_each(deny_unknown_fields);
+        definitions
+    })
+}
+
+/// Mark every object schema with named properties as closed
+fn deny_unknown_fields(schema: &mut Value) {
+    match schema {
+        Value::Object(object) => {
+            if object.contains_key("properties") && !object.contains_key("additionalProperties") {
+                object.insert("additionalProperties".to_string(), Value::Bool(false));
+            }
+            object.values_mut().for_each(deny_unknown_fields);
+        }
+        Value::Array(items) => items.iter_mut().for_each(deny_unknown_fields),
+        _ => {}
+    }
+}
+
+/// Schema for one claim, with the definitions it uses under `$defs`
+pub fn claim_schema() -> Value {
+    let mut schema = definitions()["Claim"].clone();
+    schema["$defs"] = Value::Object(definitions().clone());
+    schema
+}
+
+/// Schema for a claims file: one claim or an array of claims
+pub fn claims_file_schema() -> Value {
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "Claims file",
+        "description": "A single claim or an array of claims. Manifests (with vars, templates or includes) are expanded with `dyadt expand` before they match this schema.",
+        "anyOf": [
+            { "$ref": "#/$defs/Claim" },
+            { "type": "array", "items": { "$ref": "#/$defs/Claim" } }
+        ],
+        "$defs": definitions(),
+    })
+}
+
+/// The generated `spec` schema of one evidence type, with `$defs` attached
+/// when it refers to other definitions
+pub(crate) fn evidence_spec_schema(type_name: &str) -> Option<Value> {
+    let variant = definitions()["EvidenceSpec"]["oneOf"]
+        .as_array()?
+        .iter()
+        .find(|variant| variant["properties"]["type"]["const"] == type_name)?;
+    let mut spec = variant["properties"]["spec"].clone();
+    if spec.to_string().contains("\"$ref\"") {
+        spec["$defs"] = Value::Object(definitions().clone());
+    }
+    Some(spec)
+}
+
+/// Validate the text of a claims file (one claim or an array of claims)
+pub fn validate_str(text: &str) -> Vec<Diagnostic> {
+    let value: Value = match serde_json::from_str(text) {
+        Ok(value) => value,
+        Err(e) => {
+            let mut diagnostic = Diagnostic::new("", format!("invalid JSON: {}", e));
+            diagnostic.line = Some(e.line());
+            diagnostic.column = Some(e.column());
+            return vec![diagnostic];
+        }
+    };
+
+    let mut diagnostics = validate_value(&value);
+    if !diagnostics.is_empty() {
+        let spans = locate(text);
+        for diagnostic in &mut diagnostics {
+            if let Some(&offset) = spans.get(&diagnostic.pointer) {
+                let (line, column) = line_column(text, offset);
+                diagnostic.line = Some(line);
+                diagnostic.column = Some(column);
+            }
+        }
+    }
+    diagnostics
+}
+
+/// Validate a parsed claims file (one claim or an array of claims)
+pub fn validate_value(value: &Value) -> Vec<Diagnostic> {
+    let claim = &definitions()["Claim"];
+    let mut diagnostics = Vec::new();
+    match value {
+        Value::Array(claims) => {
+            for (n, item) in claims.iter().enumerate() {
+                let pointer = format!("/{}", n);
+                check(claim, item, &pointer, None, &mut diagnostics);
+                check_weighted_placement(item, &pointer, &mut diagnostics);
+            }
+        }
+        _ => {
+            check(claim, value, "", None, &mut diagnostics);
+            check_weighted_placement(value, "", &mut diagnostics);
+        }
+    }
+    diagnostics
+}
+
+/// `Weighted` only counts in a claim's top-level evidence, so report it
+/// anywhere deeper
+fn check_weighted_placement(claim: &Value, pointer: &str, out: &mut Vec<Diagnostic>) {
+    fn walk(evidence: &Value, pointer: &str, top: bool, out: &mut Vec<Diagnostic>) {
+        if !top && evidence["type"] == "Weighted" {
+            out.push(Diagnostic::new(
+                pointer,
+                "Weighted evidence is only allowed at the top level of a claim",
+            ));
+        }
+        match &evidence["spec"]["evidence"] {
+            Value::Array(items) => {
+                for (n, item) in items.iter().enumerate() {
+                    walk(
+                        item,
+                        &format!("{}/spec/evidence/{}", pointer, n),
+                        false,
+                        out,
+                    );
+                }
+            }
+            nested @ Value::Object(_) => {
+                walk(nested, &format!("{}/spec/evidence", pointer), false, out)
+            }
+            _ => {}
+        }
+    }
+
+    if let Some(evidence) = claim.get("evidence").and_then(Value::as_array) {
+        for (n, item) in evidence.iter().enumerate() {
+            walk(item, &format!("{}/evidence/{}", pointer, n), true, out);
+        }
+    }
+}
+
+/// Validate a claims file. Manifests are expanded first, so their
+/// diagnostics point into the `dyadt expand` output and have no position.
+pub fn validate_file(path: impl AsRef<Path>, loader: &ManifestLoader) -> Vec<Diagnostic> {
+    let path = path.as_ref();
+    let text = match std::fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(e) => return vec![Diagnostic::new("", format!("cannot read file: {}", e))],
+    };
+    let is_manifest = serde_json::from_str::<Value>(&text)
+        .map(|document| manifest::is_manifest(&document))
+        .unwrap_or(false);
+    if !is_manifest {
+        return validate_str(&text);
+    }
+    match loader.expand_file(path) {
+        Ok(claims) => validate_value(&Value::Array(claims)),
+        Err(e) => vec![Diagnostic::new("", e)],
+    }
+}
+
+/// Check `value` against `schema`. `label` names the evidence type whose
+/// wrapper or `spec` object is being checked, for friendlier messages.
+fn check(
+    schema: &Value,
+    value: &Value,
+    pointer: &str,
+    label: Option<&str>,
+    out: &mut Vec<Diagnostic>,
+) {
+    let Some(schema) = schema.as_object() else {
+        return; // `true` accepts anything
+    };
+    let within = |message: String| match label {
+        Some(label) => format!("{} in {}", message, label),
+        None => message,
+    };
+
+    if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+        let name = reference.trim_start_matches("#/$defs/");
+        if let Some(target) = definitions().get(name) {
+            check(target, value, pointer, label, out);
+        }
+        return;
+    }
+
+    if let Some(alternatives) = schema
+        .get("oneOf")
+        .or_else(|| schema.get("anyOf"))
+        .and_then(Value::as_array)
+    {
+        check_alternatives(alternatives, value, pointer, out);
+        return;
+    }
+
+    if let Some(expected) = schema.get("const") {
+        if value != expected {
+            out.push(Diagnostic::new(
+                pointer,
+                format!("expected {}, found {}", expected, value),
+            ));
+        }
+        return;
+    }
+
+    if let Some(Value::Array(allowed)) = schema.get("enum") {
+        if !allowed.contains(value) {
+            let names = allowed.iter().filter_map(Value::as_str);
+            out.push(unknown_value(pointer, value, names));
+        }
+        return;
+    }
+
+    if let Some(types) = schema.get("type") {
+        let allowed: Vec<&str> = match types {
+            Value::Array(types) => types.iter().filter_map(Value::as_str).collect(),
+            other => other.as_str().into_iter().collect(),
+        };
+        if !allowed.iter().any(|&t| has_type(value, t)) {
+            out.push(Diagnostic::new(
+                pointer,
+                format!(
+                    "expected {}, found {}",
+                    allowed.join(" or "),
+                    type_of(value)
+                ),
+            ));
+            return;
+        }
+    }
+
+    if let (Some(minimum), Some(number)) = (
+        schema.get("minimum").and_then(Value::as_f64),
+        value.as_f64(),
+    ) {
+        if number < minimum {
+            out.push(Diagnostic::new(
+                pointer,
+                format!("{} is less than the minimum of {}", number, minimum),
+            ));
+        }
+    }
+
+    if let (Some("date-time"), Some(text)) =
+        (schema.get("format").and_then(Value::as_str), value.as_str())
+    {
+        if chrono::DateTime::parse_from_rfc3339(text).is_err() {
+            out.push(Diagnostic::new(
+                pointer,
+                format!("`{}` is not an RFC 3339 timestamp", text),
+            ));
+        }
+    }
+
+    if let Some(object) = value.as_object() {
+        let properties = schema.get("properties").and_then(Value::as_object);
+        for field in schema
+            .get("required")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+        {
+            if !object.contains_key(field) {
+                out.push(Diagnostic::new(
+                    pointer,
+                    within(format!("missing field `{}`", field)),
+                ));
+            }
+        }
+        for (key, item) in object {
+            let child = format!("{}/{}", pointer, escape(key));
+            match (
+                properties.and_then(|p| p.get(key)),
+                schema.get("additionalProperties"),
+            ) {
+                (Some(property), _) => {
+                    let label = if key == "spec" { label } else { None };
+                    check(property, item, &child, label, out);
+                }
+                (None, Some(Value::Bool(false))) => {
+                    let names = properties
+                        .into_iter()
+                        .flat_map(|p| p.keys())
+                        .map(String::as_str);
+                    out.push(
+                        Diagnostic::new(&child, within(format!("unknown field `{}`", key)))
+                            .suggest(closest(key, names)),
+                    );
+                }
+                (None, Some(additional)) => check(additional, item, &child, None, out),
+                (None, None) => {}
+            }
+        }
+    }
+
+    if let (Some(items), Some(values)) = (schema.get("items"), value.as_array()) {
+        for (n, item) in values.iter().enumerate() {
+            check(items, item, &format!("{}/{}", pointer, n), None, out);
+        }
+    }
+}
+
+fn check_alternatives(
+    alternatives: &[Value],
+    value: &Value,
+    pointer: &str,
+    out: &mut Vec<Diagnostic>,
+) {
+    // String enums with per-value docs are generated as `const` alternatives
+    let constants: Option<Vec<&Value>> = alternatives.iter().map(|a| a.get("const")).collect();
+    if let Some(constants) = constants {
+        if !constants.contains(&value) {
+            out.push(unknown_value(
+                pointer,
+                value,
+                constants.into_iter().filter_map(Value::as_str),
+            ));
+        }
+        return;
+    }
+
+    // Evidence: pick the alternative by its `type` tag
+    let tags: Option<Vec<&str>> = alternatives
+        .iter()
+        .map(|a| a["properties"]["type"]["const"].as_str())
+        .collect();
+    if let Some(tags) = tags {
+        let Some(object) = value.as_object() else {
+            out.push(Diagnostic::new(
+                pointer,
+                format!("expected object, found {}", type_of(value)),
+            ));
+            return;
+        };
+        match object.get("type") {
+            None => out.push(Diagnostic::new(pointer, "missing field `type`")),
+            Some(Value::String(tag)) => match tags.iter().position(|t| t == tag) {
+                Some(n) => check(&alternatives[n], value, pointer, Some(tag), out),
+                None => out.push(
+                    Diagnostic::new(
+                        &format!("{}/type", pointer),
+                        format!("unknown evidence type `{}`", tag),
+                    )
+                    .suggest(closest(tag, tags.iter().copied())),
+                ),
+            },
+            Some(other) => out.push(Diagnostic::new(
+                &format!("{}/type", pointer),
+                format!("expected string, found {}", type_of(other)),
+            )),
+        }
+        return;
+    }
+
+    // Otherwise report the alternative that came closest, preferring
+    // anything over a bare `null`
+    let mut best: Option<Vec<Diagnostic>> = None;
+    for alternative in alternatives {
+        let mut attempt = Vec::new();
+        check(alternative, value, pointer, None, &mut attempt);
+        if attempt.is_empty() {
+            return;
+        }
+        let is_null = alternative["type"] == "null";
+        if !is_null && best.as_ref().is_none_or(|b| attempt.len() < b.len()) {
+            best = Some(attempt);
+        }
+    }
+    out.extend(best.unwrap_or_default());
+}
+
+fn unknown_value<'a>(
+    pointer: &str,
+    value: &Value,
+    names: impl Iterator<Item = &'a str> + Clone,
+) -> Diagnostic {
+    let listed: Vec<String> = names.clone().map(|n| format!("`{}`", n)).collect();
+    let suggestion = value.as_str().and_then(|v| closest(v, names));
+    Diagnostic::new(
+        pointer,
+        format!("{} is not one of {}", value, listed.join(", ")),
+    )
+    .suggest(suggestion)
+}
+
+/// The candidate closest to `word`, if it is close enough to be a typo
+fn closest<'a>(word: &str, candidates: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+    let word = word.to_lowercase();
+    candidates
+        .into_iter()
+        .map(|candidate| {
+            let distance = strsim::damerau_levenshtein(&word, &candidate.to_lowercase());
+            (distance, candidate)
+        })
+        .filter(|(distance, candidate)| *distance <= (candidate.len().max(word.len()) / 3).max(1))
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+fn has_type(value: &Value, name: &str) -> bool {
+    match name {
+        "null" => value.is_null(),
+        "boolean" => value.is_boolean(),
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => value.is_i64() || value.is_u64(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        _ => true,
+    }
+}
+
+fn type_of(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Escape a key for use in a JSON pointer (RFC 6901)
+fn escape(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
+
+/// Byte offsets of every value in `text` by JSON pointer; object members
+/// point at their key. `text` must be valid JSON.
+fn locate(text: &str) -> HashMap<String, usize> {
+    struct Scanner<'a> {
+        text: &'a str,
+        pos: usize,
+        spans: HashMap<String, usize>,
+    }
+
+    impl Scanner<'_> {
+        fn skip_whitespace(&mut self) {
+            let rest = &self.text[self.pos..];
+            self.pos += rest.len() - rest.trim_start().len();
+        }
+
+        fn peek(&self) -> Option<u8> {
+            self.text.as_bytes().get(self.pos).copied()
+        }
+
+        /// Skip a string token, returning its decoded contents
+        fn string(&mut self) -> String {
+            let start = self.pos;
+            let bytes = self.text.as_bytes();
+            self.pos += 1;
+            while let Some(&byte) = bytes.get(self.pos) {
+                self.pos += 1;
+                match byte {
+                    b'\\' => self.pos += 1,
+                    b'"' => break,
+                    _ => {}
+                }
+            }
+            serde_json::from_str(&self.text[start..self.pos]).unwrap_or_default()
+        }
+
+        fn value(&mut self, pointer: String) {
+            self.skip_whitespace();
+            self.spans.entry(pointer.clone()).or_insert(self.pos);
+            match self.peek() {
+                Some(b'{') => {
+                    self.pos += 1;
+                    loop {
+                        self.skip_whitespace();
+                        match self.peek() {
+                            Some(b'"') => {
+                                let key_start = self.pos;
+                                let key = self.string();
+                                let child = format!("{}/{}", pointer, escape(&key));
+                                self.spans.insert(child.clone(), key_start);
+                                self.skip_whitespace();
+                                self.pos += 1; // ':'
+                                self.value(child);
+                            }
+                            Some(b',') => self.pos += 1,
+                            _ => {
+                                self.pos += 1; // '}'
+                                break;
+                            }
+                        }
+                    }
+                }
+                Some(b'[') => {
+                    self.pos += 1;
+                    let mut index = 0;
+                    loop {
+                        self.skip_whitespace();
+                        match self.peek() {
+                            Some(b']') | None => {
+                                self.pos += 1;
+                                break;
+                            }
+                            Some(b',') => self.pos += 1,
+                            Some(_) => {
+                                self.value(format!("{}/{}", pointer, index));
+                                index += 1;
+                            }
+                        }
+                    }
+                }
+                Some(b'"') => {
+                    self.string();
+                }
+                _ => {
+                    let rest = &self.text[self.pos..];
+                    self.pos += rest
+                        .find(|c: char| c == ',' || c == ']' || c == '}' || c.is_whitespace())
+                        .unwrap_or(rest.len());
+                }
+            }
+        }
+    }
+
+    let mut scanner = Scanner {
+        text,
+        pos: 0,
+        spans: HashMap::new(),
+    };
+    scanner.value(String::new());
+    scanner.spans
+}
+
+/// 1-based line and column (in characters) of a byte offset
+fn line_column(text: &str, offset: usize) -> (usize, usize) {
+    let before = &text[..offset.min(text.len())];
+    let line = before.matches('\n').count() + 1;
+    let column = before
+        .rsplit('\n')
+        .next()
+        .map_or(0, |current| current.chars().count())
+        + 1;
+    (line, column)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn messages(text: &str) -> Vec<String> {
+        validate_str(text).iter().map(ToString::to_string).collect()
+    }
+
+    #[test]
+    fn test_valid_files_have_no_diagnostics() {
+        let claim = r#"{
+            "id": "c1", "description": "d", "source": null, "timestamp": "2024-01-01T00:00:00Z",
+            "evidence": [
+                { "type": "CommandSucceeds", "spec": { "command": "true", "args": [], "env": { "A": "1" } } },
+                { "type": "Not", "spec": { "evidence": { "type": "FileExists", "spec": { "path": "x" } } } },
+                { "type": "FileJsonPath", "spec": { "path": "p.json", "json_path": "$.a", "expected": [1], "op": "contains" } },
+                { "type": "SymbolDefined", "spec": { "path": "a.rs", "symbol": "f", "kind": "fn" } },
+                { "type": "Custom", "spec": { "name": "mine", "params": { "anything": true } } }
+            ]
+        }"#;
+        assert_eq!(messages(claim), Vec::<String>::new());
+        assert!(messages(&format!("[{}, {}]", claim, claim)).is_empty());
+    }
+
+    #[test]
+    fn test_serialized_claims_validate() {
+        use crate::EvidenceSpec;
+        let claim = Claim::new("round trip")
+            .with_evidence(EvidenceSpec::FileLineRange {
+                path: "a.rs".into(),
+                start: 1,
+                end: None,
+                expected: "x".into(),
+            })
+            .with_evidence(EvidenceSpec::Weighted {
+                evidence: Box::new(EvidenceSpec::TestPasses {
+                    name: "t".into(),
+                    runner: Default::default(),
+                    args: vec![],
+                    cwd: None,
+                    timeout_secs: Some(5),
+                }),
+                weight: 2.5,
+                required: true,
+            })
+            .depends_on("other");
+        let value = serde_json::to_value(&claim).unwrap();
+        assert_eq!(validate_value(&value), Vec::new());
+    }
+
+    #[test]
+    fn test_nested_weighted_is_reported() {
+        let weighted = json!({
+            "type": "Weighted",
+            "spec": { "evidence": { "type": "FileExists", "spec": { "path": "x" } }, "weight": 2.0 }
+        });
+        let claim = json!({
+            "description": "d",
+            "evidence": [weighted, { "type": "AllOf", "spec": { "evidence": [weighted] } }]
+        });
+        let diagnostics = validate_value(&claim);
+        assert_eq!(diagnostics.len(), 1, "{:?}", diagnostics);
+        assert_eq!(diagnostics[0].pointer, "/evidence/1/spec/evidence/0");
+    }
+
+    #[test]
+    fn test_op_aliases_are_valid() {
+        for (alias, _) in crate::query::JSON_OP_ALIASES {
+            let text = json!({
+                "description": "d",
+                "evidence": [{
+                    "type": "FileJsonPath",
+                    "spec": { "path": "x", "json_path": "$.n", "op": alias, "expected": 1 }
+                }]
+            })
+            .to_string();
+            assert_eq!(validate_str(&text), Vec::new(), "{}", alias);
+        }
+    }
+
+    #[test]
+    fn test_typos_get_positions_and_suggestions() {
+        let text = r#"[
+  { "description": "d", "evidence": [
+    { "type": "FileExist", "spec": { "path": "x" } },
+    { "type": "FileContains", "spec": { "path": "x", "subtring": "y" } },
+    { "type": "FileJsonPath", "spec": { "path": "x", "json_path": "$", "op": "equal" } }
+  ], "sorce": "me" }
+]"#;
+        assert_eq!(
+            messages(text),
+            vec![
+                "3:7: /0/evidence/0/type: unknown evidence type `FileExist`; did you mean `FileExists`?",
+                "4:31: /0/evidence/1/spec: missing field `substring` in FileContains",
+                "4:54: /0/evidence/1/spec/subtring: unknown field `subtring` in FileContains; did you mean `substring`?",
+                "5:72: /0/evidence/2/spec/op: \"equal\" is not one of `equals`, `exists`, `contains`, `gt`, `lt`, `matches`, `type_is`, `>`, `<`, `regex`, `type-is`; did you mean `equals`?",
+                "6:6: /0/sorce: unknown field `sorce`; did you mean `source`?",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_types_and_json_errors() {
+        let diagnostics = messages(
+            r#"{ "description": 3, "evidence": [{ "type": "AtLeast", "spec": { "n": -1, "evidence": [] } }] }"#,
+        );
+        assert_eq!(diagnostics.len(), 2, "{:?}", diagnostics);
+        assert!(diagnostics[0].ends_with("/description: expected string, found number"));
+        assert!(diagnostics[1].contains("/evidence/0/spec/n: -1 is less than the minimum of 0"));
+
+        let diagnostics = validate_str("{\n  \"description\": }");
+        assert_eq!(diagnostics[0].line, Some(2));
+        assert!(diagnostics[0].message.starts_with("invalid JSON"));
+    }
+
+    #[test]
+    fn test_schema_covers_every_evidence_type() {
+        for checker in crate::checker::BuiltinChecker::all() {
+            use crate::checker::EvidenceChecker;
+            assert!(
+                evidence_spec_schema(checker.name()).is_some(),
+                "{}",
+                checker.name()
+            );
+        }
+        assert!(evidence_spec_schema("Custom").is_some());
+        assert_eq!(
+            evidence_spec_schema("AllOf").unwrap()["properties"]["evidence"]["items"]["$ref"],
+            "#/$defs/EvidenceSpec"
+        );
+        assert!(claims_file_schema()["$defs"]["Claim"]["properties"]["id"]
+            .get("default")
+            .is_none());
+
+        // And the other way: every evidence type but `Custom` has a builtin
+        let variants = definitions()["EvidenceSpec"]["oneOf"].as_array().unwrap();
+        assert!(variants.len() > 1);
+        for variant in variants {
+            let name = variant["properties"]["type"]["const"].as_str().unwrap();
+            assert!(
+                name == "Custom" || crate::checker::BuiltinChecker::get(name).is_some(),
+                "no BuiltinChecker for {}",
+                name
+            );
+        }
+    }
+}
@@ -0,0 +1,521 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Filesystem snapshots and the evidence derived from them
+//!
+//! Instead of trusting an agent to describe what it changed, take a
+//! [`Snapshot`] of the working tree before its turn and diff against the
+//! tree afterwards. The resulting [`SnapshotDiff`] lists what was really
+//! created, modified and deleted, can be turned into ready-made claims with
+//! hash evidence, and can be cross-checked against the claims extracted from
+//! the agent's own account.
+//!
+//! ```rust
+//! use did_you_actually_do_that::snapshot::Snapshot;
+//! use did_you_actually_do_that::{Verdict, Verifier};
+//!
+//! let dir = tempfile::tempdir().unwrap();
+//! let before = Snapshot::capture(dir.path()).unwrap();
+//!
+//! std::fs::write(dir.path().join("notes.txt"), "hello").unwrap();
+//!
+//! let diff = before.diff_now().unwrap();
+//! assert_eq!(diff.created.len(), 1);
+//!
+//! let verifier = Verifier::new();
+//! for claim in diff.to_claims() {
+//!     assert_eq!(verifier.verify(&claim).overall_verdict, Verdict::Confirmed);
+//! }
+//! ```
+//!
+//! Only regular files are recorded. Symbolic links are not followed, and
+//! directories named in the ignore list (by default `.git`) are skipped.
+//! A snapshot saved inside the directory it captures remembers its own
+//! path, and diffs leave that file out.
+
+use crate::{Claim, EvidenceSpec};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+/// Source recorded on claims derived from a snapshot diff
+pub const SNAPSHOT_SOURCE: &str = "dyadt-snapshot";
+
+/// Directory names skipped unless the caller says otherwise
+const DEFAULT_IGNORE: &[&str] = &[".git"];
+
+/// Recorded state of one file
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileEntry {
+    pub size: u64,
+    #[serde(default)]
+    pub modified: Option<DateTime<Utc>>,
+    pub sha256: String,
+}
+
+/// Manifest of every regular file under a directory at one point in time
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    /// Absolute path of the captured directory
+    pub root: PathBuf,
+    pub taken_at: DateTime<Utc>,
+    /// Directory names that were skipped
+    #[serde(default)]
+    pub ignore: Vec<String>,
+    /// Files keyed by `/`-separated path relative to `root`
+    pub files: BTreeMap<String, FileEntry>,
+    /// Where [`Snapshot::save`] wrote this snapshot, relative to `root`,
+    /// if that is inside `root`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+}
+
+impl Snapshot {
+    /// Capture `root`, skipping `.git`
+    pub fn capture(root: impl AsRef<Path>) -> io::Result<Self> {
+        let ignore: Vec<String> = DEFAULT_IGNORE.iter().map(|s| s.to_string()).collect();
+        Self::capture_ignoring(root, &ignore)
+    }
+
+    /// Capture `root`, skipping any directory whose name is in `ignore`
+    pub fn capture_ignoring(root: impl AsRef<Path>, ignore: &[String]) -> io::Result<Self> {
+        let root = fs::canonicalize(root)?;
+        let mut files = BTreeMap::new();
+        walk(&root, &root, ignore, &mut files)?;
+
+        Ok(Self {
+            root,
+            taken_at: Utc::now(),
+            ignore: ignore.to_vec(),
+            files,
+            output: None,
+        })
+    }
+
+    /// Load a snapshot previously written with [`Snapshot::save`]
+    pub fn load(path: impl AsRef<Path>) -> io::Result<Self> {
+        let contents = fs::read_to_string(path)?;
+        serde_json::from_str(&contents).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Write the snapshot as JSON. If `path` is inside the captured
+    /// directory, the file is recorded as the snapshot's own output so
+    /// later diffs do not report it.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut saved = self.clone();
+        saved.output = saved.relative(path.as_ref());
+        if let Some(output) = &saved.output {
+            saved.files.remove(output);
+        }
+        let json = serde_json::to_string_pretty(&saved)?;
+        fs::write(path, json)
+    }
+
+    /// `/`-separated path of `path` relative to `root`, if it is inside it
+    fn relative(&self, path: &Path) -> Option<String> {
+        let parent = match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        let full = fs::canonicalize(parent).ok()?.join(path.file_name()?);
+        let rel = full.strip_prefix(&self.root).ok()?;
+        Some(
+            rel.components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/"),
+        )
+    }
+
+    /// Compare against a later snapshot of the same directory
+    pub fn diff(&self, after: &Snapshot) -> SnapshotDiff {
+        let mut diff = SnapshotDiff::default();
+        let own = |rel: &String| self.output.as_ref() == Some(rel);
+
+        for (rel, entry) in after.files.iter().filter(|(rel, _)| !own(rel)) {
+            match self.files.get(rel) {
+                None => diff.created.push(self.change(rel, None, Some(entry))),
+                Some(before) if before.sha256 != entry.sha256 => {
+                    diff.modified
+                        .push(self.change(rel, Some(before), Some(entry)))
+                }
+                Some(_) => {}
+            }
+        }
+        for (rel, entry) in self.files.iter().filter(|(rel, _)| !own(rel)) {
+            if !after.files.contains_key(rel) {
+                diff.deleted.push(self.change(rel, Some(entry), None));
+            }
+        }
+
+        diff
+    }
+
+    /// Capture the directory again and compare against it
+    pub fn diff_now(&self) -> io::Result<SnapshotDiff> {
+        let after = Self::capture_ignoring(&self.root, &self.ignore)?;
+        Ok(self.diff(&after))
+    }
+
+    fn change(
+        &self,
+        rel: &str,
+        before: Option<&FileEntry>,
+        after: Option<&FileEntry>,
+    ) -> FileChange {
+        FileChange {
+            path: self.root.join(rel).to_string_lossy().into_owned(),
+            before_sha256: before.map(|e| e.sha256.clone()),
+            after_sha256: after.map(|e| e.sha256.clone()),
+        }
+    }
+}
+
+fn walk(
+    root: &Path,
+    dir: &Path,
+    ignore: &[String],
+    files: &mut BTreeMap<String, FileEntry>,
+) -> io::Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let path = entry.path();
+
+        if file_type.is_dir() {
+            let name = entry.file_name();
+            if !ignore.iter().any(|i| name.to_str() == Some(i.as_str())) {
+                walk(root, &path, ignore, files)?;
+            }
+        } else if file_type.is_file() {
+            let contents = fs::read(&path)?;
+            let metadata = entry.metadata()?;
+            let rel = path
+                .strip_prefix(root)
+                .unwrap_or(&path)
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+
+            files.insert(
+                rel,
+                FileEntry {
+                    size: metadata.len(),
+                    modified: metadata.modified().ok().map(DateTime::<Utc>::from),
+                    sha256: hex::encode(Sha256::digest(&contents)),
+                },
+            );
+        }
+    }
+    Ok(())
+}
+
+/// A file that differs between two snapshots
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileChange {
+    /// Absolute path of the file
+    pub path: String,
+    pub before_sha256: Option<String>,
+    pub after_sha256: Option<String>,
+}
+
+/// What actually changed between two snapshots
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotDiff {
+    pub created: Vec<FileChange>,
+    pub modified: Vec<FileChange>,
+    pub deleted: Vec<FileChange>,
+}
+
+impl SnapshotDiff {
+    pub fn is_empty(&self) -> bool {
+        self.created.is_empty() && self.modified.is_empty() && self.deleted.is_empty()
+    }
+
+    /// Every changed path
+    pub fn paths(&self) -> impl Iterator<Item = &str> {
+        self.created
+            .iter()
+            .chain(&self.modified)
+            .chain(&self.deleted)
+            .map(|change| change.path.as_str())
+    }
+
+    /// One claim per change, with evidence that pins down the new state
+    pub fn to_claims(&self) -> Vec<Claim> {
+        let with_hash = |verb: &str, change: &FileChange| {
+            Claim::new(format!("{} file: {}", verb, change.path))
+                .with_evidence(EvidenceSpec::FileWithHash {
+                    path: change.path.clone(),
+                    sha256: change.after_sha256.clone().unwrap_or_default(),
+                })
+                .with_source(SNAPSHOT_SOURCE)
+        };
+
+        let created = self.created.iter().map(|c| with_hash("Created", c));
+        let modified = self.modified.iter().map(|c| with_hash("Modified", c));
+        let deleted = self.deleted.iter().map(|change| {
+            Claim::new(format!("Deleted file: {}", change.path))
+                .with_evidence(EvidenceSpec::FileAbsent {
+                    path: change.path.clone(),
+                })
+                .with_source(SNAPSHOT_SOURCE)
+        });
+
+        created.chain(modified).chain(deleted).collect()
+    }
+
+    /// Compare claimed file changes with the files that actually changed
+    ///
+    /// Relative claim paths are resolved against `base`. Only paths under
+    /// `root` (the snapshot root) are judged, since changes elsewhere were
+    /// never recorded.
+    pub fn cross_check(&self, claims: &[Claim], root: &Path, base: &Path) -> CrossCheck {
+        let changed: BTreeSet<PathBuf> = self.paths().map(|p| normalize(Path::new(p))).collect();
+        let mut mentioned = BTreeSet::new();
+        let mut check = CrossCheck::default();
+
+        for claim in claims {
+            let mut paths = Vec::new();
+            for evidence in &claim.evidence {
+                collect_file_paths(evidence, &mut paths);
+            }
+
+            for path in paths {
+                let resolved = normalize(&base.join(path));
+                if !resolved.starts_with(root) || !mentioned.insert(resolved.clone()) {
+                    continue;
+                }
+                let claimed = ClaimedPath {
+                    claim: claim.description.clone(),
+                    path: resolved.to_string_lossy().into_owned(),
+                };
+                if changed.contains(&resolved) {
+                    check.confirmed.push(claimed);
+                } else {
+                    check.claimed_but_unchanged.push(claimed);
+                }
+            }
+        }
+
+        check.unclaimed_changes = changed
+            .iter()
+            .filter(|path| !mentioned.contains(*path))
+            .map(|path| path.to_string_lossy().into_owned())
+            .collect();
+        check
+    }
+}
+
+/// A file path named by a claim
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClaimedPath {
+    pub claim: String,
+    pub path: String,
+}
+
+/// Result of comparing claims against a [`SnapshotDiff`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CrossCheck {
+    /// Claimed files that really changed
+    pub confirmed: Vec<ClaimedPath>,
+    /// Claimed files that did not change at all
+    pub claimed_but_unchanged: Vec<ClaimedPath>,
+    /// Changed files no claim mentions
+    pub unclaimed_changes: Vec<String>,
+}
+
+impl CrossCheck {
+    /// Whether every claimed file change actually happened
+    pub fn is_consistent(&self) -> bool {
+        self.claimed_but_unchanged.is_empty()
+    }
+}
+
+/// File paths named by file evidence, including inside composites
+fn collect_file_paths<'a>(evidence: &'a EvidenceSpec, paths: &mut Vec<&'a str>) {
+    match evidence {
+        EvidenceSpec::FileExists { path }
+        | EvidenceSpec::FileWithHash { path, .. }
+        | EvidenceSpec::FileContains { path, .. }
+        | EvidenceSpec::FileMatchesRegex { path, .. }
+        | EvidenceSpec::FileLineRange { path, .. }
+        | EvidenceSpec::FileUnifiedDiffApplied { path, .. }
+        | EvidenceSpec::FileJsonPath { path, .. }
+        | EvidenceSpec::FileStructuredPath { path, .. }
+        | EvidenceSpec::SymbolDefined { path, .. }
+        | EvidenceSpec::FileModifiedAfter { path, .. }
+        | EvidenceSpec::FileAbsent { path } => paths.push(path),
+        EvidenceSpec::AllOf { evidence }
+        | EvidenceSpec::AnyOf { evidence }
+        | EvidenceSpec::AtLeast { evidence, .. } => {
+            for nested in evidence {
+                collect_file_paths(nested, paths);
+            }
+        }
+        EvidenceSpec::Not { evidence } | EvidenceSpec::Weighted { evidence, .. } => {
+            collect_file_paths(evidence, paths)
+        }
+        _ => {}
+    }
+}
+
+/// Resolve `.` and `..` lexically; deleted files cannot be canonicalized
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Verdict, Verifier};
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_capture_records_files_and_skips_ignored() {
+        let dir = tempdir().unwrap();
+        fs::create_dir_all(dir.path().join("src")).unwrap();
+        fs::create_dir_all(dir.path().join(".git")).unwrap();
+        fs::write(dir.path().join("src/lib.rs"), "fn main() {}").unwrap();
+        fs::write(dir.path().join(".git/HEAD"), "ref").unwrap();
+
+        let snapshot = Snapshot::capture(dir.path()).unwrap();
+        assert_eq!(snapshot.files.len(), 1);
+        let entry = &snapshot.files["src/lib.rs"];
+        assert_eq!(entry.size, 12);
+        assert_eq!(entry.sha256.len(), 64);
+    }
+
+    #[test]
+    fn test_saved_snapshot_is_not_a_change() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("before.json"), "stale").unwrap();
+        let snapshot = Snapshot::capture(dir.path()).unwrap();
+        let out = dir.path().join("before.json");
+        snapshot.save(&out).unwrap();
+
+        let loaded = Snapshot::load(&out).unwrap();
+        assert_eq!(loaded.output.as_deref(), Some("before.json"));
+        assert!(loaded.diff_now().unwrap().is_empty());
+
+        // Saved outside the tree, nothing is recorded
+        let elsewhere = tempdir().unwrap();
+        snapshot.save(elsewhere.path().join("s.json")).unwrap();
+        let loaded = Snapshot::load(elsewhere.path().join("s.json")).unwrap();
+        assert_eq!(loaded.output, None);
+    }
+
+    #[test]
+    fn test_diff_classifies_changes() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("keep.txt"), "same").unwrap();
+        fs::write(dir.path().join("edit.txt"), "old").unwrap();
+        fs::write(dir.path().join("gone.txt"), "bye").unwrap();
+        let before = Snapshot::capture(dir.path()).unwrap();
+
+        fs::write(dir.path().join("edit.txt"), "new").unwrap();
+        fs::remove_file(dir.path().join("gone.txt")).unwrap();
+        fs::write(dir.path().join("new.txt"), "hi").unwrap();
+        let diff = before.diff_now().unwrap();
+
+        let name = |c: &FileChange| Path::new(&c.path).file_name().unwrap().to_owned();
+        assert_eq!(
+            diff.created.iter().map(name).collect::<Vec<_>>(),
+            ["new.txt"]
+        );
+        assert_eq!(
+            diff.modified.iter().map(name).collect::<Vec<_>>(),
+            ["edit.txt"]
+        );
+        assert_eq!(
+            diff.deleted.iter().map(name).collect::<Vec<_>>(),
+            ["gone.txt"]
+        );
+        assert!(diff.deleted[0].after_sha256.is_none());
+    }
+
+    #[test]
+    fn test_diff_claims_verify_against_tree() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("edit.txt"), "old").unwrap();
+        fs::write(dir.path().join("gone.txt"), "bye").unwrap();
+        let before = Snapshot::capture(dir.path()).unwrap();
+
+        fs::write(dir.path().join("edit.txt"), "new").unwrap();
+        fs::remove_file(dir.path().join("gone.txt")).unwrap();
+        fs::write(dir.path().join("new.txt"), "hi").unwrap();
+        let claims = before.diff_now().unwrap().to_claims();
+
+        assert_eq!(claims.len(), 3);
+        let verifier = Verifier::new();
+        for claim in &claims {
+            assert_eq!(claim.source.as_deref(), Some(SNAPSHOT_SOURCE));
+            assert_eq!(
+                verifier.verify(claim).overall_verdict,
+                Verdict::Confirmed,
+                "{}",
+                claim.description
+            );
+        }
+    }
+
+    #[test]
+    fn test_cross_check_finds_phantom_and_unclaimed_changes() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("untouched.txt"), "same").unwrap();
+        let before = Snapshot::capture(dir.path()).unwrap();
+
+        fs::write(dir.path().join("real.txt"), "new").unwrap();
+        fs::write(dir.path().join("sneaky.txt"), "new").unwrap();
+        let diff = before.diff_now().unwrap();
+
+        let claims = vec![
+            Claim::new("Created real").with_evidence(EvidenceSpec::FileExists {
+                path: "./real.txt".to_string(),
+            }),
+            Claim::new("Updated untouched").with_evidence(EvidenceSpec::FileExists {
+                path: "untouched.txt".to_string(),
+            }),
+            Claim::new("Outside").with_evidence(EvidenceSpec::FileExists {
+                path: "/somewhere/else.txt".to_string(),
+            }),
+        ];
+        let check = diff.cross_check(&claims, &before.root, &before.root);
+
+        assert_eq!(check.confirmed.len(), 1);
+        assert_eq!(check.confirmed[0].claim, "Created real");
+        assert_eq!(check.claimed_but_unchanged.len(), 1);
+        assert_eq!(check.claimed_but_unchanged[0].claim, "Updated untouched");
+        assert_eq!(check.unclaimed_changes.len(), 1);
+        assert!(check.unclaimed_changes[0].ends_with("sneaky.txt"));
+        assert!(!check.is_consistent());
+    }
+
+    #[test]
+    fn test_snapshot_round_trips_through_json() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("a.txt"), "a").unwrap();
+        let snapshot = Snapshot::capture(dir.path()).unwrap();
+
+        let file = dir.path().join("snapshot.json");
+        snapshot.save(&file).unwrap();
+        let expected = Snapshot {
+            output: Some("snapshot.json".to_string()),
+            ..snapshot
+        };
+        assert_eq!(Snapshot::load(&file).unwrap(), expected);
+    }
+}
@@ -0,0 +1,283 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Source-code-aware evidence
+//!
+//! `SymbolDefined` parses a source file and looks for a named item, so a
+//! claim like "added `Config::validate`" is not satisfied by a comment or a
+//! string that merely mentions it. Languages plug in through
+//! [`SourceParser`]; Rust is built in (via `syn`) and more parsers can be
+//! added with [`Verifier::register_source_parser`](crate::Verifier::register_source_parser).
+//!
+//! Symbols are `::`-separated paths matched against the end of each item's
+//! full path within the file. `validate` matches any item of that name,
+//! `Config::validate` a method in an `impl Config` (or a trait `Config`)
+//! anywhere, and `config::Config::validate` only inside `mod config`.
+//!
+//! ```rust
+//! use did_you_actually_do_that::source::{RustParser, SourceParser, SymbolKind};
+//!
+//! let code = "struct Config; impl Config { fn validate(&self) {} }";
+//! let symbols = RustParser.symbols(code).unwrap();
+//! assert!(symbols
+//!     .iter()
+//!     .any(|s| s.matches("Config::validate") && s.kind == SymbolKind::Method));
+//! ```
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// The kind of a source item
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SymbolKind {
+    /// A free function
+    Fn,
+    /// A function inside an `impl` or `trait` block
+    Method,
+    Struct,
+    Enum,
+    /// An enum variant, addressed as `Enum::Variant`
+    Variant,
+    Trait,
+    Union,
+    Const,
+    Static,
+    /// A type alias or associated type
+    Type,
+    Mod,
+    Macro,
+}
+
+impl fmt::Display for SymbolKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = serde_json::to_value(self).ok();
+        f.write_str(name.as_ref().and_then(|v| v.as_str()).unwrap_or("item"))
+    }
+}
+
+/// An item found in a source file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    /// Full path within the file, e.g. `["config", "Config", "validate"]`
+    pub path: Vec<String>,
+    pub kind: SymbolKind,
+    /// 1-based line of the item's name, if the parser knows it
+    pub line: Option<usize>,
+}
+
+impl Symbol {
+    /// Whether `query` (`a::b::c`) names this symbol, matching the end of its path
+    pub fn matches(&self, query: &str) -> bool {
+        let wanted: Vec<&str> = query.split("::").map(str::trim).collect();
+        wanted.len() <= self.path.len()
+            && self.path[self.path.len() - wanted.len()..]
+                .iter()
+                .zip(&wanted)
+                .all(|(have, want)| have == want)
+    }
+
+    /// The path joined with `::`
+    pub fn qualified_name(&self) -> String {
+        self.path.join("::")
+    }
+}
+
+/// Extracts the symbols defined in source files of one language
+pub trait SourceParser: Send + Sync {
+    /// Language name, as accepted in the evidence's `language` field
+    fn language(&self) -> &str;
+
+    /// File extensions (without the dot) this parser handles
+    fn extensions(&self) -> &[&str];
+
+    /// Every item defined in `source`, or why it could not be parsed
+    fn symbols(&self, source: &str) -> Result<Vec<Symbol>, String>;
+}
+
+/// Parser for Rust source, built on `syn`
+///
+/// Reports items in (inline) modules, `impl`/`trait` members and enum
+/// variants. Function bodies are not searched, and `#[cfg]` attributes are
+/// not evaluated, so gated-out items still count.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RustParser;
+
+impl SourceParser for RustParser {
+    fn language(&self) -> &str {
+        "rust"
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["rs"]
+    }
+
+    fn symbols(&self, source: &str) -> Result<Vec<Symbol>, String> {
+        let file = syn::parse_file(source).map_err(|e| {
+            let start = e.span().start();
+            format!("{} (line {})", e, start.line)
+        })?;
+        let mut symbols = Vec::new();
+        collect_items(&file.items, &[], &mut symbols);
+        Ok(symbols)
+    }
+}
+
+fn collect_items(items: &[syn::Item], prefix: &[String], out: &mut Vec<Symbol>) {
+    let mut push = |path: Vec<String>, kind, ident: &syn::Ident| {
+        out.push(Symbol {
+            path,
+            kind,
+            line: Some(ident.span().start().line),
+        })
+    };
+    let child = |name: &syn::Ident| {
+        let mut path = prefix.to_vec();
+        path.push(name.to_string());
+        path
+    };
+
+    let mut nested = Vec::new();
+    for item in items {
+        match item {
+            syn::Item::Fn(f) => push(child(&f.sig.ident), SymbolKind::Fn, &f.sig.ident),
+            syn::Item::Struct(s) => push(child(&s.ident), SymbolKind::Struct, &s.ident),
+            syn::Item::Union(u) => push(child(&u.ident), SymbolKind::Union, &u.ident),
+            syn::Item::Const(c) => push(child(&c.ident), SymbolKind::Const, &c.ident),
+            syn::Item::Static(s) => push(child(&s.ident), SymbolKind::Static, &s.ident),
+            syn::Item::Type(t) => push(child(&t.ident), SymbolKind::Type, &t.ident),
+            syn::Item::Enum(e) => {
+                push(child(&e.ident), SymbolKind::Enum, &e.ident);
+                for variant in &e.variants {
+                    let mut path = child(&e.ident);
+                    path.push(variant.ident.to_string());
+                    push(path, SymbolKind::Variant, &variant.ident);
+                }
+            }
+            syn::Item::Trait(t) => {
+                push(child(&t.ident), SymbolKind::Trait, &t.ident);
+                for member in &t.items {
+                    let (ident, kind) = match member {
+                        syn::TraitItem::Fn(f) => (&f.sig.ident, SymbolKind::Method),
+                        syn::TraitItem::Const(c) => (&c.ident, SymbolKind::Const),
+                        syn::TraitItem::Type(t) => (&t.ident, SymbolKind::Type),
+                        _ => continue,
+                    };
+                    let mut path = child(&t.ident);
+                    path.push(ident.to_string());
+                    push(path, kind, ident);
+                }
+            }
+            syn::Item::Impl(i) => {
+                let Some(owner) = type_name(&i.self_ty) else {
+                    continue;
+                };
+                for member in &i.items {
+                    let (ident, kind) = match member {
+                        syn::ImplItem::Fn(f) => (&f.sig.ident, SymbolKind::Method),
+                        syn::ImplItem::Const(c) => (&c.ident, SymbolKind::Const),
+                        syn::ImplItem::Type(t) => (&t.ident, SymbolKind::Type),
+                        _ => continue,
+                    };
+                    let mut path = child(owner);
+                    path.push(ident.to_string());
+                    push(path, kind, ident);
+                }
+            }
+            syn::Item::Mod(m) => {
+                push(child(&m.ident), SymbolKind::Mod, &m.ident);
+                if let Some((_, content)) = &m.content {
+                    nested.push((child(&m.ident), content));
+                }
+            }
+            syn::Item::Macro(m) => {
+                if let Some(ident) = &m.ident {
+                    push(child(ident), SymbolKind::Macro, ident);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    for (path, content) in nested {
+        collect_items(content, &path, out);
+    }
+}
+
+/// The name of an `impl` target: `Config` for `impl<T> Config<T>`
+fn type_name(ty: &syn::Type) -> Option<&syn::Ident> {
+    match ty {
+        syn::Type::Path(p) => p.path.segments.last().map(|s| &s.ident),
+        syn::Type::Reference(r) => type_name(&r.elem),
+        syn::Type::Group(g) => type_name(&g.elem),
+        syn::Type::Paren(p) => type_name(&p.elem),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOURCE: &str = r#"
+// fn validate() is only mentioned here
+pub struct Config;
+
+impl Config {
+    pub fn validate(&self) -> bool { true }
+}
+
+impl<T> std::fmt::Display for Wrapper<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result { Ok(()) }
+}
+
+pub enum Color { Red, Green }
+
+pub mod net {
+    pub trait Transport {
+        fn send(&self);
+    }
+    macro_rules! retry { () => {} }
+}
+"#;
+
+    fn find(query: &str) -> Vec<(String, SymbolKind, Option<usize>)> {
+        RustParser
+            .symbols(SOURCE)
+            .unwrap()
+            .into_iter()
+            .filter(|s| s.matches(query))
+            .map(|s| (s.qualified_name(), s.kind, s.line))
+            .collect()
+    }
+
+    #[test]
+    fn test_finds_methods_variants_and_nested_items() {
+        assert_eq!(
+            find("Config::validate"),
+            vec![("Config::validate".to_string(), SymbolKind::Method, Some(6))]
+        );
+        assert_eq!(find("Wrapper::fmt")[0].1, SymbolKind::Method);
+        assert_eq!(find("Color::Green")[0].1, SymbolKind::Variant);
+        assert_eq!(find("net::Transport::send")[0].1, SymbolKind::Method);
+        assert_eq!(find("retry")[0].1, SymbolKind::Macro);
+    }
+
+    #[test]
+    fn test_module_path_must_match() {
+        assert_eq!(find("Transport").len(), 1);
+        assert!(find("other::Transport").is_empty());
+        // The comment mention is not a definition
+        assert_eq!(find("validate").len(), 1);
+    }
+
+    #[test]
+    fn test_parse_errors_report_line() {
+        let err = RustParser.symbols("fn ok() {}\nfn broken( {").unwrap_err();
+        assert!(err.contains("line 2"), "{}", err);
+    }
+
+    #[test]
+    fn test_symbol_kind_display() {
+        assert_eq!(SymbolKind::Method.to_string(), "method");
+    }
+}
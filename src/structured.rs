@@ -0,0 +1,240 @@
+// SPDX-License-Identifier: MPL-2.0
+//! Configuration files as JSON values
+//!
+//! `FileStructuredPath` parses TOML, YAML, INI and dotenv files into a
+//! [`serde_json::Value`] so they can be queried exactly like JSON with the
+//! [`query`](crate::query) engine. How each format maps onto JSON:
+//!
+//! - TOML tables become objects; datetimes become RFC 3339 strings.
+//! - YAML mappings must have string keys. Only the first document is read.
+//! - INI sections become objects keyed by section name; keys before the
+//!   first section sit at the top level. All values are strings.
+//! - dotenv files become a flat object of strings. `export` prefixes,
+//!   quotes and trailing `# comments` are handled.
+//!
+//! ```rust
+//! use did_you_actually_do_that::structured::ConfigFormat;
+//!
+//! let format = ConfigFormat::detect("Cargo.toml").unwrap();
+//! let doc = format.parse("[package]\nedition = \"2021\"\n").unwrap();
+//! assert_eq!(doc["package"]["edition"], "2021");
+//! ```
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::fmt;
+use std::path::Path;
+
+/// A structured file format
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfigFormat {
+    Json,
+    Toml,
+    Yaml,
+    Ini,
+    Dotenv,
+}
+
+impl fmt::Display for ConfigFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ConfigFormat::Json => "JSON",
+            ConfigFormat::Toml => "TOML",
+            ConfigFormat::Yaml => "YAML",
+            ConfigFormat::Ini => "INI",
+            ConfigFormat::Dotenv => "dotenv",
+        })
+    }
+}
+
+impl ConfigFormat {
+    /// Guess the format from a file name (`.env`, `prod.env` and
+    /// `.env.local` are dotenv; `.cfg` and `.conf` are INI)
+    pub fn detect(path: impl AsRef<Path>) -> Option<Self> {
+        let path = path.as_ref();
+        let name = path.file_name()?.to_str()?.to_ascii_lowercase();
+        if name == ".env" || name.starts_with(".env.") {
+            return Some(ConfigFormat::Dotenv);
+        }
+
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        match extension.as_str() {
+            "json" => Some(ConfigFormat::Json),
+            "toml" => Some(ConfigFormat::Toml),
+            "yaml" | "yml" => Some(ConfigFormat::Yaml),
+            "ini" | "cfg" | "conf" => Some(ConfigFormat::Ini),
+            "env" => Some(ConfigFormat::Dotenv),
+            _ => None,
+        }
+    }
+
+    /// Parse `text` into a JSON value
+    pub fn parse(self, text: &str) -> Result<Value, String> {
+        match self {
+            ConfigFormat::Json => serde_json::from_str(text).map_err(|e| e.to_string()),
+            ConfigFormat::Toml => text
+                .parse::<toml::Table>()
+                .map(|table| toml_to_json(toml::Value::Table(table)))
+                .map_err(|e| e.to_string()),
+            ConfigFormat::Yaml => serde_yaml::from_str(text).map_err(|e| e.to_string()),
+            ConfigFormat::Ini => parse_ini(text),
+            ConfigFormat::Dotenv => parse_dotenv(text),
+        }
+    }
+}
+
+fn toml_to_json(value: toml::Value) -> Value {
+    match value {
+        toml::Value::String(s) => Value::String(s),
+        toml::Value::Integer(i) => Value::from(i),
+        toml::Value::Float(f) => Value::from(f),
+        toml::Value::Boolean(b) => Value::Bool(b),
+        toml::Value::Datetime(dt) => Value::String(dt.to_string()),
+        toml::Value::Array(items) => Value::Array(items.into_iter().map(toml_to_json).collect()),
+        toml::Value::Table(table) => Value::Object(
+            table
+                .into_iter()
+                .map(|(k, v)| (k, toml_to_json(v)))
+                .collect(),
+        ),
+    }
+}
+
+fn parse_ini(text: &str) -> Result<Value, String> {
+    let ini = ini::Ini::load_from_str(text).map_err(|e| e.to_string())?;
+    let mut root = Map::new();
+
+    for (section, properties) in ini.iter() {
+        let entries = properties
+            .iter()
+            .map(|(k, v)| (k.to_string(), Value::String(v.to_string())));
+        match section {
+            Some(name) => {
+                let target = root
+                    .entry(name.to_string())
+                    .or_insert_with(|| Value::Object(Map::new()));
+                if let Value::Object(map) = target {
+                    map.extend(entries);
+                }
+            }
+            None => root.extend(entries),
+        }
+    }
+
+    Ok(Value::Object(root))
+}
+
+fn parse_dotenv(text: &str) -> Result<Value, String> {
+    let mut root = Map::new();
+
+    for (number, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let (key, raw) = line
+            .split_once('=')
+            .ok_or_else(|| format!("line {}: expected KEY=VALUE", number + 1))?;
+        root.insert(
+            key.trim().to_string(),
+            Value::String(dotenv_value(raw.trim())),
+        );
+    }
+
+    Ok(Value::Object(root))
+}
+
+fn dotenv_value(raw: &str) -> String {
+    if let Some(inner) = raw.strip_prefix('\'') {
+        return inner.split('\'').next().unwrap_or_default().to_string();
+    }
+    if let Some(inner) = raw.strip_prefix('"') {
+        let mut value = String::new();
+        let mut chars = inner.chars();
+        while let Some(c) = chars.next() {
+            match c {
+                '"' => break,
+                '\\' => match chars.next() {
+                    Some('n') => value.push('\n'),
+                    Some('t') => value.push('\t'),
+                    Some(other) => value.push(other),
+                    None => break,
+                },
+                _ => value.push(c),
+            }
+        }
+        return value;
+    }
+    match raw.find(" #") {
+        Some(pos) => raw[..pos].trim_end().to_string(),
+        None => raw.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(ConfigFormat::detect("Cargo.toml"), Some(ConfigFormat::Toml));
+        assert_eq!(
+            ConfigFormat::detect(".github/workflows/ci.yml"),
+            Some(ConfigFormat::Yaml)
+        );
+        assert_eq!(ConfigFormat::detect("setup.cfg"), Some(ConfigFormat::Ini));
+        assert_eq!(ConfigFormat::detect(".env"), Some(ConfigFormat::Dotenv));
+        assert_eq!(
+            ConfigFormat::detect(".env.local"),
+            Some(ConfigFormat::Dotenv)
+        );
+        assert_eq!(ConfigFormat::detect("prod.env"), Some(ConfigFormat::Dotenv));
+        assert_eq!(ConfigFormat::detect("Makefile"), None);
+    }
+
+    #[test]
+    fn test_parse_toml() {
+        let doc = ConfigFormat::Toml
+            .parse("[package]\nedition = \"2021\"\nversion = 3\nreleased = 1979-05-27\n")
+            .unwrap();
+        assert_eq!(
+            doc,
+            json!({"package": {"edition": "2021", "version": 3, "released": "1979-05-27"}})
+        );
+    }
+
+    #[test]
+    fn test_parse_yaml() {
+        let doc = ConfigFormat::Yaml
+            .parse("jobs:\n  test:\n    runs-on: ubuntu-latest\n    steps: [a, b]\n")
+            .unwrap();
+        assert_eq!(doc["jobs"]["test"]["steps"], json!(["a", "b"]));
+    }
+
+    #[test]
+    fn test_parse_ini() {
+        let doc = ConfigFormat::Ini
+            .parse("root = 1\n[server]\nport = 8080\nhost = example.com\n")
+            .unwrap();
+        assert_eq!(
+            doc,
+            json!({"root": "1", "server": {"port": "8080", "host": "example.com"}})
+        );
+    }
+
+    #[test]
+    fn test_parse_dotenv() {
+        let doc = ConfigFormat::Dotenv
+            .parse("# comment\nexport A=1\nB = \"two words\\n\"\nC='lit # eral'\nD=x # note\nE=\n")
+            .unwrap();
+        assert_eq!(
+            doc,
+            json!({"A": "1", "B": "two words\n", "C": "lit # eral", "D": "x", "E": ""})
+        );
+        assert!(ConfigFormat::Dotenv.parse("not a pair").is_err());
+    }
+}
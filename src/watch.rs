@@ -15,36 +15,64 @@ pub fn extract_watch_paths(claim: &Claim) -> HashSet<String> {
     let mut paths = HashSet::new();
 
     for evidence in &claim.evidence {
-        match evidence {
-            EvidenceSpec::FileExists { path }
-            | EvidenceSpec::FileWithHash { path, .. }
-            | EvidenceSpec::FileContains { path, .. }
-            | EvidenceSpec::FileMatchesRegex { path, .. }
-            | EvidenceSpec::FileJsonPath { path, .. }
-            | EvidenceSpec::FileModifiedAfter { path, .. } => {
-                paths.insert(path.clone());
-            }
-            EvidenceSpec::DirectoryExists { path } => {
-                paths.insert(path.clone());
-            }
-            EvidenceSpec::GitClean { repo_path }
-            | EvidenceSpec::GitCommitExists { repo_path, .. }
-            | EvidenceSpec::GitBranchExists { repo_path, .. } => {
-                if let Some(p) = repo_path {
-                    paths.insert(p.clone());
-                } else {
-                    paths.insert(".".to_string());
-                }
+        collect_watch_paths(evidence, &mut paths);
+    }
+
+    paths
+}
+
+/// Collect watchable paths from one piece of evidence, descending into composites
+fn collect_watch_paths(evidence: &EvidenceSpec, paths: &mut HashSet<String>) {
+    match evidence {
+        EvidenceSpec::FileExists { path }
+        | EvidenceSpec::FileWithHash { path, .. }
+        | EvidenceSpec::FileContains { path, .. }
+        | EvidenceSpec::FileMatchesRegex { path, .. }
+        | EvidenceSpec::FileLineRange { path, .. }
+        | EvidenceSpec::FileUnifiedDiffApplied { path, .. }
+        | EvidenceSpec::FileJsonPath { path, .. }
+        | EvidenceSpec::FileStructuredPath { path, .. }
+        | EvidenceSpec::SymbolDefined { path, .. }
+        | EvidenceSpec::FileModifiedAfter { path, .. }
+        | EvidenceSpec::FileAbsent { path } => {
+            paths.insert(path.clone());
+        }
+        EvidenceSpec::DirectoryExists { path } | EvidenceSpec::DirectoryAbsent { path } => {
+            paths.insert(path.clone());
+        }
+        EvidenceSpec::GitClean { repo_path }
+        | EvidenceSpec::GitCommitExists { repo_path, .. }
+        | EvidenceSpec::GitBranchExists { repo_path, .. }
+        | EvidenceSpec::GitBranchAbsent { repo_path, .. }
+        | EvidenceSpec::FileChangedInCommit { repo_path, .. }
+        | EvidenceSpec::CommitTouchesPaths { repo_path, .. }
+        | EvidenceSpec::CommitDiffMatches { repo_path, .. }
+        | EvidenceSpec::CommitMessageMatches { repo_path, .. }
+        | EvidenceSpec::CommitIsAncestor { repo_path, .. } => {
+            if let Some(p) = repo_path {
+                paths.insert(p.clone());
+            } else {
+                paths.insert(".".to_string());
             }
-            EvidenceSpec::CommandSucceeds { .. }
-            | EvidenceSpec::EnvVar { .. }
-            | EvidenceSpec::Custom { .. } => {
-                // These don't have watchable paths
+        }
+        EvidenceSpec::AllOf { evidence }
+        | EvidenceSpec::AnyOf { evidence }
+        | EvidenceSpec::AtLeast { evidence, .. } => {
+            for nested in evidence {
+                collect_watch_paths(nested, paths);
             }
         }
+        EvidenceSpec::Not { evidence } | EvidenceSpec::Weighted { evidence, .. } => {
+            collect_watch_paths(evidence, paths)
+        }
+        EvidenceSpec::CommandSucceeds { .. }
+        | EvidenceSpec::TestPasses { .. }
+        | EvidenceSpec::EnvVar { .. }
+        | EvidenceSpec::EnvVarAbsent { .. }
+        | EvidenceSpec::Custom { .. } => {
+            // These don't have watchable paths
+        }
     }
-
-    paths
 }
 
 /// Watch claims and re-verify on changes
@@ -53,6 +81,19 @@ pub fn watch_and_verify<F>(
     on_report: F,
     debounce_ms: u64,
 ) -> Result<(), Box<dyn std::error::Error>>
+where
+    F: Fn(&VerificationReport) + Send + 'static,
+{
+    watch_and_verify_with(Verifier::new(), claims, on_report, debounce_ms)
+}
+
+/// Watch claims and re-verify on changes using a configured verifier
+pub fn watch_and_verify_with<F>(
+    verifier: Verifier,
+    claims: Vec<Claim>,
+    on_report: F,
+    debounce_ms: u64,
+) -> Result<(), Box<dyn std::error::Error>>
 where
     F: Fn(&VerificationReport) + Send + 'static,
 {
@@ -87,8 +128,6 @@ where
         }
     }
 
-    let verifier = Verifier::new();
-
     // Initial verification
     println!("Initial verification:");
     for claim in &claims {
@@ -198,6 +198,503 @@ fn cli_report_mixed_results() {
     assert!(stdout.contains("Overall: Refuted"));
 }
 
+#[test]
+fn cli_report_baseline_fails_only_on_regressions() {
+    let dir = tempdir().unwrap();
+    let kept = dir.path().join("kept.txt");
+    let removed = dir.path().join("removed.txt");
+    fs::write(&kept, "content").unwrap();
+    fs::write(&removed, "content").unwrap();
+
+    let claims_file = dir.path().join("claims.json");
+    let claims_json = format!(
+        r#"[
+        {{ "id": "kept", "description": "Kept",
+           "evidence": [{{ "type": "FileExists", "spec": {{ "path": "{}" }} }}] }},
+        {{ "id": "removed", "description": "Removed",
+           "evidence": [{{ "type": "FileExists", "spec": {{ "path": "{}" }} }}] }},
+        {{ "id": "noise", "description": "Never verifiable", "evidence": [] }}
+    ]"#,
+        kept.to_string_lossy().replace('\\', "\\\\"),
+        removed.to_string_lossy().replace('\\', "\\\\")
+    );
+    fs::write(&claims_file, claims_json).unwrap();
+
+    let run = |extra: &[&str]| {
+        let mut args = vec!["run", "--", "report", "--json"];
+        args.extend_from_slice(extra);
+        let claims = claims_file.to_string_lossy().to_string();
+        Command::new("cargo")
+            .args(&args)
+            .arg(claims)
+            .output()
+            .expect("Failed to run dyadt")
+    };
+
+    let first = run(&[]);
+    let baseline = dir.path().join("baseline.json");
+    fs::write(&baseline, &first.stdout).unwrap();
+    let baseline_arg = baseline.to_string_lossy().to_string();
+
+    // Unverifiable noise alone does not fail against the baseline
+    let unchanged = run(&["--baseline", &baseline_arg]);
+    assert_eq!(unchanged.status.code(), Some(0));
+    let json: serde_json::Value = serde_json::from_slice(&unchanged.stdout).unwrap();
+    assert_eq!(json["baseline"]["summary"]["unchanged"], 3);
+
+    fs::remove_file(&removed).unwrap();
+    let regressed = run(&["--baseline", &baseline_arg]);
+    assert_eq!(regressed.status.code(), Some(1));
+    let json: serde_json::Value = serde_json::from_slice(&regressed.stdout).unwrap();
+    assert_eq!(json["baseline"]["summary"]["regressed"], 1);
+    assert_eq!(json["baseline"]["claims"][1]["change"], "regressed");
+}
+
+#[test]
+fn cli_report_markdown_and_html() {
+    let dir = tempdir().unwrap();
+    let file = dir.path().join("exists.txt");
+    fs::write(&file, "content").unwrap();
+
+    let claims_file = dir.path().join("claims.json");
+    let claims_json = format!(
+        r#"[
+        {{ "description": "Wrote <b>bold</b> | piped", "source": "agent",
+           "evidence": [{{ "type": "FileExists", "spec": {{ "path": "{}" }} }}] }},
+        {{ "description": "Missing file",
+           "evidence": [{{ "type": "FileExists", "spec": {{ "path": "/no/such/file" }} }}] }}
+    ]"#,
+        file.to_string_lossy().replace('\\', "\\\\")
+    );
+    fs::write(&claims_file, claims_json).unwrap();
+
+    let run = |format: &str| {
+        Command::new("cargo")
+            .args([
+                "run",
+                "--",
+                "report",
+                format,
+                &claims_file.to_string_lossy(),
+            ])
+            .output()
+            .expect("Failed to run dyadt")
+    };
+
+    let markdown = run("--markdown");
+    assert_eq!(markdown.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&markdown.stdout);
+    assert!(stdout.contains("**Overall: Refuted**"));
+    assert!(stdout.contains("<b>Wrote &lt;b&gt;bold&lt;/b&gt; | piped</b>"));
+    // Refuted claims start expanded
+    assert!(stdout.contains("<details open>\n<summary>✗ <b>Missing file</b>"));
+    assert!(stdout.contains("| ✗ | File exists: /no/such/file |"));
+
+    let html = run("--html");
+    assert_eq!(html.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&html.stdout);
+    assert!(stdout.starts_with("<!DOCTYPE html>"));
+    assert!(stdout.contains(r#"data-verdict="Confirmed" data-source="agent""#));
+    assert!(stdout.contains(r#"data-verdict="Refuted" data-source="(none)""#));
+    assert!(stdout.contains("<option>agent</option>"));
+    assert!(!stdout.contains("<b>bold</b>"));
+}
+
+#[test]
+fn cli_report_tap_csv_and_ndjson() {
+    let dir = tempdir().unwrap();
+    let file = dir.path().join("exists.txt");
+    fs::write(&file, "content").unwrap();
+
+    let claims_file = dir.path().join("claims.json");
+    let claims_json = format!(
+        r#"[
+        {{ "id": "found", "description": "Wrote it, \"really\" #1",
+           "evidence": [{{ "type": "FileExists", "spec": {{ "path": "{}" }} }}] }},
+        {{ "id": "missing", "description": "Missing file",
+           "evidence": [{{ "type": "FileExists", "spec": {{ "path": "/no/such/file" }} }}] }},
+        {{ "id": "noise", "description": "Nothing to check", "evidence": [] }}
+    ]"#,
+        file.to_string_lossy().replace('\\', "\\\\")
+    );
+    fs::write(&claims_file, claims_json).unwrap();
+
+    let run = |format: &str| {
+        Command::new("cargo")
+            .args([
+                "run",
+                "--",
+                "report",
+                "--jobs",
+                "2",
+                format,
+                &claims_file.to_string_lossy(),
+            ])
+            .output()
+            .expect("Failed to run dyadt")
+    };
+
+    let tap = run("--tap");
+    assert_eq!(tap.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&tap.stdout);
+    assert!(stdout.starts_with("TAP version 14\n1..3\n"));
+    assert!(stdout.contains("ok 1 - Wrote it, \"really\" \\#1\n"));
+    assert!(stdout.contains("not ok 2 - Missing file\n  ---\n"));
+    assert!(stdout.contains("ok 3 - Nothing to check # SKIP Unverifiable\n"));
+
+    let csv = run("--csv");
+    let stdout = String::from_utf8_lossy(&csv.stdout);
+    let rows: Vec<&str> = stdout.split("\r\n").filter(|r| !r.is_empty()).collect();
+    assert_eq!(rows.len(), 4);
+    assert!(rows[0].starts_with("claim_id,claim,source,claim_verdict,"));
+    assert!(rows[1].starts_with("found,\"Wrote it, \"\"really\"\" #1\",,Confirmed,"));
+    assert!(rows[3].starts_with("noise,Nothing to check,,Unverifiable,"));
+
+    let ndjson = run("--ndjson");
+    assert_eq!(ndjson.status.code(), Some(1));
+    let mut ids: Vec<String> = String::from_utf8_lossy(&ndjson.stdout)
+        .lines()
+        .map(|line| {
+            let report: serde_json::Value = serde_json::from_str(line).unwrap();
+            report["claim"]["id"].as_str().unwrap().to_string()
+        })
+        .collect();
+    ids.sort();
+    assert_eq!(ids, ["found", "missing", "noise"]);
+}
+
+#[test]
+fn cli_report_parallel_jobs_keeps_order() {
+    let dir = tempdir().unwrap();
+
+    let claims: Vec<String> = (0..12)
+        .map(|i| {
+            let path = dir.path().join(format!("file{}.txt", i));
+            fs::write(&path, "content").unwrap();
+            format!(
+                r#"{{ "description": "Claim number {}", "evidence": [{{ "type": "FileExists", "spec": {{ "path": "{}" }} }}] }}"#,
+                i,
+                path.to_string_lossy().replace('\\', "\\\\")
+            )
+        })
+        .collect();
+    let claims_file = dir.path().join("claims.json");
+    fs::write(&claims_file, format!("[{}]", claims.join(","))).unwrap();
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--",
+            "report",
+            "--json",
+            "--jobs",
+            "4",
+            &claims_file.to_string_lossy(),
+        ])
+        .output()
+        .expect("Failed to run dyadt");
+
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let descriptions: Vec<&str> = json["reports"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|r| r["claim"]["description"].as_str().unwrap())
+        .collect();
+    let expected: Vec<String> = (0..12).map(|i| format!("Claim number {}", i)).collect();
+    assert_eq!(descriptions, expected);
+}
+
+#[test]
+fn cli_report_skips_dependents_and_rejects_cycles() {
+    let dir = tempdir().unwrap();
+    let claims_file = dir.path().join("claims.json");
+    fs::write(
+        &claims_file,
+        r#"[
+        { "id": "created", "description": "Created the file",
+          "evidence": [{ "type": "FileExists", "spec": { "path": "/no/such/file" } }] },
+        { "id": "tested", "description": "Tests for the file pass", "depends_on": ["created"],
+          "evidence": [{ "type": "DirectoryExists", "spec": { "path": "/" } }] }
+    ]"#,
+    )
+    .unwrap();
+
+    let output = Command::new("cargo")
+        .args(["run", "--", "report", &claims_file.to_string_lossy()])
+        .output()
+        .expect("Failed to run dyadt");
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Skipped: Depends on claim 'created', which was refuted"));
+
+    fs::write(
+        &claims_file,
+        r#"[
+        { "id": "a", "description": "A", "depends_on": ["b"], "evidence": [] },
+        { "id": "b", "description": "B", "depends_on": ["a"], "evidence": [] }
+    ]"#,
+    )
+    .unwrap();
+    let output = Command::new("cargo")
+        .args(["run", "--", "report", &claims_file.to_string_lossy()])
+        .output()
+        .expect("Failed to run dyadt");
+    assert_eq!(output.status.code(), Some(3));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Dependency cycle: a -> b -> a"));
+}
+
+#[test]
+fn cli_expand_resolves_manifest() {
+    let dir = tempdir().unwrap();
+    fs::write(
+        dir.path().join("common.json"),
+        r#"{ "templates": { "exists": { "type": "FileExists", "spec": { "path": "${root}/${file}" } } } }"#,
+    )
+    .unwrap();
+    let manifest = dir.path().join("claims.json");
+    fs::write(
+        &manifest,
+        r#"{
+        "include": ["common.json"],
+        "vars": { "root": "/tmp" },
+        "claims": [{ "description": "Wrote ${file:-nothing}",
+                     "evidence": [{ "template": "exists", "with": { "file": "out.txt" } }] }]
+    }"#,
+    )
+    .unwrap();
+
+    let output = Command::new("cargo")
+        .args(["run", "--", "--var", "root=/srv", "expand"])
+        .arg(&manifest)
+        .output()
+        .expect("Failed to run dyadt");
+    assert_eq!(output.status.code(), Some(0));
+    let claims: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(claims[0]["description"], "Wrote nothing");
+    assert_eq!(claims[0]["evidence"][0]["spec"]["path"], "/srv/out.txt");
+
+    fs::write(
+        &manifest,
+        r#"{ "claims": [{ "description": "${undefined_var}", "evidence": [] }] }"#,
+    )
+    .unwrap();
+    let output = Command::new("cargo")
+        .args(["run", "--", "check"])
+        .arg(&manifest)
+        .output()
+        .expect("Failed to run dyadt");
+    assert_eq!(output.status.code(), Some(3));
+    assert!(String::from_utf8_lossy(&output.stderr)
+        .contains("claim 1: Undefined variable 'undefined_var'"));
+}
+
+#[test]
+fn cli_validate_reports_typos_with_positions() {
+    let dir = tempdir().unwrap();
+    let claim_file = dir.path().join("claim.json");
+    fs::write(
+        &claim_file,
+        "{\n  \"description\": \"Wrote it\",\n  \"evidence\": [{ \"type\": \"FileExist\", \"spec\": { \"path\": \"x\" } }]\n}",
+    )
+    .unwrap();
+
+    let output = Command::new("cargo")
+        .args(["run", "--", "validate"])
+        .arg(&claim_file)
+        .output()
+        .expect("Failed to run dyadt");
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(
+        stdout.contains(":3:18: /evidence/0/type: unknown evidence type `FileExist`; did you mean `FileExists`?"),
+        "{}",
+        stdout
+    );
+
+    // Verifying the same file fails up front with the same diagnostics
+    let output = Command::new("cargo")
+        .args(["run", "--", "check"])
+        .arg(&claim_file)
+        .output()
+        .expect("Failed to run dyadt");
+    assert_eq!(output.status.code(), Some(3));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("did you mean `FileExists`?"));
+
+    let output = Command::new("cargo")
+        .args(["run", "--", "schema"])
+        .output()
+        .expect("Failed to run dyadt");
+    assert_eq!(output.status.code(), Some(0));
+    let schema: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert!(schema["$defs"]["EvidenceSpec"]["oneOf"].is_array());
+}
+
+#[test]
+fn cli_report_rejects_bad_jobs() {
+    let output = Command::new("cargo")
+        .args(["run", "--", "report", "--jobs", "many", "claims.json"])
+        .output()
+        .expect("Failed to run dyadt");
+
+    assert_eq!(output.status.code(), Some(3));
+}
+
+#[test]
+fn cli_report_rejects_unknown_format() {
+    let output = Command::new("cargo")
+        .args(["run", "--", "report", "--format", "pdf", "claims.json"])
+        .output()
+        .expect("Failed to run dyadt");
+
+    assert_eq!(output.status.code(), Some(3));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("Unknown format 'pdf'"));
+    assert!(stderr.contains("sarif"));
+}
+
+#[test]
+fn cli_no_exec_blocks_command_evidence() {
+    let dir = tempdir().unwrap();
+    let marker = dir.path().join("marker.txt");
+    let claim_file = dir.path().join("claim.json");
+    let claim_json = format!(
+        r#"{{
+        "description": "Touched a marker",
+        "evidence": [
+            {{ "type": "CommandSucceeds", "spec": {{ "command": "touch", "args": ["{}"] }} }}
+        ]
+    }}"#,
+        marker.to_string_lossy().replace('\\', "\\\\")
+    );
+    fs::write(&claim_file, claim_json).unwrap();
+
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--",
+            "--no-exec",
+            "check",
+            &claim_file.to_string_lossy(),
+        ])
+        .output()
+        .expect("Failed to run dyadt");
+
+    assert_eq!(output.status.code(), Some(2));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("Execution blocked by policy"));
+    assert!(!marker.exists(), "Blocked command must not run");
+}
+
+#[test]
+fn cli_stats_reports_history() {
+    let dir = tempdir().unwrap();
+    let history = dir.path().join("history");
+    let claims_file = dir.path().join("claims.json");
+    fs::write(
+        &claims_file,
+        r#"[
+        { "description": "Refuted", "source": "agent-a",
+          "evidence": [{ "type": "FileExists", "spec": { "path": "/no/such/file" } }] },
+        { "description": "Confirmed", "source": "agent-b",
+          "evidence": [{ "type": "DirectoryExists", "spec": { "path": "." } }] }
+    ]"#,
+    )
+    .unwrap();
+
+    let history_arg = history.to_string_lossy().to_string();
+    let claims_arg = claims_file.to_string_lossy().to_string();
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--",
+            "--history",
+            &history_arg,
+            "report",
+            &claims_arg,
+        ])
+        .output()
+        .expect("Failed to run dyadt");
+    assert_eq!(output.status.code(), Some(1));
+
+    let output = Command::new("cargo")
+        .args(["run", "--", "stats", "--json"])
+        .env("DYADT_HISTORY", &history)
+        .output()
+        .expect("Failed to run dyadt");
+    assert!(output.status.success());
+
+    let stats: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(stats["claims"]["total"], 2);
+    assert_eq!(stats["by_source"]["agent-a"]["refuted"], 1);
+    assert_eq!(stats["by_source"]["agent-b"]["confirmed"], 1);
+    assert_eq!(stats["by_evidence_type"]["DirectoryExists"]["total"], 1);
+}
+
+#[test]
+fn cli_snapshot_diff_emits_verifiable_claims() {
+    let dir = tempdir().unwrap();
+    let tree = dir.path().join("tree");
+    fs::create_dir_all(&tree).unwrap();
+    fs::write(tree.join("existing.txt"), "old").unwrap();
+    let snapshot = dir.path().join("before.json");
+    let tree_arg = tree.to_string_lossy().to_string();
+    let snapshot_arg = snapshot.to_string_lossy().to_string();
+
+    let output = Command::new("cargo")
+        .args(["run", "--", "snapshot", "-o", &snapshot_arg, &tree_arg])
+        .output()
+        .expect("Failed to run dyadt");
+    assert!(output.status.success());
+
+    fs::write(tree.join("existing.txt"), "new").unwrap();
+    fs::write(tree.join("added.txt"), "added").unwrap();
+
+    let output = Command::new("cargo")
+        .args(["run", "--", "diff", "--claims", &snapshot_arg])
+        .output()
+        .expect("Failed to run dyadt");
+    assert!(output.status.success());
+    let claims: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(claims.as_array().unwrap().len(), 2);
+
+    let claims_file = dir.path().join("claims.json");
+    fs::write(&claims_file, &output.stdout).unwrap();
+    let output = Command::new("cargo")
+        .args(["run", "--", "report", &claims_file.to_string_lossy()])
+        .output()
+        .expect("Failed to run dyadt");
+    assert!(output.status.success());
+
+    // The agent claims a file it never touched
+    let transcript = dir.path().join("reply.txt");
+    fs::write(
+        &transcript,
+        format!(
+            "I created the file {}/added.txt and modified the file {}/phantom.txt",
+            tree_arg, tree_arg
+        ),
+    )
+    .unwrap();
+    let output = Command::new("cargo")
+        .args([
+            "run",
+            "--",
+            "diff",
+            "--cross-check",
+            &transcript.to_string_lossy(),
+            &snapshot_arg,
+        ])
+        .output()
+        .expect("Failed to run dyadt");
+    assert_eq!(output.status.code(), Some(1));
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    assert!(stdout.contains("claimed but unchanged"));
+    assert!(stdout.contains("phantom.txt"));
+    assert!(stdout.contains("changed but not claimed"));
+}
+
 #[test]
 fn cli_help_shows_usage() {
     let output = Command::new("cargo")
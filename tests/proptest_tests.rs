@@ -1,7 +1,7 @@
 // SPDX-License-Identifier: MPL-2.0
 //! Property-based tests using proptest
 
-use did_you_actually_do_that::{Claim, EvidenceSpec, Verdict, Verifier};
+use did_you_actually_do_that::{Claim, DiffSide, EvidenceSpec, Verdict, Verifier};
 use proptest::prelude::*;
 
 // Strategy for generating arbitrary Verdict values
@@ -212,6 +212,423 @@ mod deterministic_tests {
         assert_eq!(report.overall_verdict, Verdict::Confirmed);
     }
 
+    #[test]
+    fn absence_evidence_tracks_removal() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("old.rs");
+        let sub = dir.path().join("build");
+        fs::write(&file, "").unwrap();
+        fs::create_dir(&sub).unwrap();
+
+        let claim = Claim::new("Cleaned up")
+            .with_evidence(EvidenceSpec::FileAbsent {
+                path: file.to_string_lossy().to_string(),
+            })
+            .with_evidence(EvidenceSpec::DirectoryAbsent {
+                path: sub.to_string_lossy().to_string(),
+            });
+
+        let verifier = Verifier::new();
+        let report = verifier.verify(&claim);
+        assert_eq!(report.overall_verdict, Verdict::Refuted);
+        assert!(report
+            .evidence_results
+            .iter()
+            .all(|r| r.verdict == Verdict::Refuted));
+
+        fs::remove_file(&file).unwrap();
+        fs::remove_dir(&sub).unwrap();
+        assert_eq!(verifier.verify(&claim).overall_verdict, Verdict::Confirmed);
+    }
+
+    #[test]
+    fn env_var_absent() {
+        let claim = Claim::new("Unset").with_evidence(EvidenceSpec::EnvVarAbsent {
+            name: "DYADT_SURELY_NOT_SET_ANYWHERE".to_string(),
+        });
+        assert_eq!(
+            Verifier::new().verify(&claim).overall_verdict,
+            Verdict::Confirmed
+        );
+
+        let claim = Claim::new("Set").with_evidence(EvidenceSpec::EnvVarAbsent {
+            name: "PATH".to_string(),
+        });
+        assert_eq!(
+            Verifier::new().verify(&claim).overall_verdict,
+            Verdict::Refuted
+        );
+    }
+
+    #[test]
+    fn git_branch_absent_outside_repo_is_unverifiable() {
+        let dir = tempdir().unwrap();
+        let claim = Claim::new("Branch gone").with_evidence(EvidenceSpec::GitBranchAbsent {
+            branch: "topic".to_string(),
+            repo_path: Some(dir.path().to_string_lossy().to_string()),
+        });
+        assert_eq!(
+            Verifier::new().verify(&claim).overall_verdict,
+            Verdict::Unverifiable
+        );
+    }
+
+    fn git(dir: &std::path::Path, args: &[&str]) -> String {
+        let output = std::process::Command::new("git")
+            .args(["-c", "user.name=t", "-c", "user.email=t@example.com", "-C"])
+            .arg(dir)
+            .args(args)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "git {:?} failed", args);
+        String::from_utf8_lossy(&output.stdout).trim().to_string()
+    }
+
+    #[test]
+    fn commit_evidence_inspects_the_diff() {
+        let dir = tempdir().unwrap();
+        let repo = Some(dir.path().to_string_lossy().to_string());
+        git(dir.path(), &["init", "-q"]);
+        fs::write(dir.path().join("a.rs"), "fn old() {}\n").unwrap();
+        git(dir.path(), &["add", "."]);
+        git(dir.path(), &["commit", "-qm", "Initial import"]);
+        let base = git(dir.path(), &["rev-parse", "HEAD"]);
+        fs::write(dir.path().join("a.rs"), "fn new() {}\n").unwrap();
+        fs::write(dir.path().join("b.rs"), "").unwrap();
+        git(dir.path(), &["add", "."]);
+        git(
+            dir.path(),
+            &["commit", "-qm", "Rename old to new\n\nFixes #12"],
+        );
+
+        let verifier = Verifier::new();
+        let check = |spec: EvidenceSpec| verifier.check_evidence(&spec);
+
+        let changed = check(EvidenceSpec::FileChangedInCommit {
+            commit: "HEAD".to_string(),
+            path: "a.rs".to_string(),
+            repo_path: repo.clone(),
+        });
+        assert_eq!(changed.verdict, Verdict::Confirmed);
+        assert!(changed.details.unwrap().contains("a.rs +1 -1"));
+
+        let touches = check(EvidenceSpec::CommitTouchesPaths {
+            commit: format!("{}..HEAD", base),
+            paths: vec!["a.rs".to_string(), "c.rs".to_string()],
+            repo_path: repo.clone(),
+        });
+        assert_eq!(touches.verdict, Verdict::Refuted);
+        assert!(touches.details.unwrap().contains("does not touch c.rs"));
+
+        let added = check(EvidenceSpec::CommitDiffMatches {
+            commit: "HEAD".to_string(),
+            pattern: r"fn new\(".to_string(),
+            side: DiffSide::Added,
+            path: None,
+            repo_path: repo.clone(),
+        });
+        assert_eq!(added.verdict, Verdict::Confirmed);
+        assert_eq!(
+            check(EvidenceSpec::CommitDiffMatches {
+                commit: "HEAD".to_string(),
+                pattern: r"fn new\(".to_string(),
+                side: DiffSide::Removed,
+                path: None,
+                repo_path: repo.clone(),
+            })
+            .verdict,
+            Verdict::Refuted
+        );
+
+        let message = check(EvidenceSpec::CommitMessageMatches {
+            commit: "HEAD".to_string(),
+            pattern: "Fixes #12".to_string(),
+            repo_path: repo.clone(),
+        });
+        assert_eq!(message.verdict, Verdict::Confirmed);
+
+        let ancestor = check(EvidenceSpec::CommitIsAncestor {
+            commit: base,
+            repo_path: repo.clone(),
+        });
+        assert_eq!(ancestor.verdict, Verdict::Confirmed);
+        assert!(ancestor.details.unwrap().contains("2 files changed"));
+
+        let bogus = check(EvidenceSpec::FileChangedInCommit {
+            commit: "--output=x".to_string(),
+            path: "a.rs".to_string(),
+            repo_path: repo,
+        });
+        assert_eq!(bogus.verdict, Verdict::Unverifiable);
+    }
+
+    #[test]
+    fn file_json_path_supports_queries_and_operators() {
+        use did_you_actually_do_that::query::JsonOp;
+
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("Cargo.json");
+        fs::write(
+            &file,
+            r#"{"package": {"version": "0.3.1"}, "deps": [{"name": "serde", "v": 3}]}"#,
+        )
+        .unwrap();
+        let path = file.to_string_lossy().to_string();
+        let spec = |json_path: &str, op, expected| EvidenceSpec::FileJsonPath {
+            path: path.clone(),
+            json_path: json_path.to_string(),
+            expected,
+            op,
+        };
+        let verifier = Verifier::new();
+
+        let cases = [
+            (
+                spec(
+                    ".package.version",
+                    JsonOp::Equals,
+                    serde_json::json!("0.3.1"),
+                ),
+                Verdict::Confirmed,
+            ),
+            (
+                spec(
+                    "$.deps[?@.name == 'serde'].v",
+                    JsonOp::Gt,
+                    serde_json::json!(2),
+                ),
+                Verdict::Confirmed,
+            ),
+            (
+                spec(
+                    "$.deps[-1].name",
+                    JsonOp::Matches,
+                    serde_json::json!("^ser"),
+                ),
+                Verdict::Confirmed,
+            ),
+            (
+                spec(
+                    "$.deps[?@.name == 'tokio']",
+                    JsonOp::Exists,
+                    serde_json::Value::Null,
+                ),
+                Verdict::Refuted,
+            ),
+            (
+                spec("$.deps", JsonOp::TypeIs, serde_json::json!("object")),
+                Verdict::Refuted,
+            ),
+            (
+                spec("$.deps[", JsonOp::Exists, serde_json::Value::Null),
+                Verdict::Unverifiable,
+            ),
+        ];
+        for (evidence, expected) in cases {
+            assert_eq!(
+                verifier.check_evidence(&evidence).verdict,
+                expected,
+                "{:?}",
+                evidence
+            );
+        }
+    }
+
+    #[test]
+    fn file_structured_path_reads_config_formats() {
+        use did_you_actually_do_that::query::JsonOp;
+        use did_you_actually_do_that::structured::ConfigFormat;
+
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("Cargo.toml"),
+            "[package]\nname = \"demo\"\nedition = \"2021\"\n",
+        )
+        .unwrap();
+        fs::write(dir.path().join("settings"), "PORT=8080\n").unwrap();
+        let spec = |file: &str, query: &str, format, expected| EvidenceSpec::FileStructuredPath {
+            path: dir.path().join(file).to_string_lossy().to_string(),
+            query: query.to_string(),
+            format,
+            expected,
+            op: JsonOp::Equals,
+        };
+        let verifier = Verifier::new();
+
+        let edition = spec(
+            "Cargo.toml",
+            "package.edition",
+            None,
+            serde_json::json!("2021"),
+        );
+        assert_eq!(
+            verifier.check_evidence(&edition).verdict,
+            Verdict::Confirmed
+        );
+
+        let wrong = spec(
+            "Cargo.toml",
+            "$.package.edition",
+            None,
+            serde_json::json!("2018"),
+        );
+        assert_eq!(verifier.check_evidence(&wrong).verdict, Verdict::Refuted);
+
+        let undetected = spec("settings", "PORT", None, serde_json::json!("8080"));
+        assert_eq!(
+            verifier.check_evidence(&undetected).verdict,
+            Verdict::Unverifiable
+        );
+
+        let dotenv = spec(
+            "settings",
+            "PORT",
+            Some(ConfigFormat::Dotenv),
+            serde_json::json!("8080"),
+        );
+        assert_eq!(verifier.check_evidence(&dotenv).verdict, Verdict::Confirmed);
+    }
+
+    #[test]
+    fn symbol_defined_parses_rust_source() {
+        use did_you_actually_do_that::source::SymbolKind;
+
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("config.rs");
+        fs::write(
+            &file,
+            "// TODO: add fn reload\npub struct Config;\nimpl Config {\n    pub fn validate(&self) {}\n}\n",
+        )
+        .unwrap();
+        let spec = |symbol: &str, kind| EvidenceSpec::SymbolDefined {
+            path: file.to_string_lossy().to_string(),
+            symbol: symbol.to_string(),
+            kind,
+            language: None,
+        };
+        let verifier = Verifier::new();
+
+        let result = verifier.check_evidence(&spec("Config::validate", Some(SymbolKind::Method)));
+        assert_eq!(result.verdict, Verdict::Confirmed);
+        assert!(result.details.unwrap().ends_with("config.rs:4"));
+
+        // Mentioned only in a comment
+        let reload = spec("reload", None);
+        assert_eq!(verifier.check_evidence(&reload).verdict, Verdict::Refuted);
+
+        let wrong_kind = spec("Config", Some(SymbolKind::Enum));
+        let result = verifier.check_evidence(&wrong_kind);
+        assert_eq!(result.verdict, Verdict::Refuted);
+        assert_eq!(result.details.unwrap(), "Config is a struct, expected enum");
+
+        let unknown = EvidenceSpec::SymbolDefined {
+            path: dir.path().join("main.zig").to_string_lossy().to_string(),
+            symbol: "main".to_string(),
+            kind: None,
+            language: None,
+        };
+        assert_eq!(
+            verifier.check_evidence(&unknown).verdict,
+            Verdict::Unverifiable
+        );
+    }
+
+    #[test]
+    fn test_passes_requires_the_named_test_to_run() {
+        let dir = tempdir().unwrap();
+        fs::create_dir(dir.path().join("src")).unwrap();
+        fs::write(
+            dir.path().join("Cargo.toml"),
+            "[package]\nname = \"demo\"\nversion = \"0.1.0\"\nedition = \"2021\"\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.path().join("src/lib.rs"),
+            "#[cfg(test)]\nmod tests {\n    #[test]\n    fn adds() { assert_eq!(1 + 1, 2); }\n    \
+             #[test]\n    fn breaks() { panic!(); }\n    #[test]\n    #[ignore]\n    fn slow() {}\n}\n",
+        )
+        .unwrap();
+        let spec = |name: &str| EvidenceSpec::TestPasses {
+            name: name.to_string(),
+            runner: Default::default(),
+            args: Vec::new(),
+            cwd: Some(dir.path().to_string_lossy().to_string()),
+            timeout_secs: Some(300),
+        };
+        let verifier = Verifier::new();
+
+        let result = verifier.check_evidence(&spec("tests::adds"));
+        assert_eq!(result.verdict, Verdict::Confirmed, "{:?}", result.details);
+        assert_eq!(result.artifacts["tests"][0]["name"], "tests::adds");
+
+        assert_eq!(
+            verifier.check_evidence(&spec("breaks")).verdict,
+            Verdict::Refuted
+        );
+        assert_eq!(
+            verifier.check_evidence(&spec("slow")).verdict,
+            Verdict::Refuted
+        );
+
+        // cargo exits 0 when the filter matches nothing
+        let result = verifier.check_evidence(&spec("renamed_away"));
+        assert_eq!(result.verdict, Verdict::Refuted);
+        assert_eq!(
+            result.details.as_deref(),
+            Some("No test matching 'renamed_away' ran")
+        );
+    }
+
+    #[test]
+    fn line_level_evidence_shows_a_diff() {
+        let dir = tempdir().unwrap();
+        let file = dir.path().join("main.rs");
+        fs::write(
+            &file,
+            "fn main() {\n    let cfg = load()?;\n    run(cfg);\n}\n",
+        )
+        .unwrap();
+        let path = file.to_string_lossy().to_string();
+        let verifier = Verifier::new();
+
+        let range = |expected: &str| EvidenceSpec::FileLineRange {
+            path: path.clone(),
+            start: 2,
+            end: None,
+            expected: expected.to_string(),
+        };
+        assert_eq!(
+            verifier
+                .check_evidence(&range("    let cfg = load()?;"))
+                .verdict,
+            Verdict::Confirmed
+        );
+        let result = verifier.check_evidence(&range("    let cfg = load().unwrap();"));
+        assert_eq!(result.verdict, Verdict::Refuted);
+        assert!(result
+            .details
+            .unwrap()
+            .contains("-    let cfg = load().unwrap();\n+    let cfg = load()?;"));
+
+        let applied = EvidenceSpec::FileUnifiedDiffApplied {
+            path: path.clone(),
+            diff: "@@ -1,3 +1,3 @@\n fn main() {\n-    let cfg = load().unwrap();\n+    let cfg = load()?;\n     run(cfg);\n"
+                .to_string(),
+        };
+        assert_eq!(
+            verifier.check_evidence(&applied).verdict,
+            Verdict::Confirmed
+        );
+
+        let reverted = EvidenceSpec::FileUnifiedDiffApplied {
+            path,
+            diff:
+                "@@ -2 +2 @@\n-    let cfg = load()?;\n+    let cfg = load().expect(\"config\");\n"
+                    .to_string(),
+        };
+        assert_eq!(verifier.check_evidence(&reverted).verdict, Verdict::Refuted);
+    }
+
     #[test]
     fn multiple_evidence_all_must_pass() {
         let dir = tempdir().unwrap();
@@ -263,6 +680,12 @@ mod deterministic_tests {
         let claim = Claim::new("True succeeds").with_evidence(EvidenceSpec::CommandSucceeds {
             command: "true".to_string(),
             args: vec![],
+            cwd: None,
+            env: Default::default(),
+            timeout_secs: None,
+            expected_exit_code: 0,
+            stdout_contains: None,
+            stdout_matches: None,
         });
 
         let verifier = Verifier::new();
@@ -276,6 +699,12 @@ mod deterministic_tests {
         let claim = Claim::new("False fails").with_evidence(EvidenceSpec::CommandSucceeds {
             command: "false".to_string(),
             args: vec![],
+            cwd: None,
+            env: Default::default(),
+            timeout_secs: None,
+            expected_exit_code: 0,
+            stdout_contains: None,
+            stdout_matches: None,
         });
 
         let verifier = Verifier::new();
@@ -284,16 +713,166 @@ mod deterministic_tests {
         assert_eq!(report.overall_verdict, Verdict::Refuted);
     }
 
+    #[cfg(unix)]
+    fn sh(script: &str) -> EvidenceSpec {
+        EvidenceSpec::CommandSucceeds {
+            command: "sh".to_string(),
+            args: vec!["-c".to_string(), script.to_string()],
+            cwd: None,
+            env: Default::default(),
+            timeout_secs: None,
+            expected_exit_code: 0,
+            stdout_contains: None,
+            stdout_matches: None,
+        }
+    }
+
+    #[cfg(unix)]
     #[test]
-    fn custom_checker_works() {
-        use std::collections::HashMap;
+    fn command_timeout_is_inconclusive() {
+        let mut evidence = sh("sleep 5");
+        if let EvidenceSpec::CommandSucceeds { timeout_secs, .. } = &mut evidence {
+            *timeout_secs = Some(1);
+        }
 
+        let started = std::time::Instant::now();
+        let result = Verifier::new().check_evidence(&evidence);
+
+        assert_eq!(result.verdict, Verdict::Inconclusive);
+        assert!(result
+            .details
+            .unwrap()
+            .contains("timed out after 1 seconds"));
+        assert!(started.elapsed() < std::time::Duration::from_secs(4));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn command_checks_exit_code_and_output() {
+        let mut evidence = sh("echo \"$GREETING from $(pwd)\"; echo oops >&2; exit 2");
+        if let EvidenceSpec::CommandSucceeds {
+            cwd,
+            env,
+            expected_exit_code,
+            stdout_contains,
+            stdout_matches,
+            ..
+        } = &mut evidence
+        {
+            *cwd = Some("/".to_string());
+            env.insert("GREETING".to_string(), "hello".to_string());
+            *expected_exit_code = 2;
+            *stdout_contains = Some("hello".to_string());
+            *stdout_matches = Some(r"^hello from /".to_string());
+        }
+
+        let verifier = Verifier::new();
+        let result = verifier.check_evidence(&evidence);
+        assert_eq!(result.verdict, Verdict::Confirmed, "{:?}", result.details);
+        assert_eq!(result.artifacts["stdout"], "hello from /\n");
+        assert_eq!(result.artifacts["stderr"], "oops\n");
+
+        if let EvidenceSpec::CommandSucceeds {
+            stdout_contains, ..
+        } = &mut evidence
+        {
+            *stdout_contains = Some("goodbye".to_string());
+        }
+        let result = verifier.check_evidence(&evidence);
+        assert_eq!(result.verdict, Verdict::Refuted);
+        assert!(result.details.unwrap().contains("goodbye"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn command_reports_unexpected_exit_code() {
+        let result = Verifier::new().check_evidence(&sh("echo failing test; exit 101"));
+
+        assert_eq!(result.verdict, Verdict::Refuted);
+        assert_eq!(
+            result.details.as_deref(),
+            Some("Command exited with code 101 (expected 0)")
+        );
+        assert_eq!(result.artifacts["stdout"], "failing test\n");
+    }
+
+    #[test]
+    fn execution_policy_blocks_commands_as_unverifiable() {
+        use did_you_actually_do_that::policy::ExecutionPolicy;
+
+        let claim = Claim::new("Ran a program")
+            .with_evidence(EvidenceSpec::CommandSucceeds {
+                command: "true".to_string(),
+                args: vec![],
+                cwd: None,
+                env: Default::default(),
+                timeout_secs: None,
+                expected_exit_code: 0,
+                stdout_contains: None,
+                stdout_matches: None,
+            })
+            // Diff-aware git evidence always runs the git binary
+            .with_evidence(EvidenceSpec::CommitIsAncestor {
+                commit: "HEAD".to_string(),
+                repo_path: None,
+            });
+        // As does all git evidence with the default CLI backend
+        #[cfg(not(feature = "libgit2"))]
+        let claim = claim.with_evidence(EvidenceSpec::GitClean { repo_path: None });
+
+        let verifier = Verifier::new().with_policy(ExecutionPolicy::deny_all());
+        let report = verifier.verify(&claim);
+
+        assert_eq!(report.overall_verdict, Verdict::Unverifiable);
+        for result in &report.evidence_results {
+            assert_eq!(result.verdict, Verdict::Unverifiable);
+            assert!(result
+                .details
+                .as_deref()
+                .unwrap()
+                .starts_with("Execution blocked by policy"));
+        }
+
+        let verifier = Verifier::new().with_policy(ExecutionPolicy::allowlist(["git"]));
+        let report = verifier.verify(&claim);
+        assert_eq!(report.evidence_results[0].verdict, Verdict::Unverifiable);
+        for result in &report.evidence_results[1..] {
+            assert_ne!(result.verdict, Verdict::Unverifiable);
+        }
+    }
+
+    #[test]
+    fn command_spec_defaults_optional_fields() {
+        let spec: EvidenceSpec = serde_json::from_str(
+            r#"{"type": "CommandSucceeds", "spec": {"command": "true", "args": []}}"#,
+        )
+        .unwrap();
+
+        match spec {
+            EvidenceSpec::CommandSucceeds {
+                cwd,
+                env,
+                timeout_secs,
+                expected_exit_code,
+                ..
+            } => {
+                assert!(cwd.is_none());
+                assert!(env.is_empty());
+                assert!(timeout_secs.is_none());
+                assert_eq!(expected_exit_code, 0);
+            }
+            other => panic!("unexpected spec: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn custom_checker_works() {
         let mut verifier = Verifier::new();
         verifier.register_checker("always_pass", |_params| Ok(Verdict::Confirmed));
 
         let claim = Claim::new("Custom check").with_evidence(EvidenceSpec::Custom {
             name: "always_pass".to_string(),
-            params: HashMap::new(),
+            params: serde_json::json!({}),
         });
 
         let report = verifier.verify(&claim);
@@ -302,18 +881,173 @@ mod deterministic_tests {
     }
 
     #[test]
-    fn unknown_custom_checker_is_unverifiable() {
-        use std::collections::HashMap;
+    fn closure_checker_receives_stringified_params() {
+        let mut verifier = Verifier::new();
+        verifier.register_checker("is_even", |params| {
+            let number: i64 = params.get("number").unwrap().parse().unwrap();
+            Ok(if number % 2 == 0 {
+                Verdict::Confirmed
+            } else {
+                Verdict::Refuted
+            })
+        });
+
+        let claim = Claim::new("Even").with_evidence(EvidenceSpec::Custom {
+            name: "is_even".to_string(),
+            params: serde_json::json!({ "number": 7 }),
+        });
+
+        assert_eq!(verifier.verify(&claim).overall_verdict, Verdict::Refuted);
+    }
+
+    #[test]
+    fn trait_checker_reports_details_and_artifacts() {
+        use did_you_actually_do_that::checker::{CheckOutcome, EvidenceChecker};
+        use did_you_actually_do_that::VerificationError;
 
+        struct Echo;
+
+        impl EvidenceChecker for Echo {
+            fn name(&self) -> &str {
+                "echo"
+            }
+
+            fn check(
+                &self,
+                params: &serde_json::Value,
+                _verifier: &Verifier,
+            ) -> Result<CheckOutcome, VerificationError> {
+                Ok(CheckOutcome::new(Verdict::Confirmed)
+                    .with_details("echoed")
+                    .with_artifact("params", params.clone()))
+            }
+        }
+
+        let mut verifier = Verifier::new();
+        verifier.register(Echo);
+
+        let params = serde_json::json!({ "nested": { "value": [1, 2] } });
+        let result = verifier.check_evidence(&EvidenceSpec::Custom {
+            name: "echo".to_string(),
+            params: params.clone(),
+        });
+
+        assert_eq!(result.verdict, Verdict::Confirmed);
+        assert_eq!(result.details.as_deref(), Some("echoed"));
+        assert_eq!(result.artifacts["params"], params);
+    }
+
+    #[test]
+    fn unknown_custom_checker_is_unverifiable() {
         let verifier = Verifier::new();
 
         let claim = Claim::new("Unknown checker").with_evidence(EvidenceSpec::Custom {
             name: "nonexistent".to_string(),
-            params: HashMap::new(),
+            params: serde_json::json!({}),
         });
 
         let report = verifier.verify(&claim);
 
         assert_eq!(report.overall_verdict, Verdict::Unverifiable);
     }
+
+    #[test]
+    fn any_of_confirmed_when_one_alternative_exists() {
+        let dir = tempdir().unwrap();
+        let yaml = dir.path().join("config.yaml");
+        fs::write(&yaml, "key: value").unwrap();
+
+        let claim = Claim::new("Config exists").with_evidence(EvidenceSpec::AnyOf {
+            evidence: vec![
+                EvidenceSpec::FileExists {
+                    path: dir.path().join("config.toml").to_string_lossy().to_string(),
+                },
+                EvidenceSpec::FileExists {
+                    path: yaml.to_string_lossy().to_string(),
+                },
+            ],
+        });
+
+        let verifier = Verifier::new();
+        let report = verifier.verify(&claim);
+
+        assert_eq!(report.overall_verdict, Verdict::Confirmed);
+        let children = &report.evidence_results[0].children;
+        assert_eq!(children.len(), 2);
+        assert_eq!(children[0].verdict, Verdict::Refuted);
+        assert_eq!(children[1].verdict, Verdict::Confirmed);
+    }
+
+    #[test]
+    fn not_inverts_nested_verdict() {
+        let dir = tempdir().unwrap();
+        let lock = dir.path().join("app.lock");
+
+        let claim = Claim::new("Lock released").with_evidence(EvidenceSpec::Not {
+            evidence: Box::new(EvidenceSpec::FileExists {
+                path: lock.to_string_lossy().to_string(),
+            }),
+        });
+
+        let verifier = Verifier::new();
+        assert_eq!(verifier.verify(&claim).overall_verdict, Verdict::Confirmed);
+
+        fs::write(&lock, "").unwrap();
+        assert_eq!(verifier.verify(&claim).overall_verdict, Verdict::Refuted);
+    }
+
+    #[test]
+    fn at_least_counts_confirmed_children() {
+        let dir = tempdir().unwrap();
+        let present = dir.path().to_string_lossy().to_string();
+        let missing = dir.path().join("missing").to_string_lossy().to_string();
+
+        let evidence = vec![
+            EvidenceSpec::DirectoryExists {
+                path: present.clone(),
+            },
+            EvidenceSpec::DirectoryExists {
+                path: missing.clone(),
+            },
+            EvidenceSpec::DirectoryExists { path: present },
+        ];
+
+        let verifier = Verifier::new();
+        let two = Claim::new("Two of three").with_evidence(EvidenceSpec::AtLeast {
+            n: 2,
+            evidence: evidence.clone(),
+        });
+        let three =
+            Claim::new("Three of three").with_evidence(EvidenceSpec::AtLeast { n: 3, evidence });
+
+        assert_eq!(verifier.verify(&two).overall_verdict, Verdict::Confirmed);
+        assert_eq!(verifier.verify(&three).overall_verdict, Verdict::Refuted);
+    }
+
+    #[test]
+    fn composite_evidence_parses_from_json() {
+        let json = r#"{
+            "type": "AllOf",
+            "spec": {
+                "evidence": [
+                    { "type": "AnyOf", "spec": { "evidence": [
+                        { "type": "FileExists", "spec": { "path": "/nonexistent/a" } },
+                        { "type": "FileExists", "spec": { "path": "/nonexistent/b" } }
+                    ] } },
+                    { "type": "Not", "spec": { "evidence":
+                        { "type": "FileExists", "spec": { "path": "/nonexistent/lock" } }
+                    } }
+                ]
+            }
+        }"#;
+
+        let spec: EvidenceSpec = serde_json::from_str(json).unwrap();
+        let result = Verifier::new().check_evidence(&spec);
+
+        assert_eq!(result.verdict, Verdict::Refuted);
+        assert_eq!(result.children.len(), 2);
+        assert_eq!(result.children[0].verdict, Verdict::Refuted);
+        assert_eq!(result.children[0].children.len(), 2);
+        assert_eq!(result.children[1].verdict, Verdict::Confirmed);
+    }
 }